@@ -1,5 +1,6 @@
 import SwiftUI
 import AppKit
+import Network
 
 // MARK: - Modern UI Constants
 private enum UI {
@@ -41,6 +42,7 @@ struct ContentView: View {
     @State private var showMediaAlert = false
     @State private var statusTimer: Timer?
     @State private var logCleanupTimer: Timer?
+    @State private var pathMonitor: NWPathMonitor?
     
     // External
     private let appDelegate = NSApp.delegate as? AppDelegate
@@ -371,7 +373,7 @@ struct ContentView: View {
         // 防止重复初始化
         guard statusTimer == nil else { return }
         
-        loadConfig(); checkAccessibilityPermission(); startStatusUpdates(); startLogCleanup()
+        loadConfig(); checkAccessibilityPermission(); startStatusUpdates(); startLogCleanup(); startNetworkMonitor()
         if RustBridge.isRunning() { setupCallbacks() }
         if config.enableMediaReporting { DispatchQueue.main.asyncAfter(deadline: .now() + 1) { checkMediaPermission() } }
     }
@@ -427,10 +429,21 @@ struct ContentView: View {
     }
     private func stopStatusUpdates() { statusTimer?.invalidate(); logCleanupTimer?.invalidate() }
 
+    /// 网络恢复（切换 Wi-Fi、接入 VPN 等）时跳过剩余的重连等待
+    private func startNetworkMonitor() {
+        let monitor = NWPathMonitor()
+        monitor.pathUpdateHandler = { path in
+            if path.status == .satisfied { RustBridge.notifyNetworkChanged() }
+        }
+        monitor.start(queue: DispatchQueue.global(qos: .utility))
+        pathMonitor = monitor
+    }
+
     private func cleanup() {
         print("🧹 ContentView: Starting cleanup...")
         // Stop timers
         stopStatusUpdates()
+        pathMonitor?.cancel(); pathMonitor = nil
         // Clear FFI callbacks to prevent memory leaks
         RustBridge.clearCallbacks()
         // Clear image cache
@@ -79,6 +79,18 @@ func sm_reporter_get_status(_ handle: UnsafeRawPointer) -> SmStatus
 @_silgen_name("sm_reporter_is_running")
 func sm_reporter_is_running() -> Bool
 
+@_silgen_name("sm_reporter_pause")
+func sm_reporter_pause(_ durationSecs: UInt64) -> Bool
+
+@_silgen_name("sm_reporter_resume")
+func sm_reporter_resume() -> Bool
+
+@_silgen_name("sm_reporter_get_paused_until")
+func sm_reporter_get_paused_until() -> Int64
+
+@_silgen_name("sm_reporter_network_changed")
+func sm_reporter_network_changed() -> Bool
+
 @_silgen_name("sm_reporter_set_log_callback")
 func sm_reporter_set_log_callback(_ callback: @convention(c) (UInt8, UnsafePointer<CChar>, UInt) -> Void, _ userData: UInt)
 
@@ -115,6 +127,10 @@ struct SmConfig {
     var wsUrl: UnsafeMutablePointer<CChar>
     var token: UnsafeMutablePointer<CChar>
     var enableMediaReporting: Bool
+    /// 0 = off, 1 = app only, 2 = app + title
+    var windowPrivacy: UInt8
+    /// 0 = off, 1 = track only, 2 = track + artwork
+    var mediaPrivacy: UInt8
 }
 
 /// C-compatible struct for Status
@@ -122,6 +138,18 @@ struct SmStatus {
     var isRunning: Bool
     var isConnected: Bool
     var lastError: UnsafeMutablePointer<CChar>
+    /// Last heartbeat round-trip time in milliseconds, -1 if unknown
+    var latencyMs: Int32
+    /// Smoothed heartbeat round-trip time in milliseconds, -1 if unknown
+    var smoothedLatencyMs: Int32
+    /// Failed connection attempts since the last successful connection
+    var reconnectAttempt: UInt32
+    /// Unix time (ms) of the next connection attempt, -1 if none is scheduled
+    var nextRetryMs: Int64
+    /// Messages waiting to be sent
+    var pendingMessages: UInt32
+    /// Messages dropped because the outbound queue limits were exceeded
+    var droppedMessages: UInt64
 }
 
 // MARK: - Swift Models
@@ -132,6 +160,8 @@ struct ReporterConfig: Equatable {
     var wsUrl: String
     var token: String
     var enableMediaReporting: Bool
+    var windowPrivacy: UInt8 = 2
+    var mediaPrivacy: UInt8 = 2
 }
 
 /// Swift model for Reporter Status
@@ -139,6 +169,12 @@ struct ReporterStatus {
     var isRunning: Bool
     var isConnected: Bool
     var lastError: String?
+    var latencyMs: Int? = nil
+    var smoothedLatencyMs: Int? = nil
+    var reconnectAttempt: Int = 0
+    var nextRetry: Date? = nil
+    var pendingMessages: Int = 0
+    var droppedMessages: UInt64 = 0
 }
 
 /// Window data from backend
@@ -243,7 +279,9 @@ class RustBridge {
             enabled: config.enabled,
             wsUrl: wsUrl,
             token: token,
-            enableMediaReporting: config.enableMediaReporting
+            enableMediaReporting: config.enableMediaReporting,
+            windowPrivacy: config.windowPrivacy,
+            mediaPrivacy: config.mediaPrivacy
         )
     }
 
@@ -262,7 +300,9 @@ class RustBridge {
             enabled: config.enabled,
             wsUrl: wsUrlPtr,
             token: tokenPtr,
-            enableMediaReporting: config.enableMediaReporting
+            enableMediaReporting: config.enableMediaReporting,
+            windowPrivacy: config.windowPrivacy,
+            mediaPrivacy: config.mediaPrivacy
         )
 
         return withUnsafePointer(to: &smConfig) { ptr in
@@ -285,7 +325,9 @@ class RustBridge {
             enabled: config.enabled,
             wsUrl: wsUrlPtr,
             token: tokenPtr,
-            enableMediaReporting: config.enableMediaReporting
+            enableMediaReporting: config.enableMediaReporting,
+            windowPrivacy: config.windowPrivacy,
+            mediaPrivacy: config.mediaPrivacy
         )
 
         // Set environment variable for media reporting
@@ -323,7 +365,13 @@ class RustBridge {
         return ReporterStatus(
             isRunning: status.isRunning,
             isConnected: status.isConnected,
-            lastError: lastError
+            lastError: lastError,
+            latencyMs: status.latencyMs >= 0 ? Int(status.latencyMs) : nil,
+            smoothedLatencyMs: status.smoothedLatencyMs >= 0 ? Int(status.smoothedLatencyMs) : nil,
+            reconnectAttempt: Int(status.reconnectAttempt),
+            nextRetry: status.nextRetryMs >= 0 ? Date(timeIntervalSince1970: TimeInterval(status.nextRetryMs) / 1000) : nil,
+            pendingMessages: Int(status.pendingMessages),
+            droppedMessages: status.droppedMessages
         )
     }
 
@@ -331,6 +379,33 @@ class RustBridge {
     static func isRunning() -> Bool {
         return sm_reporter_is_running()
     }
+
+    /// Pause reporting without disconnecting; nil pauses until `resumeReporter()`
+    static func pauseReporter(for duration: TimeInterval?) -> Bool {
+        let seconds = duration.map { UInt64(max(1, $0)) } ?? 0
+        return sm_reporter_pause(seconds)
+    }
+
+    /// Resume reporting after `pauseReporter(for:)`
+    static func resumeReporter() -> Bool {
+        return sm_reporter_resume()
+    }
+
+    /// Hint that the network changed so a pending reconnect is attempted right away
+    @discardableResult
+    static func notifyNetworkChanged() -> Bool {
+        return sm_reporter_network_changed()
+    }
+
+    /// The active pause: nil when not paused, `.distantFuture` when paused until resumed
+    static func pausedUntil() -> Date? {
+        let pausedUntil = sm_reporter_get_paused_until()
+        switch pausedUntil {
+        case ..<0: return nil
+        case 0: return .distantFuture
+        default: return Date(timeIntervalSince1970: TimeInterval(pausedUntil) / 1000)
+        }
+    }
     
     /// Check if accessibility permission is granted
     static func checkAccessibilityPermission() -> Bool {
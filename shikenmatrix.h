@@ -8,6 +8,16 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * Bumped when the meaning of existing messages changes
+ */
+#define PROTOCOL_VERSION 1
+
+/**
+ * Lower bound for `initial_delay_ms` and `max_delay_ms`, so a zero delay cannot spin
+ */
+#define MIN_DELAY_MS 100
+
 /**
  * Log level for callback
  */
@@ -38,6 +48,14 @@ typedef struct SmConfig {
    * Whether to enable media reporting
    */
   bool enable_media_reporting;
+  /**
+   * Window privacy level: 0 = off, 1 = app only, 2 = app and title
+   */
+  uint8_t window_privacy;
+  /**
+   * Media privacy level: 0 = off, 1 = track only, 2 = track and artwork
+   */
+  uint8_t media_privacy;
 } SmConfig;
 
 /**
@@ -63,6 +81,30 @@ typedef struct SmStatus {
    * Last error message (null-terminated string, owned by Rust, null if no error)
    */
   char *last_error;
+  /**
+   * Round-trip time of the last heartbeat in milliseconds (-1 if unknown)
+   */
+  int32_t latency_ms;
+  /**
+   * Smoothed heartbeat round-trip time in milliseconds (-1 if unknown)
+   */
+  int32_t smoothed_latency_ms;
+  /**
+   * Failed connection attempts since the last successful connection
+   */
+  uint32_t reconnect_attempt;
+  /**
+   * Unix time (ms) of the next connection attempt (-1 if none is scheduled)
+   */
+  int64_t next_retry_ms;
+  /**
+   * Messages waiting to be sent
+   */
+  uint32_t pending_messages;
+  /**
+   * Messages dropped because the outbound queue limits were exceeded
+   */
+  uint64_t dropped_messages;
 } SmStatus;
 
 /**
@@ -196,6 +238,52 @@ struct SmReporter *sm_reporter_start(const struct SmConfig *config);
  */
 bool sm_reporter_stop(struct SmReporter *_handle);
 
+/**
+ * Pause reporting without closing the connection
+ *
+ * The pause is persisted, so it also applies to a reporter started later and survives restarts
+ * until it expires.
+ *
+ * # Arguments
+ * * `duration_secs` - Pause length in seconds, 0 to pause until `sm_reporter_resume`
+ *
+ * # Returns
+ * * `true` - Paused
+ * * `false` - Failed to persist the pause (a running reporter is paused anyway)
+ */
+bool sm_reporter_pause(uint64_t duration_secs);
+
+/**
+ * Resume reporting after `sm_reporter_pause`
+ *
+ * # Returns
+ * * `true` - Resumed (or was not paused)
+ * * `false` - Failed to remove the stored pause
+ */
+bool sm_reporter_resume(void);
+
+/**
+ * Get the active pause
+ *
+ * # Returns
+ * * `-1` - Not paused
+ * * `0` - Paused until `sm_reporter_resume`
+ * * Otherwise the Unix time (ms) the pause ends
+ */
+int64_t sm_reporter_get_paused_until(void);
+
+/**
+ * Hint that the network changed (Wi-Fi switched, VPN connected, ...)
+ *
+ * Skips the remaining reconnect backoff if the reporter is waiting to reconnect;
+ * a live connection is kept.
+ *
+ * # Returns
+ * * `true` - Hint delivered
+ * * `false` - No reporter running
+ */
+bool sm_reporter_network_changed(void);
+
 /**
  * Get the current status of the reporter
  *
@@ -254,6 +342,8 @@ extern bool AXIsProcessTrusted(void);
 
 extern bool AXIsProcessTrustedWithOptions(const __CFDictionary *options);
 
+extern double CGEventSourceSecondsSinceLastEventType(int32_t state_id, uint32_t event_type);
+
 extern void *AXUIElementCreateApplication(int32_t pid);
 
 extern int32_t AXUIElementCopyAttributeValue(void *element, const void *attribute, void **value);
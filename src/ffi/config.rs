@@ -1,6 +1,6 @@
 //! FFI functions for configuration management
 
-use super::types::SmConfig;
+use super::types::{media_privacy_from_ffi, media_privacy_to_ffi, window_privacy_from_ffi, window_privacy_to_ffi, SmConfig};
 use crate::services::{load_config, save_reporter_config, ReporterConfig};
 use std::ffi::{CStr, CString, c_char};
 
@@ -20,6 +20,8 @@ pub extern "C" fn sm_config_load() -> *mut SmConfig {
         ws_url,
         token,
         enable_media_reporting: reporter_cfg.enable_media_reporting,
+        window_privacy: window_privacy_to_ffi(reporter_cfg.window_privacy),
+        media_privacy: media_privacy_to_ffi(reporter_cfg.media_privacy),
     }))
 }
 
@@ -38,24 +40,30 @@ pub extern "C" fn sm_config_save(config: *const SmConfig) -> bool {
         return false;
     }
 
-    let (enabled, ws_url, token, enable_media_reporting) = unsafe {
+    let (enabled, ws_url, token, enable_media_reporting, window_privacy, media_privacy) = unsafe {
         let cfg = &*config;
         (
             cfg.enabled,
             CStr::from_ptr(cfg.ws_url).to_string_lossy().to_string(),
             CStr::from_ptr(cfg.token).to_string_lossy().to_string(),
             cfg.enable_media_reporting,
+            window_privacy_from_ffi(cfg.window_privacy),
+            media_privacy_from_ffi(cfg.media_privacy),
         )
     };
 
     tracing::info!("Saving config: enabled={}, ws_url={}, token_len={}", 
         enabled, ws_url, token.len());
 
+    // Settings not exposed through SmConfig keep their value from config.toml
     let reporter_config = ReporterConfig {
         enabled,
         ws_url,
         token,
         enable_media_reporting,
+        window_privacy,
+        media_privacy,
+        ..load_config().reporter
     };
 
     match save_reporter_config(&reporter_config) {
@@ -1,7 +1,7 @@
 //! FFI functions for reporter lifecycle management
 
-use super::types::{SmConfig, SmReporter, SmStatus, SmLogCallback, SmWindowDataCallback, SmMediaDataCallback};
-use crate::services::Reporter;
+use super::types::{media_privacy_from_ffi, window_privacy_from_ffi, SmConfig, SmReporter, SmStatus, SmLogCallback, SmWindowDataCallback, SmMediaDataCallback};
+use crate::services::{Pause, PrivacyFilter, Reporter, Schedule, TitleRewriter};
 use std::ffi::CStr;
 use std::sync::{Arc, Mutex, OnceLock};
 use tracing::{info, error};
@@ -29,6 +29,7 @@ fn init_logging() {
 
         tracing_subscriber::fmt()
             .with_env_filter(env_filter)
+            .with_writer(crate::services::redact::stdout)
             .init();
     });
 }
@@ -71,13 +72,15 @@ pub extern "C" fn sm_reporter_start(config: *const SmConfig) -> *mut SmReporter
         }
     }
 
-    let (enabled, ws_url, token, enable_media_reporting) = unsafe {
+    let (enabled, ws_url, token, enable_media_reporting, window_privacy, media_privacy) = unsafe {
         let cfg = &*config;
         (
             cfg.enabled,
             CStr::from_ptr(cfg.ws_url).to_string_lossy().to_string(),
             CStr::from_ptr(cfg.token).to_string_lossy().to_string(),
             cfg.enable_media_reporting,
+            window_privacy_from_ffi(cfg.window_privacy),
+            media_privacy_from_ffi(cfg.media_privacy),
         )
     };
 
@@ -90,11 +93,41 @@ pub extern "C" fn sm_reporter_start(config: *const SmConfig) -> *mut SmReporter
         info!(">>> Media reporting DISABLED");
     }
 
+    // Settings not exposed through SmConfig are read from config.toml
+    let app_config = crate::services::load_config();
+
+    // Refuse to start rather than report data the user asked to hide
+    let privacy_filter = match PrivacyFilter::new(&app_config.privacy) {
+        Ok(filter) => filter,
+        Err(e) => {
+            error!("sm_reporter_start: {}", e);
+            return std::ptr::null_mut();
+        }
+    };
+    let title_rewriter = match TitleRewriter::new(&app_config.title_rewrite) {
+        Ok(rewriter) => rewriter,
+        Err(e) => {
+            error!("sm_reporter_start: {}", e);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let schedule = match Schedule::new(&app_config.schedule) {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            error!("sm_reporter_start: {}", e);
+            return std::ptr::null_mut();
+        }
+    };
+
     let reporter_config = crate::services::ReporterConfig {
         enabled,
         ws_url: ws_url.clone(),
         token: token.clone(),
         enable_media_reporting,
+        window_privacy,
+        media_privacy,
+        ..app_config.reporter
     };
 
     info!(">>> Creating reporter with config:");
@@ -102,11 +135,16 @@ pub extern "C" fn sm_reporter_start(config: *const SmConfig) -> *mut SmReporter
     info!(">>>   ws_url: {}", ws_url);
     info!(">>>   token length: {}", token.len());
     info!(">>>   enable_media_reporting: {}", enable_media_reporting);
+    info!(">>>   window_privacy: {:?}", window_privacy);
+    info!(">>>   media_privacy: {:?}", media_privacy);
 
     // Create reporter using the runtime handle
     let rt = get_runtime();
     let handle = rt.handle().clone();
     let reporter = Reporter::new_with_handle(reporter_config, handle);
+    reporter.set_privacy_filter(privacy_filter);
+    reporter.set_title_rewriter(title_rewriter);
+    reporter.set_schedule(schedule);
 
     // Store the reporter globally
     {
@@ -143,6 +181,100 @@ pub extern "C" fn sm_reporter_stop(_handle: *mut SmReporter) -> bool {
     }
 }
 
+/// Pause reporting without closing the connection
+///
+/// The pause is persisted, so it also applies to a reporter started later and survives restarts
+/// until it expires.
+///
+/// # Arguments
+/// * `duration_secs` - Pause length in seconds, 0 to pause until `sm_reporter_resume`
+///
+/// # Returns
+/// * `true` - Paused
+/// * `false` - Failed to persist the pause (a running reporter is paused anyway)
+#[no_mangle]
+pub extern "C" fn sm_reporter_pause(duration_secs: u64) -> bool {
+    let pause = match duration_secs {
+        0 => Pause::indefinite(),
+        secs => Pause::for_duration(std::time::Duration::from_secs(secs)),
+    };
+    let result = match GLOBAL_REPORTER.lock().unwrap().as_ref() {
+        Some(reporter) => reporter.pause(pause),
+        None => crate::services::save_pause(Some(pause)),
+    };
+    match result {
+        Ok(()) => {
+            info!("Reporter paused: {:?}", pause);
+            true
+        }
+        Err(e) => {
+            error!("sm_reporter_pause: {}", e);
+            false
+        }
+    }
+}
+
+/// Resume reporting after `sm_reporter_pause`
+///
+/// # Returns
+/// * `true` - Resumed (or was not paused)
+/// * `false` - Failed to remove the stored pause
+#[no_mangle]
+pub extern "C" fn sm_reporter_resume() -> bool {
+    let result = match GLOBAL_REPORTER.lock().unwrap().as_ref() {
+        Some(reporter) => reporter.resume(),
+        None => crate::services::save_pause(None),
+    };
+    match result {
+        Ok(()) => {
+            info!("Reporter resumed");
+            true
+        }
+        Err(e) => {
+            error!("sm_reporter_resume: {}", e);
+            false
+        }
+    }
+}
+
+/// Get the active pause
+///
+/// # Returns
+/// * `-1` - Not paused
+/// * `0` - Paused until `sm_reporter_resume`
+/// * Otherwise the Unix time (ms) the pause ends
+#[no_mangle]
+pub extern "C" fn sm_reporter_get_paused_until() -> i64 {
+    let pause = match GLOBAL_REPORTER.lock().unwrap().as_ref() {
+        Some(reporter) => reporter.paused(),
+        None => crate::services::load_pause(),
+    };
+    match pause {
+        None => -1,
+        Some(Pause { until: None }) => 0,
+        Some(Pause { until: Some(until) }) => until as i64,
+    }
+}
+
+/// Hint that the network changed (Wi-Fi switched, VPN connected, ...)
+///
+/// Skips the remaining reconnect backoff if the reporter is waiting to reconnect;
+/// a live connection is kept.
+///
+/// # Returns
+/// * `true` - Hint delivered
+/// * `false` - No reporter running
+#[no_mangle]
+pub extern "C" fn sm_reporter_network_changed() -> bool {
+    match GLOBAL_REPORTER.lock().unwrap().as_ref() {
+        Some(reporter) => {
+            reporter.network_changed();
+            true
+        }
+        None => false,
+    }
+}
+
 /// Get the current status of the reporter
 ///
 /// # Arguments
@@ -155,13 +287,25 @@ pub extern "C" fn sm_reporter_get_status(_handle: *const SmReporter) -> SmStatus
     let guard = GLOBAL_REPORTER.lock().unwrap();
 
     let is_running = guard.is_some();
-    // Get actual WebSocket connection status from the reporter
-    let is_connected = guard.as_ref().map(|r| r.is_connected()).unwrap_or(false);
+    // Get actual WebSocket connection status, heartbeat latency and retry state from the reporter
+    let status = guard.as_ref().map(|r| r.status()).unwrap_or_default();
+    let millis = |latency: Option<std::time::Duration>| {
+        latency.map(|d| d.as_millis().min(i32::MAX as u128) as i32).unwrap_or(-1)
+    };
 
     SmStatus {
         is_running,
-        is_connected,
+        is_connected: status.connected,
         last_error: std::ptr::null_mut(),
+        latency_ms: millis(status.latency),
+        smoothed_latency_ms: millis(status.smoothed_latency),
+        reconnect_attempt: status.reconnect_attempt,
+        next_retry_ms: status.next_retry
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(-1),
+        pending_messages: status.outbox.pending.min(u32::MAX as usize) as u32,
+        dropped_messages: status.outbox.dropped_events + status.outbox.dropped_artwork,
     }
 }
 
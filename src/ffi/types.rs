@@ -2,6 +2,8 @@
 
 use std::ffi::c_char;
 
+use crate::services::{MediaPrivacyLevel, WindowPrivacyLevel};
+
 /// Configuration for the reporter
 #[repr(C)]
 pub struct SmConfig {
@@ -13,6 +15,44 @@ pub struct SmConfig {
     pub token: *mut c_char,
     /// Whether to enable media reporting
     pub enable_media_reporting: bool,
+    /// Window privacy level: 0 = off, 1 = app only, 2 = app and title
+    pub window_privacy: u8,
+    /// Media privacy level: 0 = off, 1 = track only, 2 = track and artwork
+    pub media_privacy: u8,
+}
+
+/// Convert `SmConfig::window_privacy`; unknown values fall back to the most private level
+pub(crate) fn window_privacy_from_ffi(value: u8) -> WindowPrivacyLevel {
+    match value {
+        1 => WindowPrivacyLevel::AppOnly,
+        2 => WindowPrivacyLevel::Full,
+        _ => WindowPrivacyLevel::Off,
+    }
+}
+
+pub(crate) fn window_privacy_to_ffi(level: WindowPrivacyLevel) -> u8 {
+    match level {
+        WindowPrivacyLevel::Off => 0,
+        WindowPrivacyLevel::AppOnly => 1,
+        WindowPrivacyLevel::Full => 2,
+    }
+}
+
+/// Convert `SmConfig::media_privacy`; unknown values fall back to the most private level
+pub(crate) fn media_privacy_from_ffi(value: u8) -> MediaPrivacyLevel {
+    match value {
+        1 => MediaPrivacyLevel::TrackOnly,
+        2 => MediaPrivacyLevel::FullWithArtwork,
+        _ => MediaPrivacyLevel::Off,
+    }
+}
+
+pub(crate) fn media_privacy_to_ffi(level: MediaPrivacyLevel) -> u8 {
+    match level {
+        MediaPrivacyLevel::Off => 0,
+        MediaPrivacyLevel::TrackOnly => 1,
+        MediaPrivacyLevel::FullWithArtwork => 2,
+    }
 }
 
 /// Status of the reporter
@@ -24,6 +64,18 @@ pub struct SmStatus {
     pub is_connected: bool,
     /// Last error message (null-terminated string, owned by Rust, null if no error)
     pub last_error: *mut c_char,
+    /// Round-trip time of the last heartbeat in milliseconds (-1 if unknown)
+    pub latency_ms: i32,
+    /// Smoothed heartbeat round-trip time in milliseconds (-1 if unknown)
+    pub smoothed_latency_ms: i32,
+    /// Failed connection attempts since the last successful connection
+    pub reconnect_attempt: u32,
+    /// Unix time (ms) of the next connection attempt (-1 if none is scheduled)
+    pub next_retry_ms: i64,
+    /// Messages waiting to be sent
+    pub pending_messages: u32,
+    /// Messages dropped because the outbound queue limits were exceeded
+    pub dropped_messages: u64,
 }
 
 /// Window information for FFI
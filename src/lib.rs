@@ -14,7 +14,7 @@
 //! ## Usage as a Library
 //!
 //! ```rust
-//! use shikenmatrix::services::{Reporter, ReporterConfig, load_config};
+//! use shikenmatrix_native::services::{Reporter, ReporterConfig, load_config};
 //!
 //! let config = load_config();
 //! if config.reporter.enabled {
@@ -1,8 +1,4 @@
-mod services;
-mod platform;
-
-use services::{Reporter, load_config};
-use std::sync::Arc;
+use shikenmatrix_native::services::{Pause, PrivacyFilter, Reporter, Schedule, TitleRewriter, load_config, save_pause};
 use tokio::signal;
 
 #[tokio::main]
@@ -13,88 +9,57 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"))
         )
+        .with_writer(shikenmatrix_native::services::redact::stdout)
         .init();
 
+    // `pause [duration]` / `resume` only update pause.toml; a running reporter picks it up
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("pause") => {
+            let pause = Pause::parse(args.next().as_deref())?;
+            save_pause(Some(pause))?;
+            match pause.until {
+                Some(until) => {
+                    let until = jiff::Timestamp::from_millisecond(until as i64)?.to_zoned(jiff::tz::TimeZone::system());
+                    println!("Reporting paused until {}", until.strftime("%Y-%m-%d %H:%M %Z"));
+                }
+                None => println!("Reporting paused until resumed"),
+            }
+            return Ok(());
+        }
+        Some("resume") => {
+            save_pause(None)?;
+            println!("Reporting resumed");
+            return Ok(());
+        }
+        Some(other) => {
+            return Err(format!("Unknown command: {} (expected `pause [15m|2h|1d|tomorrow]` or `resume`)", other).into());
+        }
+        None => {}
+    }
+
     // Load configuration
     let app_config = load_config();
     tracing::info!("Loaded config: enabled={}, ws_url={}", app_config.reporter.enabled, app_config.reporter.ws_url);
 
     // Create reporter if enabled
-    let reporter = if app_config.reporter.enabled {
-        Some(Reporter::new(app_config.reporter.clone()))
+    // The reporter monitors the native platform's window and media sources on its own thread
+    let _reporter = if app_config.reporter.enabled {
+        // Refuse to start rather than report data the user asked to hide
+        let privacy_filter = PrivacyFilter::new(&app_config.privacy)?;
+        let title_rewriter = TitleRewriter::new(&app_config.title_rewrite)?;
+        let schedule = Schedule::new(&app_config.schedule)?;
+
+        let reporter = Reporter::new(app_config.reporter.clone());
+        reporter.set_privacy_filter(privacy_filter);
+        reporter.set_title_rewriter(title_rewriter);
+        reporter.set_schedule(schedule);
+        Some(reporter)
     } else {
         tracing::info!("Reporter disabled in config");
         None
     };
 
-    // Spawn background task to report window and media info
-    let reporter_handle = Arc::new(std::sync::Mutex::new(reporter));
-    let reporter_clone = reporter_handle.clone();
-
-    tokio::spawn(async move {
-        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
-        loop {
-            interval.tick().await;
-
-            let reporter_opt = { reporter_clone.lock().unwrap().as_ref().cloned() };
-
-            if let Some(reporter) = reporter_opt {
-                // Report window info
-                #[cfg(target_os = "macos")]
-                if let Ok(info) = platform::macos::get_frontmost_window_info_sync() {
-                    reporter.send_window_info(&info);
-                }
-
-                #[cfg(target_os = "windows")]
-                if let Ok(info) = platform::windows::get_frontmost_window() {
-                    reporter.send_window_info(&info);
-                }
-
-                // Report media playback info (macOS only)
-                // TEMPORARILY DISABLED - causing crashes
-                // Set ENABLE_MEDIA_REPORTING=1 to enable this feature
-                #[cfg(target_os = "macos")]
-                if std::env::var("ENABLE_MEDIA_REPORTING").unwrap_or_default() == "1" {
-                    // Wrap in catch_unwind to prevent panics from crashing the app
-                    let media_result = std::panic::catch_unwind(|| {
-                        match platform::macos::get_media_metadata() {
-                            Ok(Some(metadata)) => {
-                                match platform::macos::get_playback_state() {
-                                    Ok(Some(state)) => {
-                                        reporter.send_media_playback(&metadata, &state);
-                                        
-                                        // Upload artwork if available and not cached
-                                        if let (Some(artwork_data), Some(mime_type), Some(content_id)) = 
-                                            (metadata.artwork_data.as_ref(), metadata.artwork_mime_type.as_ref(), metadata.content_item_identifier.as_ref()) {
-                                            // Artwork data is now binary (Arc<Vec<u8>>), no need to decode
-                                            reporter.upload_artwork(content_id.clone(), artwork_data.to_vec(), mime_type.clone());
-                                        }
-                                    }
-                                    Ok(None) => {
-                                        // No media playing, skip
-                                    }
-                                    Err(e) => {
-                                        tracing::warn!("Failed to get playback state: {}", e);
-                                    }
-                                }
-                            }
-                            Ok(None) => {
-                                // No media metadata available
-                            }
-                            Err(e) => {
-                                tracing::warn!("Failed to get media metadata: {}", e);
-                            }
-                        }
-                    });
-                    
-                    if let Err(e) = media_result {
-                        tracing::error!("Media reporting panicked: {:?}", e);
-                    }
-                }
-            }
-        }
-    });
-
     tracing::info!("ShikenMatrix Reporter started");
     tracing::info!("Press Ctrl+C to exit");
 
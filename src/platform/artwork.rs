@@ -0,0 +1,310 @@
+//! 封面图片格式识别
+//! 通过文件头 (magic bytes) 判断 MIME 类型并读取图片尺寸，不解码像素数据
+
+/// 识别出的图片信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageInfo {
+    /// MIME 类型
+    pub mime_type: &'static str,
+    /// 宽度（像素）
+    pub width: u32,
+    /// 高度（像素）
+    pub height: u32,
+}
+
+/// 识别图片格式与尺寸，支持 PNG / JPEG / GIF / WebP / BMP
+///
+/// 无法识别或数据不完整时返回 `None`
+pub fn sniff_image(data: &[u8]) -> Option<ImageInfo> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return sniff_png(data);
+    }
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return sniff_jpeg(data);
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return sniff_gif(data);
+    }
+    if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return sniff_webp(data);
+    }
+    if data.starts_with(b"BM") {
+        return sniff_bmp(data);
+    }
+    None
+}
+
+fn be_u16(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]) as u32)
+}
+
+fn le_u16(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]) as u32)
+}
+
+fn le_u24(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 3)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]))
+}
+
+fn be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn le_i32(data: &[u8], offset: usize) -> Option<i32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// PNG：第一个块必须是 IHDR，宽高为大端 u32
+fn sniff_png(data: &[u8]) -> Option<ImageInfo> {
+    if data.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    Some(ImageInfo {
+        mime_type: "image/png",
+        width: be_u32(data, 16)?,
+        height: be_u32(data, 20)?,
+    })
+}
+
+/// JPEG：遍历段，读取 SOF 段中的尺寸
+fn sniff_jpeg(data: &[u8]) -> Option<ImageInfo> {
+    let mut offset = 2;
+    loop {
+        // 跳过填充字节
+        while *data.get(offset)? == 0xFF && *data.get(offset + 1)? == 0xFF {
+            offset += 1;
+        }
+        if *data.get(offset)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(offset + 1)?;
+        match marker {
+            // 无长度的独立标记
+            0xD8 | 0x01 | 0xD0..=0xD7 => {
+                offset += 2;
+                continue;
+            }
+            // SOF0..SOF15（排除 DHT / JPG / DAC）
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                return Some(ImageInfo {
+                    mime_type: "image/jpeg",
+                    height: be_u16(data, offset + 5)?,
+                    width: be_u16(data, offset + 7)?,
+                });
+            }
+            // 图像数据开始或结束前仍未找到 SOF
+            0xD9 | 0xDA => return None,
+            _ => {
+                let len = be_u16(data, offset + 2)? as usize;
+                if len < 2 {
+                    return None;
+                }
+                offset += 2 + len;
+            }
+        }
+    }
+}
+
+/// GIF：逻辑屏幕宽高为小端 u16
+fn sniff_gif(data: &[u8]) -> Option<ImageInfo> {
+    Some(ImageInfo {
+        mime_type: "image/gif",
+        width: le_u16(data, 6)?,
+        height: le_u16(data, 8)?,
+    })
+}
+
+/// WebP：按 VP8 / VP8L / VP8X 三种格式分别读取尺寸
+fn sniff_webp(data: &[u8]) -> Option<ImageInfo> {
+    let (width, height) = match data.get(12..16)? {
+        b"VP8 " => {
+            // 关键帧起始码 9D 01 2A 之后是 14 位宽高
+            if data.get(23..26)? != [0x9D, 0x01, 0x2A] {
+                return None;
+            }
+            (le_u16(data, 26)? & 0x3FFF, le_u16(data, 28)? & 0x3FFF)
+        }
+        b"VP8L" => {
+            if *data.get(20)? != 0x2F {
+                return None;
+            }
+            let bits = u32::from_le_bytes(data.get(21..25)?.try_into().ok()?);
+            ((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1)
+        }
+        b"VP8X" => (le_u24(data, 24)? + 1, le_u24(data, 27)? + 1),
+        _ => return None,
+    };
+    Some(ImageInfo { mime_type: "image/webp", width, height })
+}
+
+/// BMP：BITMAPINFOHEADER 中的宽高为小端 i32（高度为负表示自上而下存储）
+fn sniff_bmp(data: &[u8]) -> Option<ImageInfo> {
+    let width = le_i32(data, 18)?;
+    let height = le_i32(data, 22)?;
+    if width <= 0 || height == 0 {
+        return None;
+    }
+    Some(ImageInfo {
+        mime_type: "image/bmp",
+        width: width as u32,
+        height: height.unsigned_abs(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        data.extend(width.to_be_bytes());
+        data.extend(height.to_be_bytes());
+        data
+    }
+
+    /// APP0, a DHT segment and fill bytes before the SOF
+    fn jpeg(sof: u8, width: u16, height: u16) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xD8];
+        data.extend([0xFF, 0xE0, 0x00, 0x10]);
+        data.extend(b"JFIF\0\x01\x01\x00\x00\x01\x00\x01\x00\x00");
+        data.extend([0xFF, 0xC4, 0x00, 0x04, 0x00, 0x00]);
+        data.extend([0xFF, 0xFF, 0xFF, sof, 0x00, 0x11, 0x08]);
+        data.extend(height.to_be_bytes());
+        data.extend(width.to_be_bytes());
+        data
+    }
+
+    fn gif(width: u16, height: u16) -> Vec<u8> {
+        let mut data = b"GIF89a".to_vec();
+        data.extend(width.to_le_bytes());
+        data.extend(height.to_le_bytes());
+        data
+    }
+
+    fn webp(chunk: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = b"RIFF\x00\x00\x00\x00WEBP".to_vec();
+        data.extend(chunk);
+        data.extend((payload.len() as u32).to_le_bytes());
+        data.extend(payload);
+        data
+    }
+
+    fn bmp(width: i32, height: i32) -> Vec<u8> {
+        let mut data = b"BM".to_vec();
+        data.extend([0; 16]);
+        data.extend(width.to_le_bytes());
+        data.extend(height.to_le_bytes());
+        data
+    }
+
+    fn info(mime_type: &'static str, width: u32, height: u32) -> Option<ImageInfo> {
+        Some(ImageInfo { mime_type, width, height })
+    }
+
+    #[test]
+    fn png_dimensions() {
+        assert_eq!(sniff_image(&png(640, 480)), info("image/png", 640, 480));
+        let mut not_ihdr = png(640, 480);
+        not_ihdr[12..16].copy_from_slice(b"IDAT");
+        assert_eq!(sniff_image(&not_ihdr), None);
+    }
+
+    #[test]
+    fn jpeg_dimensions_from_sof() {
+        assert_eq!(sniff_image(&jpeg(0xC0, 1200, 800)), info("image/jpeg", 1200, 800));
+        // Progressive
+        assert_eq!(sniff_image(&jpeg(0xC2, 300, 300)), info("image/jpeg", 300, 300));
+    }
+
+    #[test]
+    fn jpeg_without_sof_before_scan_is_rejected() {
+        let mut data = vec![0xFF, 0xD8, 0xFF, 0xDA, 0x00, 0x02];
+        assert_eq!(sniff_image(&data), None);
+        // A segment length below 2 would loop forever
+        data = vec![0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x01, 0xFF, 0xC0];
+        assert_eq!(sniff_image(&data), None);
+        // Garbage where a marker should be
+        data = vec![0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x02, 0x12, 0x34];
+        assert_eq!(sniff_image(&data), None);
+    }
+
+    #[test]
+    fn gif_dimensions() {
+        assert_eq!(sniff_image(&gif(32, 16)), info("image/gif", 32, 16));
+        let mut gif87 = gif(1, 2);
+        gif87[..6].copy_from_slice(b"GIF87a");
+        assert_eq!(sniff_image(&gif87), info("image/gif", 1, 2));
+    }
+
+    #[test]
+    fn webp_lossy_dimensions() {
+        // Frame tag, start code, then 14-bit sizes whose top two bits are the scale
+        let mut payload = vec![0x00, 0x00, 0x00, 0x9D, 0x01, 0x2A];
+        payload.extend((400u16 | 0xC000).to_le_bytes());
+        payload.extend(300u16.to_le_bytes());
+        assert_eq!(sniff_image(&webp(b"VP8 ", &payload)), info("image/webp", 400, 300));
+
+        payload[3] = 0x00;
+        assert_eq!(sniff_image(&webp(b"VP8 ", &payload)), None);
+    }
+
+    #[test]
+    fn webp_lossless_dimensions() {
+        let bits: u32 = (1023 - 1) | ((768 - 1) << 14);
+        let mut payload = vec![0x2F];
+        payload.extend(bits.to_le_bytes());
+        assert_eq!(sniff_image(&webp(b"VP8L", &payload)), info("image/webp", 1023, 768));
+
+        payload[0] = 0x00;
+        assert_eq!(sniff_image(&webp(b"VP8L", &payload)), None);
+    }
+
+    #[test]
+    fn webp_extended_dimensions() {
+        let mut payload = vec![0x10, 0x00, 0x00, 0x00];
+        payload.extend(&(5000u32 - 1).to_le_bytes()[..3]);
+        payload.extend(&(20u32 - 1).to_le_bytes()[..3]);
+        assert_eq!(sniff_image(&webp(b"VP8X", &payload)), info("image/webp", 5000, 20));
+        assert_eq!(sniff_image(&webp(b"ALPH", &payload)), None);
+    }
+
+    #[test]
+    fn bmp_dimensions() {
+        assert_eq!(sniff_image(&bmp(64, 32)), info("image/bmp", 64, 32));
+        // Top-down bitmaps have a negative height
+        assert_eq!(sniff_image(&bmp(64, -32)), info("image/bmp", 64, 32));
+        assert_eq!(sniff_image(&bmp(0, 32)), None);
+        assert_eq!(sniff_image(&bmp(64, 0)), None);
+    }
+
+    #[test]
+    fn unknown_data_is_rejected() {
+        assert_eq!(sniff_image(b""), None);
+        assert_eq!(sniff_image(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"), None);
+        assert_eq!(sniff_image(b"RIFF\x00\x00\x00\x00WAVEfmt "), None);
+    }
+
+    #[test]
+    fn truncated_input_is_rejected_without_panicking() {
+        let fixtures = [
+            png(640, 480),
+            jpeg(0xC0, 1200, 800),
+            gif(32, 16),
+            webp(b"VP8 ", &[0x00, 0x00, 0x00, 0x9D, 0x01, 0x2A, 0x90, 0x01, 0x2C, 0x01]),
+            webp(b"VP8L", &[0x2F, 0xFF, 0xFF, 0xFF, 0x0F]),
+            webp(b"VP8X", &[0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00]),
+            bmp(64, 32),
+        ];
+        for fixture in &fixtures {
+            assert!(sniff_image(fixture).is_some(), "fixture not recognised: {:?}", fixture);
+            for len in 0..fixture.len() {
+                assert_eq!(sniff_image(&fixture[..len]), None, "{} of {:?}", len, fixture);
+            }
+        }
+    }
+}
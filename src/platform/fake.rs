@@ -0,0 +1,199 @@
+//! 内存中的平台实现
+//! 不依赖任何桌面环境，用于在 CI 或无图形界面的 Linux 上驱动完整的监控流程
+
+use super::{IdleSource, MediaEvents, MediaMetadata, MediaSession, MediaSource, PlatformProvider, PlaybackState, SessionEvent, SessionEvents, SessionSource, WindowEntry, WindowEvents, WindowInfo, WindowSource};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Default)]
+struct FakeState {
+    frontmost_window: Option<WindowInfo>,
+    windows: Vec<WindowEntry>,
+    media_sessions: Vec<MediaSession>,
+    idle_time: Duration,
+    /// 为 true 时支持 `subscribe`，否则调用方走轮询回退路径
+    event_driven: bool,
+    subscribers: Vec<mpsc::Sender<WindowInfo>>,
+    media_subscribers: Vec<mpsc::Sender<Vec<MediaSession>>>,
+    session_subscribers: Vec<mpsc::Sender<SessionEvent>>,
+}
+
+/// 可由调用方随时修改返回值的平台实现
+///
+/// 克隆得到的实例共享同一份状态，因此可以把一份交给 `Reporter`，
+/// 另一份留在测试代码中模拟窗口切换与播放变化。
+#[derive(Clone)]
+pub struct FakePlatform {
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl Default for FakePlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakePlatform {
+    /// 创建一个没有窗口、没有播放的实例
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(FakeState::default())),
+        }
+    }
+
+    /// 设置前台窗口（`None` 表示获取失败），并推送给所有订阅者
+    pub fn set_frontmost_window(&self, window: Option<WindowInfo>) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(info) = &window {
+                state.subscribers.retain(|tx| tx.send(info.clone()).is_ok());
+            }
+            state.frontmost_window = window;
+        }
+    }
+
+    /// 设置是否支持事件订阅（需在把实例交给 `Reporter` 之前设置）
+    pub fn set_event_driven(&self, event_driven: bool) {
+        if let Ok(mut state) = self.state.lock() {
+            state.event_driven = event_driven;
+        }
+    }
+
+    /// 设置窗口列表（按叠放次序从上到下）
+    pub fn set_windows(&self, windows: Vec<WindowEntry>) {
+        if let Ok(mut state) = self.state.lock() {
+            state.windows = windows;
+        }
+    }
+
+    /// 设置当前播放（`None` 表示无播放），作为唯一的会话推送给所有订阅者
+    pub fn set_now_playing(&self, now_playing: Option<(MediaMetadata, PlaybackState)>) {
+        let sessions = now_playing.into_iter().map(|(metadata, state)| MediaSession {
+            id: metadata.bundle_identifier.clone().unwrap_or_default(),
+            last_changed: state.timestamp,
+            metadata,
+            state,
+        }).collect();
+        self.set_media_sessions(sessions);
+    }
+
+    /// 设置全部媒体会话（空列表表示无播放），并推送给所有订阅者
+    pub fn set_media_sessions(&self, sessions: Vec<MediaSession>) {
+        if let Ok(mut state) = self.state.lock() {
+            state.media_subscribers.retain(|tx| tx.send(sessions.clone()).is_ok());
+            state.media_sessions = sessions;
+        }
+    }
+
+    /// 设置距离最后一次用户输入的时长
+    pub fn set_idle_time(&self, idle_time: Duration) {
+        if let Ok(mut state) = self.state.lock() {
+            state.idle_time = idle_time;
+        }
+    }
+
+    /// 推送会话状态变化给所有订阅者
+    pub fn emit_session_event(&self, event: SessionEvent) {
+        if let Ok(mut state) = self.state.lock() {
+            state.session_subscribers.retain(|tx| tx.send(event).is_ok());
+        }
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&FakeState) -> T) -> Result<T, String> {
+        self.state
+            .lock()
+            .map(|state| f(&state))
+            .map_err(|e| format!("Fake platform state poisoned: {}", e))
+    }
+}
+
+impl WindowSource for FakePlatform {
+    fn frontmost_window(&self) -> Result<WindowInfo, String> {
+        self.with_state(|state| state.frontmost_window.clone())?
+            .ok_or_else(|| "No active window".to_string())
+    }
+
+    fn all_windows(&self) -> Result<Vec<WindowEntry>, String> {
+        self.with_state(|state| state.windows.clone())
+    }
+
+    fn subscribe(&self) -> Option<WindowEvents> {
+        let mut state = self.state.lock().ok()?;
+        if !state.event_driven {
+            return None;
+        }
+        let (tx, rx) = mpsc::channel();
+        if let Some(info) = &state.frontmost_window {
+            let _ = tx.send(info.clone());
+        }
+        state.subscribers.push(tx);
+        Some(rx)
+    }
+}
+
+impl MediaSource for FakePlatform {
+    fn now_playing(&self) -> Result<Option<(MediaMetadata, PlaybackState)>, String> {
+        self.with_state(|state| {
+            state.media_sessions.iter()
+                .find(|session| session.state.playing)
+                .or_else(|| state.media_sessions.first())
+                .map(|session| (session.metadata.clone(), session.state.clone()))
+        })
+    }
+
+    fn sessions(&self) -> Result<Vec<MediaSession>, String> {
+        self.with_state(|state| state.media_sessions.clone())
+    }
+
+    fn subscribe(&self) -> Option<MediaEvents> {
+        let mut state = self.state.lock().ok()?;
+        if !state.event_driven {
+            return None;
+        }
+        let (tx, rx) = mpsc::channel();
+        let _ = tx.send(state.media_sessions.clone());
+        state.media_subscribers.push(tx);
+        Some(rx)
+    }
+}
+
+impl IdleSource for FakePlatform {
+    fn idle_time(&self) -> Result<Duration, String> {
+        self.with_state(|state| state.idle_time)
+    }
+}
+
+impl SessionSource for FakePlatform {
+    fn subscribe(&self) -> Option<SessionEvents> {
+        let mut state = self.state.lock().ok()?;
+        let (tx, rx) = mpsc::channel();
+        state.session_subscribers.push(tx);
+        Some(rx)
+    }
+}
+
+impl PlatformProvider for FakePlatform {
+    /// 无需任何权限
+    fn request_permissions(&self) -> Result<bool, String> {
+        Ok(true)
+    }
+
+    fn check_permissions(&self) -> bool {
+        true
+    }
+
+    fn window_source(&self) -> Arc<dyn WindowSource> {
+        Arc::new(self.clone())
+    }
+
+    fn media_source(&self) -> Arc<dyn MediaSource> {
+        Arc::new(self.clone())
+    }
+
+    fn idle_source(&self) -> Arc<dyn IdleSource> {
+        Arc::new(self.clone())
+    }
+
+    fn session_source(&self) -> Arc<dyn SessionSource> {
+        Arc::new(self.clone())
+    }
+}
@@ -0,0 +1,18 @@
+//! Linux 用户空闲时间检测模块
+//! 基于 X11 MIT-SCREEN-SAVER 扩展
+
+use super::window::with_context;
+use std::time::Duration;
+use x11rb::protocol::screensaver::ConnectionExt as _;
+
+/// 获取距离最后一次用户输入的时长
+pub fn get_idle_time() -> Result<Duration, String> {
+    with_context(|ctx| {
+        let reply = ctx.conn
+            .screensaver_query_info(ctx.root)
+            .map_err(|e| format!("X11 request failed: {}", e))?
+            .reply()
+            .map_err(|e| format!("MIT-SCREEN-SAVER 查询失败: {}", e))?;
+        Ok(Duration::from_millis(reply.ms_since_user_input as u64))
+    })
+}
@@ -0,0 +1,454 @@
+//! Linux 媒体播放信息获取模块
+//! 基于 D-Bus MPRIS (org.mpris.MediaPlayer2.Player)
+
+use crate::platform::media_tracker::MediaChangeTracker;
+use crate::platform::{MediaEvents, MediaKind, MediaMetadata, MediaSession, PlaybackState};
+use std::collections::HashMap;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use base64::{Engine as _, engine::general_purpose};
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::blocking::fdo::{DBusProxy, PropertiesProxy};
+use zbus::message::Type as MessageType;
+use zbus::names::InterfaceName;
+use zbus::MatchRule;
+use zbus::zvariant::{OwnedValue, Value};
+
+/// MPRIS 播放器总线名前缀
+const MPRIS_BUS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+/// MPRIS 对象路径
+const MPRIS_OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+/// MPRIS 播放器接口
+const MPRIS_PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+/// MPRIS 规定的“无曲目”标识
+const MPRIS_NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+/// 媒体信息缓存
+struct MediaCache {
+    /// 播放中或暂停的播放器，按总线名排序
+    sessions: Vec<MediaSession>,
+    last_update: Instant,
+    /// 每个播放器上一次读取封面时的 artUrl（同一封面不重复读取）
+    art_urls: HashMap<String, String>,
+    tracker: MediaChangeTracker,
+}
+
+impl Default for MediaCache {
+    fn default() -> Self {
+        Self {
+            sessions: Vec::new(),
+            last_update: Instant::now() - Duration::from_secs(10), // 确保首次会更新
+            art_urls: HashMap::new(),
+            tracker: MediaChangeTracker::default(),
+        }
+    }
+}
+
+impl MediaCache {
+    /// 默认会话：优先正在播放的，其次暂停的
+    fn current(&self) -> Option<&MediaSession> {
+        self.sessions.iter().find(|s| s.state.playing).or_else(|| self.sessions.first())
+    }
+}
+
+// 全局缓存，缓存时间 200ms（避免频繁调用 D-Bus）
+static MEDIA_CACHE: Mutex<Option<MediaCache>> = Mutex::new(None);
+const CACHE_DURATION_MS: u64 = 200;
+
+/// 全局 session bus 连接，出错时丢弃并在下次调用时重连
+static SESSION_BUS: Mutex<Option<Connection>> = Mutex::new(None);
+
+/// 封面文件大小上限（避免读取异常大的文件）
+const MAX_ARTWORK_SIZE: u64 = 10 * 1024 * 1024;
+
+/// 单个 MPRIS 播放器的原始状态
+struct PlayerSnapshot {
+    bus_name: String,
+    status: String,
+    properties: HashMap<String, OwnedValue>,
+}
+
+/// 获取 session bus 连接
+fn session_bus() -> Result<Connection, String> {
+    let mut guard = SESSION_BUS.lock().map_err(|e| format!("D-Bus 连接锁定失败: {}", e))?;
+    if let Some(conn) = guard.as_ref() {
+        return Ok(conn.clone());
+    }
+    let conn = Connection::session().map_err(|e| format!("无法连接到 session bus: {}", e))?;
+    *guard = Some(conn.clone());
+    Ok(conn)
+}
+
+/// 丢弃 session bus 连接（下次调用时重连）
+fn reset_session_bus() {
+    if let Ok(mut guard) = SESSION_BUS.lock() {
+        *guard = None;
+    }
+}
+
+/// 列出 bus 上所有 MPRIS 播放器
+fn list_players(conn: &Connection) -> Result<Vec<String>, String> {
+    let dbus = DBusProxy::new(conn).map_err(|e| format!("D-Bus 代理创建失败: {}", e))?;
+    let names = dbus.list_names().map_err(|e| format!("ListNames 失败: {}", e))?;
+    let mut players: Vec<String> = names
+        .into_iter()
+        .map(|n| n.to_string())
+        .filter(|n| n.starts_with(MPRIS_BUS_PREFIX))
+        .collect();
+    players.sort();
+    Ok(players)
+}
+
+/// 读取播放器的 org.mpris.MediaPlayer2.Player 全部属性
+fn read_player(conn: &Connection, bus_name: &str) -> Result<PlayerSnapshot, String> {
+    let proxy = PropertiesProxy::builder(conn)
+        .destination(bus_name.to_string())
+        .and_then(|b| b.path(MPRIS_OBJECT_PATH))
+        .and_then(|b| b.build())
+        .map_err(|e| format!("MPRIS 代理创建失败: {}", e))?;
+    let properties = proxy
+        .get_all(InterfaceName::from_static_str_unchecked(MPRIS_PLAYER_INTERFACE))
+        .map_err(|e| format!("读取 {} 属性失败: {}", bus_name, e))?;
+    let status = properties
+        .get("PlaybackStatus")
+        .and_then(|v| value_to_string(v))
+        .unwrap_or_else(|| "Stopped".to_string());
+
+    Ok(PlayerSnapshot {
+        bus_name: bus_name.to_string(),
+        status,
+        properties,
+    })
+}
+
+/// 读取所有播放中或暂停的播放器；停止的播放器视为无播放
+fn active_players(conn: &Connection) -> Result<Vec<PlayerSnapshot>, String> {
+    Ok(list_players(conn)?
+        .iter()
+        // 播放器可能在枚举过程中退出，忽略单个播放器的错误
+        .filter_map(|bus_name| read_player(conn, bus_name).ok())
+        .filter(|snapshot| matches!(snapshot.status.as_str(), "Playing" | "Paused"))
+        .collect())
+}
+
+/// 从 MPRIS 总线名提取播放器标识（去掉前缀与 `.instanceNNN` 后缀）
+fn player_identifier(bus_name: &str) -> String {
+    let name = bus_name.strip_prefix(MPRIS_BUS_PREFIX).unwrap_or(bus_name);
+    match name.split_once(".instance") {
+        Some((player, _)) => player.to_string(),
+        None => name.to_string(),
+    }
+}
+
+/// 去掉 variant 包装
+fn unwrap_value<'a>(value: &'a Value<'a>) -> &'a Value<'a> {
+    match value {
+        Value::Value(inner) => unwrap_value(inner),
+        other => other,
+    }
+}
+
+fn value_to_string(value: &Value) -> Option<String> {
+    match unwrap_value(value) {
+        Value::Str(s) => Some(s.to_string()),
+        Value::ObjectPath(p) => Some(p.to_string()),
+        _ => None,
+    }
+}
+
+fn value_to_f64(value: &Value) -> Option<f64> {
+    match unwrap_value(value) {
+        Value::F64(v) => Some(*v),
+        Value::I64(v) => Some(*v as f64),
+        Value::U64(v) => Some(*v as f64),
+        Value::I32(v) => Some(*v as f64),
+        Value::U32(v) => Some(*v as f64),
+        _ => None,
+    }
+}
+
+/// `xesam:artist` 为字符串数组，部分播放器会直接给字符串
+fn value_to_joined_strings(value: &Value) -> Option<String> {
+    match unwrap_value(value) {
+        Value::Array(items) => {
+            let parts: Vec<String> = items.iter().filter_map(value_to_string).collect();
+            if parts.is_empty() { None } else { Some(parts.join(", ")) }
+        }
+        other => value_to_string(other),
+    }
+}
+
+/// 把 `Metadata` (a{sv}) 转为 key -> Value 的映射
+fn metadata_map(properties: &HashMap<String, OwnedValue>) -> HashMap<String, Value<'static>> {
+    let Some(metadata) = properties.get("Metadata") else { return HashMap::new() };
+    let Ok(metadata) = metadata.try_clone() else { return HashMap::new() };
+    HashMap::<String, OwnedValue>::try_from(metadata)
+        .map(|map| map.into_iter().map(|(k, v)| (k, Value::from(v))).collect())
+        .unwrap_or_default()
+}
+
+fn non_empty(s: Option<String>) -> Option<String> {
+    s.filter(|s| !s.is_empty())
+}
+
+/// `xesam:url` 扩展名为视频格式时视为视频
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mkv", "webm", "avi", "mov", "m4v", "flv", "wmv", "ts"];
+
+/// MPRIS 没有内容类型字段，根据流派、trackid（如 Spotify 的 `/com/spotify/episode/...`）与 URL 推断
+fn media_kind(metadata: &HashMap<String, Value<'static>>, track_id: Option<&str>) -> MediaKind {
+    let genre = metadata.get("xesam:genre").and_then(value_to_joined_strings).unwrap_or_default().to_lowercase();
+    let track_id = track_id.unwrap_or("").to_lowercase();
+    if genre.contains("podcast") || track_id.contains("/episode/") {
+        return MediaKind::Podcast;
+    }
+    if track_id.contains("/track/") {
+        return MediaKind::Music;
+    }
+
+    let url = metadata.get("xesam:url").and_then(value_to_string).unwrap_or_default();
+    let extension = url.split(['?', '#']).next().unwrap_or("")
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+    if VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        MediaKind::Video
+    } else {
+        MediaKind::Unknown
+    }
+}
+
+/// 读取 `mpris:artUrl` 指向的封面，支持 `file://` 与 `data:` URI
+fn load_artwork(art_url: &str) -> Option<Vec<u8>> {
+    if let Some(rest) = art_url.strip_prefix("data:") {
+        let (header, payload) = rest.split_once(',')?;
+        if !header.ends_with(";base64") {
+            return None;
+        }
+        return general_purpose::STANDARD.decode(payload).ok();
+    }
+
+    let path = url::Url::parse(art_url).ok()?.to_file_path().ok()?;
+    if std::fs::metadata(&path).ok()?.len() > MAX_ARTWORK_SIZE {
+        return None;
+    }
+    std::fs::read(&path).ok()
+}
+
+/// 把播放器快照转为会话，封面与变化时间记录在缓存中
+fn session_from_snapshot(snapshot: &PlayerSnapshot, cache: &mut MediaCache) -> MediaSession {
+    let metadata = metadata_map(&snapshot.properties);
+    let bundle_identifier = player_identifier(&snapshot.bus_name);
+
+    let title = non_empty(metadata.get("xesam:title").and_then(value_to_string));
+    let artist = non_empty(metadata.get("xesam:artist").and_then(value_to_joined_strings));
+    let album = non_empty(metadata.get("xesam:album").and_then(value_to_string));
+    // mpris:length 与 Position 的单位为微秒
+    let duration = metadata.get("mpris:length").and_then(value_to_f64).unwrap_or(0.0) / 1_000_000.0;
+    let elapsed_time = snapshot.properties.get("Position").and_then(|v| value_to_f64(v)).unwrap_or(0.0) / 1_000_000.0;
+    let playing = snapshot.status == "Playing";
+    let playback_rate = snapshot.properties.get("Rate").and_then(|v| value_to_f64(v)).unwrap_or(1.0);
+
+    // 生成内容标识符：优先使用 mpris:trackid，缺失时退回 player + title + album 的组合
+    let track_id = non_empty(metadata.get("mpris:trackid").and_then(value_to_string))
+        .filter(|id| id != MPRIS_NO_TRACK);
+    let kind = media_kind(&metadata, track_id.as_deref());
+    let content_item_identifier = match track_id {
+        Some(track_id) => format!("{}:{}", bundle_identifier, track_id),
+        None => format!(
+            "{}:{}:{}",
+            bundle_identifier,
+            title.as_deref().unwrap_or(""),
+            album.as_deref().unwrap_or("")
+        ),
+    };
+
+    // 封面：artUrl 未变化时复用缓存，避免重复读取
+    let art_url = non_empty(metadata.get("mpris:artUrl").and_then(value_to_string));
+    let artwork_data = if art_url.is_some() && art_url.as_ref() == cache.art_urls.get(&snapshot.bus_name) {
+        cache.sessions.iter()
+            .find(|s| s.id == snapshot.bus_name)
+            .and_then(|s| s.metadata.artwork_data.clone())
+    } else {
+        art_url.as_deref().and_then(load_artwork).map(Arc::new)
+    };
+    match art_url {
+        Some(art_url) => cache.art_urls.insert(snapshot.bus_name.clone(), art_url),
+        None => cache.art_urls.remove(&snapshot.bus_name),
+    };
+
+    let mut media_metadata = MediaMetadata {
+        bundle_identifier: Some(bundle_identifier),
+        title,
+        artist,
+        album,
+        duration,
+        content_item_identifier: Some(content_item_identifier),
+        kind,
+        ..Default::default()
+    };
+    media_metadata.set_artwork(artwork_data);
+
+    let state = PlaybackState {
+        playing,
+        playback_rate: if playing { playback_rate } else { 0.0 },
+        elapsed_time,
+        timestamp: SystemTime::now(),
+    };
+
+    MediaSession {
+        id: snapshot.bus_name.clone(),
+        last_changed: cache.tracker.observe(&snapshot.bus_name, &media_metadata, &state),
+        metadata: media_metadata,
+        state,
+    }
+}
+
+/// 使缓存失效，下次读取时重新查询 D-Bus
+fn invalidate_cache() {
+    if let Ok(mut guard) = MEDIA_CACHE.lock() {
+        if let Some(cache) = guard.as_mut() {
+            cache.last_update = Instant::now() - Duration::from_secs(10);
+        }
+    }
+}
+
+/// 刷新缓存（如已过期）并读取其中的数据
+fn with_fresh_cache<T>(read: impl Fn(&MediaCache) -> T) -> Result<T, String> {
+    let mut cache_guard = MEDIA_CACHE.lock().map_err(|e| format!("缓存锁定失败: {}", e))?;
+    let cache = cache_guard.get_or_insert_with(MediaCache::default);
+
+    if cache.last_update.elapsed() < Duration::from_millis(CACHE_DURATION_MS) {
+        return Ok(read(cache));
+    }
+
+    let conn = session_bus()?;
+    let snapshots = match active_players(&conn) {
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            reset_session_bus();
+            return Err(e);
+        }
+    };
+
+    let sessions: Vec<MediaSession> = snapshots.iter().map(|s| session_from_snapshot(s, cache)).collect();
+    // 丢弃已退出播放器的封面与变化记录
+    cache.art_urls.retain(|bus_name, _| sessions.iter().any(|s| &s.id == bus_name));
+    cache.tracker.retain(sessions.iter().map(|s| s.id.as_str()));
+    cache.sessions = sessions;
+    cache.last_update = Instant::now();
+
+    Ok(read(cache))
+}
+
+/// 获取当前播放状态
+pub fn get_playback_state() -> Result<Option<PlaybackState>, String> {
+    with_fresh_cache(|cache| cache.current().map(|s| s.state.clone()))
+}
+
+/// 获取当前媒体元数据
+pub fn get_media_metadata() -> Result<Option<MediaMetadata>, String> {
+    with_fresh_cache(|cache| cache.current().map(|s| s.metadata.clone()))
+}
+
+/// 同时获取元数据与播放状态（来自同一次刷新），多个播放器时优先正在播放的
+pub fn get_now_playing() -> Result<Option<(MediaMetadata, PlaybackState)>, String> {
+    with_fresh_cache(|cache| cache.current().map(|s| (s.metadata.clone(), s.state.clone())))
+}
+
+/// 获取所有播放中或暂停的播放器，按总线名排序
+pub fn get_media_sessions() -> Result<Vec<MediaSession>, String> {
+    with_fresh_cache(|cache| cache.sessions.clone())
+}
+
+/// 需要监听的信号：播放器属性变化、位置跳转、播放器启动/退出
+fn media_match_rules() -> zbus::Result<Vec<MatchRule<'static>>> {
+    Ok(vec![
+        MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .interface("org.freedesktop.DBus.Properties")?
+            .member("PropertiesChanged")?
+            .path(MPRIS_OBJECT_PATH)?
+            .arg(0, MPRIS_PLAYER_INTERFACE)?
+            .build(),
+        MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .interface(MPRIS_PLAYER_INTERFACE)?
+            .member("Seeked")?
+            .path(MPRIS_OBJECT_PATH)?
+            .build(),
+        MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .sender("org.freedesktop.DBus")?
+            .interface("org.freedesktop.DBus")?
+            .member("NameOwnerChanged")?
+            .arg0ns(MPRIS_BUS_PREFIX.trim_end_matches('.'))?
+            .build(),
+    ])
+}
+
+/// 订阅媒体播放变化
+///
+/// 监听 `PropertiesChanged` / `Seeked` / `NameOwnerChanged` 信号，收到后重新读取全部播放器；
+/// 播放过程中位置的自然推进不会产生信号。信号使用独立的连接，不影响查询用的连接。
+pub fn subscribe_media_changes() -> Result<MediaEvents, String> {
+    let conn = Connection::session().map_err(|e| format!("无法连接到 session bus: {}", e))?;
+    // 先创建消息迭代器，避免错过添加规则后立即到达的信号
+    let messages = MessageIterator::from(&conn);
+    let dbus = DBusProxy::new(&conn).map_err(|e| format!("D-Bus 代理创建失败: {}", e))?;
+    let rules = media_match_rules().map_err(|e| format!("构造信号匹配规则失败: {}", e))?;
+    for rule in rules {
+        dbus.add_match_rule(rule).map_err(|e| format!("AddMatch 失败: {}", e))?;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("mpris-watcher".to_string())
+        .spawn(move || watch_media_changes(messages, tx))
+        .map_err(|e| format!("Failed to spawn MPRIS watcher thread: {}", e))?;
+    Ok(rx)
+}
+
+/// 信号循环：接收端被丢弃或连接断开时退出
+fn watch_media_changes(messages: MessageIterator, tx: mpsc::Sender<Vec<MediaSession>>) {
+    if !refresh_sessions(&tx) {
+        return;
+    }
+
+    for message in messages {
+        let message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("MPRIS watcher stopped: {}", e);
+                return;
+            }
+        };
+
+        let header = message.header();
+        if header.message_type() != MessageType::Signal {
+            continue;
+        }
+        let relevant = matches!(
+            header.member().map(|m| m.as_str()),
+            Some("PropertiesChanged" | "Seeked" | "NameOwnerChanged")
+        );
+
+        if relevant {
+            invalidate_cache();
+            if !refresh_sessions(&tx) {
+                return;
+            }
+        }
+    }
+}
+
+/// 读取全部播放器并推送；返回 `false` 表示接收端已丢弃
+fn refresh_sessions(tx: &mpsc::Sender<Vec<MediaSession>>) -> bool {
+    match get_media_sessions() {
+        Ok(sessions) => tx.send(sessions).is_ok(),
+        Err(e) => {
+            // 单次读取失败（如播放器正在退出）不结束订阅
+            tracing::debug!("Failed to read MPRIS state: {}", e);
+            true
+        }
+    }
+}
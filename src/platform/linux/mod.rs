@@ -0,0 +1,100 @@
+//! Linux 平台实现 (X11 + MPRIS + logind)
+
+pub mod idle;
+pub mod media;
+pub mod session;
+pub mod window;
+
+pub use idle::get_idle_time;
+pub use media::{get_media_metadata, get_playback_state, get_now_playing, get_media_sessions, subscribe_media_changes};
+pub use session::{subscribe_session_changes, subscribe_session_changes_on, LogindSessionSource};
+pub use window::{get_frontmost_window, get_all_windows, subscribe_window_changes};
+
+use super::{IdleSource, MediaEvents, MediaMetadata, MediaSession, MediaSource, PlatformProvider, PlaybackState, SessionSource, WindowEntry, WindowEvents, WindowInfo, WindowSource};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 请求必要的权限 (X11 下不需要额外授权)
+pub fn request_permissions() -> Result<bool, String> {
+    Ok(check_permissions())
+}
+
+/// 检查权限状态 (能否连接到 X server)
+pub fn check_permissions() -> bool {
+    std::env::var_os("DISPLAY").is_some()
+}
+
+/// Linux 平台实现 (X11 + MPRIS)
+pub struct LinuxPlatform;
+
+impl WindowSource for LinuxPlatform {
+    fn frontmost_window(&self) -> Result<WindowInfo, String> {
+        get_frontmost_window()
+    }
+
+    fn all_windows(&self) -> Result<Vec<WindowEntry>, String> {
+        get_all_windows()
+    }
+
+    fn subscribe(&self) -> Option<WindowEvents> {
+        match subscribe_window_changes() {
+            Ok(events) => Some(events),
+            Err(e) => {
+                tracing::warn!("X11 focus events unavailable, falling back to polling: {}", e);
+                None
+            }
+        }
+    }
+}
+
+impl MediaSource for LinuxPlatform {
+    fn now_playing(&self) -> Result<Option<(MediaMetadata, PlaybackState)>, String> {
+        get_now_playing()
+    }
+
+    fn sessions(&self) -> Result<Vec<MediaSession>, String> {
+        get_media_sessions()
+    }
+
+    fn subscribe(&self) -> Option<MediaEvents> {
+        match subscribe_media_changes() {
+            Ok(events) => Some(events),
+            Err(e) => {
+                tracing::warn!("MPRIS signals unavailable, falling back to polling: {}", e);
+                None
+            }
+        }
+    }
+}
+
+impl IdleSource for LinuxPlatform {
+    fn idle_time(&self) -> Result<Duration, String> {
+        get_idle_time()
+    }
+}
+
+impl PlatformProvider for LinuxPlatform {
+    fn request_permissions(&self) -> Result<bool, String> {
+        request_permissions()
+    }
+
+    fn check_permissions(&self) -> bool {
+        check_permissions()
+    }
+
+    fn window_source(&self) -> Arc<dyn WindowSource> {
+        Arc::new(LinuxPlatform)
+    }
+
+    fn media_source(&self) -> Arc<dyn MediaSource> {
+        Arc::new(LinuxPlatform)
+    }
+
+    fn idle_source(&self) -> Arc<dyn IdleSource> {
+        Arc::new(LinuxPlatform)
+    }
+
+    fn session_source(&self) -> Arc<dyn SessionSource> {
+        Arc::new(LogindSessionSource::new())
+    }
+}
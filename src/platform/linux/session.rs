@@ -0,0 +1,226 @@
+//! Linux 会话状态监听模块
+//! 基于 systemd-logind (org.freedesktop.login1) 的锁屏与休眠信号，
+//! 以及 NetworkManager 的联网状态变化（用于提示重连）
+
+use crate::platform::{SessionEvent, SessionEvents, SessionSource};
+use std::collections::HashMap;
+use std::sync::mpsc;
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::blocking::fdo::{DBusProxy, PropertiesProxy};
+use zbus::message::Type as MessageType;
+use zbus::names::InterfaceName;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+use zbus::MatchRule;
+
+/// logind 总线名
+const LOGIN1_BUS_NAME: &str = "org.freedesktop.login1";
+/// logind Manager 对象路径
+const LOGIN1_MANAGER_PATH: &str = "/org/freedesktop/login1";
+/// logind Manager 接口
+const LOGIN1_MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+/// logind Session 接口
+const LOGIN1_SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+/// NetworkManager 总线名（同时也是接口名）
+const NM_BUS_NAME: &str = "org.freedesktop.NetworkManager";
+/// NetworkManager 对象路径
+const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+/// NM_STATE_CONNECTED_GLOBAL：已连接且可访问互联网
+const NM_STATE_CONNECTED_GLOBAL: u32 = 70;
+
+/// 基于 logind 的会话状态来源
+///
+/// 默认连接 system bus；`with_address` 可指定其他总线（例如测试时运行在私有
+/// D-Bus 上的 mock logind）。
+#[derive(Debug, Clone, Default)]
+pub struct LogindSessionSource {
+    bus_address: Option<String>,
+}
+
+impl LogindSessionSource {
+    /// 连接 system bus 上的 logind
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 连接指定地址的总线，例如 `unix:path=/tmp/test-bus`
+    pub fn with_address(address: impl Into<String>) -> Self {
+        Self { bus_address: Some(address.into()) }
+    }
+
+    fn connect(&self) -> Result<Connection, String> {
+        match &self.bus_address {
+            Some(address) => zbus::blocking::connection::Builder::address(address.as_str())
+                .and_then(|builder| builder.build())
+                .map_err(|e| format!("无法连接到 D-Bus {}: {}", address, e)),
+            None => Connection::system().map_err(|e| format!("无法连接到 system bus: {}", e)),
+        }
+    }
+}
+
+impl SessionSource for LogindSessionSource {
+    fn subscribe(&self) -> Option<SessionEvents> {
+        match self.connect().and_then(subscribe_session_changes_on) {
+            Ok(events) => Some(events),
+            Err(e) => {
+                tracing::warn!("logind session events unavailable: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// 订阅 system bus 上 logind 的会话状态变化
+pub fn subscribe_session_changes() -> Result<SessionEvents, String> {
+    LogindSessionSource::new().connect().and_then(subscribe_session_changes_on)
+}
+
+/// 在指定连接上订阅会话状态变化
+///
+/// 监听当前会话的 `LockedHint` 属性变化、Manager 的 `PrepareForSleep` 信号，以及
+/// NetworkManager 的 `StateChanged` 信号（未运行 NetworkManager 时不会收到）。订阅成功且当前会话已锁定时，会先推送一次 [`SessionEvent::Locked`]。
+pub fn subscribe_session_changes_on(conn: Connection) -> Result<SessionEvents, String> {
+    let session_path = current_session_path(&conn)?;
+    let locked = locked_hint(&conn, &session_path)?;
+
+    // 先创建消息迭代器，避免错过添加规则后立即到达的信号
+    let messages = MessageIterator::from(&conn);
+    let dbus = DBusProxy::new(&conn).map_err(|e| format!("D-Bus 代理创建失败: {}", e))?;
+    let rules = session_match_rules(&session_path).map_err(|e| format!("构造信号匹配规则失败: {}", e))?;
+    for rule in rules {
+        dbus.add_match_rule(rule).map_err(|e| format!("AddMatch 失败: {}", e))?;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    if locked {
+        let _ = tx.send(SessionEvent::Locked);
+    }
+    std::thread::Builder::new()
+        .name("logind-watcher".to_string())
+        .spawn(move || watch_session_changes(messages, locked, tx))
+        .map_err(|e| format!("Failed to spawn logind watcher thread: {}", e))?;
+    Ok(rx)
+}
+
+/// 获取当前进程所在会话的对象路径：优先 `XDG_SESSION_ID`，否则按 PID 查询
+fn current_session_path(conn: &Connection) -> Result<OwnedObjectPath, String> {
+    let reply = match std::env::var("XDG_SESSION_ID").ok().filter(|id| !id.is_empty()) {
+        Some(session_id) => conn.call_method(
+            Some(LOGIN1_BUS_NAME),
+            LOGIN1_MANAGER_PATH,
+            Some(LOGIN1_MANAGER_INTERFACE),
+            "GetSession",
+            &(session_id.as_str(),),
+        ),
+        None => conn.call_method(
+            Some(LOGIN1_BUS_NAME),
+            LOGIN1_MANAGER_PATH,
+            Some(LOGIN1_MANAGER_INTERFACE),
+            "GetSessionByPID",
+            &(std::process::id(),),
+        ),
+    }
+    .map_err(|e| format!("获取 logind 会话失败: {}", e))?;
+
+    reply
+        .body()
+        .deserialize::<OwnedObjectPath>()
+        .map_err(|e| format!("解析 logind 会话路径失败: {}", e))
+}
+
+/// 读取会话的 `LockedHint` 属性
+fn locked_hint(conn: &Connection, session_path: &OwnedObjectPath) -> Result<bool, String> {
+    let proxy = PropertiesProxy::builder(conn)
+        .destination(LOGIN1_BUS_NAME)
+        .and_then(|b| b.path(session_path.as_ref()))
+        .and_then(|b| b.build())
+        .map_err(|e| format!("logind 代理创建失败: {}", e))?;
+    let value = proxy
+        .get(InterfaceName::from_static_str_unchecked(LOGIN1_SESSION_INTERFACE), "LockedHint")
+        .map_err(|e| format!("读取 LockedHint 失败: {}", e))?;
+    bool::try_from(value).map_err(|e| format!("解析 LockedHint 失败: {}", e))
+}
+
+/// 需要监听的信号：当前会话的锁定状态、系统休眠与联网状态
+fn session_match_rules(session_path: &OwnedObjectPath) -> zbus::Result<Vec<MatchRule<'static>>> {
+    let session_path = session_path.to_string();
+    Ok(vec![
+        MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .sender(LOGIN1_BUS_NAME)?
+            .interface(LOGIN1_MANAGER_INTERFACE)?
+            .member("PrepareForSleep")?
+            .path(LOGIN1_MANAGER_PATH)?
+            .build(),
+        MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .sender(LOGIN1_BUS_NAME)?
+            .interface("org.freedesktop.DBus.Properties")?
+            .member("PropertiesChanged")?
+            .path(session_path)?
+            .arg(0, LOGIN1_SESSION_INTERFACE)?
+            .build(),
+        MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .sender(NM_BUS_NAME)?
+            .interface(NM_BUS_NAME)?
+            .member("StateChanged")?
+            .path(NM_PATH)?
+            .build(),
+    ])
+}
+
+/// 信号循环：接收端被丢弃或连接断开时退出
+fn watch_session_changes(messages: MessageIterator, mut locked: bool, tx: mpsc::Sender<SessionEvent>) {
+    for message in messages {
+        let message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::warn!("logind watcher stopped: {}", e);
+                return;
+            }
+        };
+
+        let header = message.header();
+        if header.message_type() != MessageType::Signal {
+            continue;
+        }
+
+        let event = match header.member().map(|m| m.as_str()) {
+            Some("PrepareForSleep") => match message.body().deserialize::<bool>() {
+                Ok(true) => Some(SessionEvent::Suspending),
+                Ok(false) => Some(SessionEvent::Resumed),
+                Err(_) => None,
+            },
+            // 重新联网（切换 Wi-Fi、VPN 等）后提示立即重连
+            Some("StateChanged") if header.interface().is_some_and(|i| i.as_str() == NM_BUS_NAME) => {
+                match message.body().deserialize::<u32>() {
+                    Ok(NM_STATE_CONNECTED_GLOBAL) => Some(SessionEvent::NetworkChanged),
+                    _ => None,
+                }
+            }
+            // `Lock` / `Unlock` 只是请求锁屏程序动手，是否真的锁定以 `LockedHint` 为准
+            Some("PropertiesChanged") => message
+                .body()
+                .deserialize::<(String, HashMap<String, OwnedValue>, Vec<String>)>()
+                .ok()
+                .and_then(|(_, changed, _)| changed.get("LockedHint").and_then(|v| bool::try_from(v).ok()))
+                .and_then(|hint| set_locked(&mut locked, hint)),
+            _ => None,
+        };
+
+        if let Some(event) = event {
+            if tx.send(event).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// 更新锁定状态，只在状态翻转时产生事件
+fn set_locked(locked: &mut bool, value: bool) -> Option<SessionEvent> {
+    if *locked == value {
+        return None;
+    }
+    *locked = value;
+    Some(if value { SessionEvent::Locked } else { SessionEvent::Unlocked })
+}
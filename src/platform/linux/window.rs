@@ -0,0 +1,536 @@
+//! Linux 窗口信息获取模块
+//! 基于 X11 EWMH 属性 (_NET_ACTIVE_WINDOW / _NET_WM_NAME / _NET_WM_PID / _NET_WM_ICON)
+//! 焦点与标题变化通过 PropertyNotify 事件推送；窗口列表来自 _NET_CLIENT_LIST_STACKING
+//! 浏览器隐私窗口通过 WM_CLASS / WM_WINDOW_ROLE 与进程命令行识别
+
+use crate::platform::{WindowBounds, WindowEntry, WindowEvents, WindowInfo};
+use image::codecs::png::PngEncoder;
+use image::{ExtendedColorType, ImageEncoder};
+use std::collections::HashMap;
+use std::sync::{mpsc, Mutex};
+use x11rb::connection::Connection;
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::Event;
+use x11rb::protocol::xproto::{AtomEnum, ChangeWindowAttributesAux, ConnectionExt, EventMask, Window};
+use x11rb::rust_connection::RustConnection;
+
+x11rb::atom_manager! {
+    pub Atoms: AtomsCookie {
+        _NET_ACTIVE_WINDOW,
+        _NET_CLIENT_LIST,
+        _NET_CLIENT_LIST_STACKING,
+        _NET_WM_DESKTOP,
+        _NET_WM_STATE,
+        _NET_WM_STATE_HIDDEN,
+        _NET_WM_STATE_FULLSCREEN,
+        _NET_WM_NAME,
+        _NET_WM_PID,
+        _NET_WM_ICON,
+        WM_WINDOW_ROLE,
+        UTF8_STRING,
+    }
+}
+
+/// X11 连接上下文（连接、根窗口与预先 intern 的 atom）
+pub(super) struct X11Context {
+    pub(super) conn: RustConnection,
+    pub(super) root: Window,
+    atoms: Atoms,
+}
+
+/// 全局 X11 连接，出错时丢弃并在下次调用时重连
+static X11_CONTEXT: Mutex<Option<X11Context>> = Mutex::new(None);
+
+/// 图标缓存：WM_CLASS -> PNG 数据（带 LRU 淘汰）
+static ICON_CACHE: Mutex<Option<HashMap<String, Vec<u8>>>> = Mutex::new(None);
+static ICON_CACHE_KEYS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+const MAX_ICON_CACHE_SIZE: usize = 20;  // 最多缓存 20 个图标
+
+/// 期望的图标边长，与 macOS 实现保持一致
+const PREFERRED_ICON_SIZE: u32 = 32;
+/// 单个属性最多读取的 32 位单元数（约 4 MB，足以容纳 _NET_WM_ICON）
+const MAX_PROPERTY_LENGTH: u32 = 1 << 20;
+/// `_NET_WM_DESKTOP` 中表示“在所有桌面显示”的值
+const ALL_DESKTOPS: u32 = 0xFFFF_FFFF;
+
+/// 窗口类 / 角色中表示隐私窗口的单词（小写，如 `firefox-private`、`google-chrome (incognito)`）
+const PRIVATE_WINDOW_MARKERS: &[&str] = &["incognito", "inprivate", "private"];
+/// 以隐私模式启动浏览器的命令行参数（Firefox / Chromium 系 / Edge / Opera）
+const PRIVATE_BROWSING_FLAGS: &[&str] = &[
+    "--incognito", "-incognito",
+    "--private-window", "-private-window", "--private", "-private",
+    "--inprivate", "-inprivate",
+];
+
+impl X11Context {
+    fn connect() -> Result<Self, String> {
+        let (conn, screen_num) = x11rb::connect(None)
+            .map_err(|e| format!("无法连接到 X server: {}", e))?;
+        let root = conn.setup().roots[screen_num].root;
+        let atoms = Atoms::new(&conn)
+            .map_err(|e| format!("Failed to intern atoms: {}", e))?
+            .reply()
+            .map_err(|e| format!("Failed to intern atoms: {}", e))?;
+        Ok(Self { conn, root, atoms })
+    }
+
+    /// 读取窗口属性的原始回复
+    fn get_property(
+        &self,
+        window: Window,
+        property: impl Into<u32>,
+        type_: impl Into<u32>,
+        long_length: u32,
+    ) -> Result<x11rb::protocol::xproto::GetPropertyReply, String> {
+        self.conn
+            .get_property(false, window, property, type_, 0, long_length)
+            .map_err(|e| format!("X11 request failed: {}", e))?
+            .reply()
+            .map_err(|e| format!("X11 request failed: {}", e))
+    }
+
+    /// 读取 32 位属性列表（WINDOW / CARDINAL 等）
+    fn get_u32_list(&self, window: Window, property: impl Into<u32>, type_: impl Into<u32>, long_length: u32) -> Result<Vec<u32>, String> {
+        let reply = self.get_property(window, property, type_, long_length)?;
+        Ok(reply.value32().map(|values| values.collect()).unwrap_or_default())
+    }
+
+    /// 获取当前激活窗口
+    fn active_window(&self) -> Result<Option<Window>, String> {
+        let values = self.get_u32_list(self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW, 1)?;
+        Ok(values.first().copied().filter(|&w| w != x11rb::NONE))
+    }
+
+    /// 获取窗口管理器管理的所有顶层窗口，按叠放次序从下到上排列
+    ///
+    /// 窗口管理器不支持 `_NET_CLIENT_LIST_STACKING` 时回退到 `_NET_CLIENT_LIST`（映射顺序）
+    fn client_list(&self) -> Result<Vec<Window>, String> {
+        let stacking = self.get_u32_list(self.root, self.atoms._NET_CLIENT_LIST_STACKING, AtomEnum::WINDOW, MAX_PROPERTY_LENGTH)?;
+        if !stacking.is_empty() {
+            return Ok(stacking);
+        }
+        self.get_u32_list(self.root, self.atoms._NET_CLIENT_LIST, AtomEnum::WINDOW, MAX_PROPERTY_LENGTH)
+    }
+
+    /// 获取各显示器的区域；RandR 不可用时把整个根窗口视为一个显示器
+    fn monitors(&self) -> Vec<WindowBounds> {
+        let monitors = self.conn
+            .randr_get_monitors(self.root, true)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.monitors)
+            .unwrap_or_default();
+        if !monitors.is_empty() {
+            return monitors
+                .iter()
+                .map(|m| WindowBounds { x: m.x as i32, y: m.y as i32, width: m.width as u32, height: m.height as u32 })
+                .collect();
+        }
+
+        self.window_bounds(self.root).map(|bounds| vec![bounds]).unwrap_or_default()
+    }
+
+    /// 获取窗口在根窗口坐标系中的位置与大小（不含窗口管理器的边框）
+    fn window_bounds(&self, window: Window) -> Result<WindowBounds, String> {
+        let geometry = self.conn
+            .get_geometry(window)
+            .map_err(|e| format!("X11 request failed: {}", e))?
+            .reply()
+            .map_err(|e| format!("X11 request failed: {}", e))?;
+        let origin = self.conn
+            .translate_coordinates(window, self.root, 0, 0)
+            .map_err(|e| format!("X11 request failed: {}", e))?
+            .reply()
+            .map_err(|e| format!("X11 request failed: {}", e))?;
+        Ok(WindowBounds {
+            x: origin.dst_x as i32,
+            y: origin.dst_y as i32,
+            width: geometry.width as u32,
+            height: geometry.height as u32,
+        })
+    }
+
+    /// 获取窗口所在桌面序号，在所有桌面显示时返回 `None`
+    fn window_desktop(&self, window: Window) -> Result<Option<u32>, String> {
+        let values = self.get_u32_list(window, self.atoms._NET_WM_DESKTOP, AtomEnum::CARDINAL, 1)?;
+        Ok(values.first().copied().filter(|&desktop| desktop != ALL_DESKTOPS))
+    }
+
+    /// 获取 `_NET_WM_STATE` 中的状态 atom
+    fn window_state(&self, window: Window) -> Result<Vec<u32>, String> {
+        self.get_u32_list(window, self.atoms._NET_WM_STATE, AtomEnum::ATOM, 64)
+    }
+
+    /// 获取窗口标题：优先 _NET_WM_NAME (UTF-8)，回退到 WM_NAME
+    fn window_title(&self, window: Window) -> Result<String, String> {
+        let reply = self.get_property(window, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING, 1024)?;
+        if !reply.value.is_empty() {
+            return Ok(String::from_utf8_lossy(&reply.value).into_owned());
+        }
+
+        let reply = self.get_property(window, AtomEnum::WM_NAME, AtomEnum::ANY, 1024)?;
+        if reply.type_ == u32::from(AtomEnum::STRING) {
+            // STRING 类型为 Latin-1 编码
+            Ok(reply.value.iter().map(|&b| b as char).collect())
+        } else {
+            Ok(String::from_utf8_lossy(&reply.value).into_owned())
+        }
+    }
+
+    /// 获取窗口所属进程 ID
+    fn window_pid(&self, window: Window) -> Result<Option<u32>, String> {
+        let values = self.get_u32_list(window, self.atoms._NET_WM_PID, AtomEnum::CARDINAL, 1)?;
+        Ok(values.first().copied())
+    }
+
+    /// 获取 WM_CLASS (instance, class)
+    fn window_class(&self, window: Window) -> Result<Option<(String, String)>, String> {
+        let reply = self.get_property(window, AtomEnum::WM_CLASS, AtomEnum::STRING, 256)?;
+        Ok(parse_wm_class(&reply.value))
+    }
+
+    /// 获取 WM_WINDOW_ROLE
+    fn window_role(&self, window: Window) -> Result<Option<String>, String> {
+        let reply = self.get_property(window, self.atoms.WM_WINDOW_ROLE, AtomEnum::STRING, 256)?;
+        Ok(Some(String::from_utf8_lossy(&reply.value).trim_end_matches('\0').to_string()).filter(|role| !role.is_empty()))
+    }
+
+    /// 是否为浏览器隐私窗口：WM_CLASS / WM_WINDOW_ROLE 带隐私标记，或进程以隐私模式启动
+    fn is_private_browsing(&self, window: Window) -> Result<bool, String> {
+        let class = self.window_class(window)?;
+        let role = self.window_role(window)?;
+        let marked = class
+            .iter()
+            .flat_map(|(instance, class)| [instance, class])
+            .chain(role.as_ref())
+            .any(|value| {
+                value
+                    .to_lowercase()
+                    .split(|c: char| !c.is_alphanumeric())
+                    .any(|word| PRIVATE_WINDOW_MARKERS.contains(&word))
+            });
+        if marked {
+            return Ok(true);
+        }
+
+        let command_line = self.window_pid(window)?.and_then(command_line_from_proc).unwrap_or_default();
+        Ok(command_line.iter().skip(1).any(|arg| {
+            let flag = arg.split('=').next().unwrap_or(arg);
+            PRIVATE_BROWSING_FLAGS.contains(&flag)
+        }))
+    }
+
+    /// 获取 _NET_WM_ICON 并转换为 PNG
+    fn window_icon_png(&self, window: Window) -> Result<Option<Vec<u8>>, String> {
+        let data = self.get_u32_list(window, self.atoms._NET_WM_ICON, AtomEnum::CARDINAL, MAX_PROPERTY_LENGTH)?;
+        Ok(select_icon(&data, PREFERRED_ICON_SIZE).and_then(|(width, height, pixels)| argb_to_png(width, height, pixels)))
+    }
+
+    /// 在窗口上监听属性变化（PropertyNotify）
+    fn select_property_events(&self, window: Window) -> Result<(), String> {
+        self.set_event_mask(window, EventMask::PROPERTY_CHANGE)?;
+        self.conn.flush().map_err(|e| format!("X11 flush failed: {}", e))
+    }
+
+    /// 设置窗口的事件掩码；窗口可能已被销毁，错误会以事件形式返回并被忽略
+    fn set_event_mask(&self, window: Window, mask: EventMask) -> Result<(), String> {
+        self.conn
+            .change_window_attributes(window, &ChangeWindowAttributesAux::new().event_mask(mask))
+            .map(|_| ())
+            .map_err(|e| format!("X11 request failed: {}", e))
+    }
+
+    /// 获取窗口所属进程：(进程名, PID, app_id)
+    fn window_process(&self, window: Window) -> Result<(String, i32, Option<String>), String> {
+        let pid = self.window_pid(window)?;
+        let class = self.window_class(window)?;
+
+        let process_name = pid
+            .and_then(process_name_from_proc)
+            .or_else(|| class.as_ref().map(|(instance, _)| instance.clone()))
+            .unwrap_or_else(|| "Unknown".to_string());
+        let app_id = class.map(|(_, class)| class);
+
+        Ok((process_name, pid.map(|p| p as i32).unwrap_or(0), app_id))
+    }
+
+    /// 组装窗口信息
+    fn window_info(&self, window: Window) -> Result<WindowInfo, String> {
+        let title = self.window_title(window)?;
+        let (process_name, pid, app_id) = self.window_process(window)?;
+        let icon_data = get_cached_window_icon(self, window, app_id.as_deref());
+
+        Ok(WindowInfo {
+            title,
+            icon_data,
+            process_name,
+            pid,
+            app_id,
+            private_browsing: self.is_private_browsing(window)?,
+        })
+    }
+
+    /// 组装窗口列表项（不读取图标）
+    fn window_entry(&self, window: Window, monitors: &[WindowBounds]) -> Result<WindowEntry, String> {
+        let title = self.window_title(window)?;
+        let (process_name, pid, app_id) = self.window_process(window)?;
+        let bounds = self.window_bounds(window)?;
+        let state = self.window_state(window)?;
+
+        Ok(WindowEntry {
+            title,
+            process_name,
+            pid,
+            app_id,
+            bounds: Some(bounds),
+            monitor: bounds.monitor_index(monitors),
+            workspace: self.window_desktop(window)?.map(|desktop| desktop.to_string()),
+            minimized: state.contains(&self.atoms._NET_WM_STATE_HIDDEN),
+            fullscreen: state.contains(&self.atoms._NET_WM_STATE_FULLSCREEN),
+            focused: false,
+            z_order: 0,
+            private_browsing: self.is_private_browsing(window)?,
+        })
+    }
+}
+
+/// 在全局 X11 连接上执行操作；连接失败或请求出错时丢弃连接，下次重新建立
+pub(super) fn with_context<T>(f: impl FnOnce(&X11Context) -> Result<T, String>) -> Result<T, String> {
+    let mut guard = X11_CONTEXT.lock().map_err(|e| format!("X11 连接锁定失败: {}", e))?;
+    if guard.is_none() {
+        *guard = Some(X11Context::connect()?);
+    }
+
+    let result = f(guard.as_ref().expect("X11 context initialized above"));
+    if result.is_err() {
+        *guard = None;
+    }
+    result
+}
+
+/// 获取当前前台窗口信息
+pub fn get_frontmost_window() -> Result<WindowInfo, String> {
+    with_context(|ctx| {
+        let window = ctx.active_window()?
+            .ok_or_else(|| "No active window".to_string())?;
+        ctx.window_info(window)
+    })
+}
+
+/// 获取所有窗口列表
+///
+/// 按叠放次序从上到下排列
+pub fn get_all_windows() -> Result<Vec<WindowEntry>, String> {
+    with_context(|ctx| {
+        let windows = ctx.client_list()?;
+        let active = ctx.active_window()?;
+        let monitors = ctx.monitors();
+
+        // 窗口可能在枚举过程中被关闭，忽略单个窗口的错误
+        let mut entries: Vec<WindowEntry> = windows
+            .into_iter()
+            .rev()
+            .filter_map(|w| {
+                let mut entry = ctx.window_entry(w, &monitors).ok()?;
+                entry.focused = Some(w) == active;
+                Some(entry)
+            })
+            .collect();
+        for (z_order, entry) in entries.iter_mut().enumerate() {
+            entry.z_order = z_order as u32;
+        }
+        Ok(entries)
+    })
+}
+
+/// 订阅前台窗口变化
+///
+/// 在独立连接上监听根窗口的 `_NET_ACTIVE_WINDOW` 与当前激活窗口的
+/// `_NET_WM_NAME` / `WM_NAME` 的 PropertyNotify 事件，每次变化推送最新窗口信息。
+pub fn subscribe_window_changes() -> Result<WindowEvents, String> {
+    let ctx = X11Context::connect()?;
+    ctx.select_property_events(ctx.root)?;
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("x11-focus-watcher".to_string())
+        .spawn(move || watch_window_changes(ctx, tx))
+        .map_err(|e| format!("Failed to spawn X11 watcher thread: {}", e))?;
+    Ok(rx)
+}
+
+/// 事件循环：接收端被丢弃或连接断开时退出
+fn watch_window_changes(ctx: X11Context, tx: mpsc::Sender<WindowInfo>) {
+    let mut active = None;
+    if !refresh_active_window(&ctx, &mut active, &tx) {
+        return;
+    }
+
+    loop {
+        let event = match ctx.conn.wait_for_event() {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!("X11 focus watcher stopped: {}", e);
+                return;
+            }
+        };
+
+        let Event::PropertyNotify(event) = event else { continue };
+        let focus_changed = event.window == ctx.root && event.atom == ctx.atoms._NET_ACTIVE_WINDOW;
+        let title_changed = Some(event.window) == active
+            && (event.atom == ctx.atoms._NET_WM_NAME || event.atom == u32::from(AtomEnum::WM_NAME));
+
+        if (focus_changed || title_changed) && !refresh_active_window(&ctx, &mut active, &tx) {
+            return;
+        }
+    }
+}
+
+/// 切换跟踪的激活窗口并推送其信息；返回 `false` 表示接收端已丢弃
+fn refresh_active_window(ctx: &X11Context, active: &mut Option<Window>, tx: &mpsc::Sender<WindowInfo>) -> bool {
+    let window = ctx.active_window().ok().flatten();
+    if window != *active {
+        if let Some(old) = active.take() {
+            let _ = ctx.set_event_mask(old, EventMask::NO_EVENT);
+        }
+        if let Some(new) = window {
+            let _ = ctx.select_property_events(new);
+        }
+        *active = window;
+    }
+    match window.and_then(|w| ctx.window_info(w).ok()) {
+        Some(info) => tx.send(info).is_ok(),
+        None => true,
+    }
+}
+
+/// 解析 WM_CLASS：两个以 NUL 结尾的字符串 (instance, class)
+fn parse_wm_class(value: &[u8]) -> Option<(String, String)> {
+    let mut parts = value
+        .split(|&b| b == 0)
+        .map(|s| String::from_utf8_lossy(s).into_owned());
+    let instance = parts.next().filter(|s| !s.is_empty())?;
+    let class = parts.next().filter(|s| !s.is_empty()).unwrap_or_else(|| instance.clone());
+    Some((instance, class))
+}
+
+/// 从 /proc/<pid>/comm 读取进程名
+fn process_name_from_proc(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|s| s.trim_end().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// 从 /proc/<pid>/cmdline 读取进程命令行（以 NUL 分隔的参数）
+fn command_line_from_proc(pid: u32) -> Option<Vec<String>> {
+    let data = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    Some(
+        data.split(|&b| b == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect(),
+    )
+}
+
+/// 从 _NET_WM_ICON 数据中选择最合适的尺寸
+///
+/// 数据格式为若干组 `width, height, width * height 个 ARGB 像素`。
+/// 优先选择不小于 `preferred` 的最小图标，否则选择最大的图标。
+fn select_icon(data: &[u32], preferred: u32) -> Option<(u32, u32, &[u32])> {
+    let mut best: Option<(u32, u32, &[u32])> = None;
+    let mut offset = 0;
+
+    while offset + 2 <= data.len() {
+        let (width, height) = (data[offset], data[offset + 1]);
+        let len = (width as usize).checked_mul(height as usize)?;
+        let start = offset + 2;
+        let end = start.checked_add(len)?;
+        if width == 0 || height == 0 || end > data.len() {
+            break;
+        }
+
+        let candidate = (width, height, &data[start..end]);
+        best = match best {
+            None => Some(candidate),
+            Some(current) => {
+                let current_size = current.0.max(current.1);
+                let size = width.max(height);
+                let better = if current_size >= preferred {
+                    size >= preferred && size < current_size
+                } else {
+                    size > current_size
+                };
+                if better { Some(candidate) } else { Some(current) }
+            }
+        };
+        offset = end;
+    }
+
+    best
+}
+
+/// 将 ARGB 像素 (每像素一个 u32) 编码为 PNG
+fn argb_to_png(width: u32, height: u32, pixels: &[u32]) -> Option<Vec<u8>> {
+    let rgba: Vec<u8> = pixels
+        .iter()
+        .flat_map(|&argb| {
+            let [a, r, g, b] = argb.to_be_bytes();
+            [r, g, b, a]
+        })
+        .collect();
+
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png)
+        .write_image(&rgba, width, height, ExtendedColorType::Rgba8)
+        .ok()?;
+    Some(png)
+}
+
+/// 带缓存的图标获取（带 LRU 淘汰）
+fn get_cached_window_icon(ctx: &X11Context, window: Window, app_id: Option<&str>) -> Option<Vec<u8>> {
+    let Some(cache_key) = app_id.map(str::to_string) else {
+        // 没有 WM_CLASS 时无法安全复用，直接读取
+        return ctx.window_icon_png(window).ok().flatten();
+    };
+
+    // 检查缓存
+    {
+        let cache = ICON_CACHE.lock().ok()?;
+        if let Some(data) = cache.as_ref().and_then(|map| map.get(&cache_key)) {
+            // 更新 LRU 顺序（移到末尾）
+            if let Ok(mut keys) = ICON_CACHE_KEYS.lock() {
+                if let Some(pos) = keys.iter().position(|k| k == &cache_key) {
+                    keys.remove(pos);
+                }
+                keys.push(cache_key.clone());
+            }
+            return Some(data.clone());
+        }
+    }
+
+    // 获取并缓存图标
+    let icon_data = ctx.window_icon_png(window).ok().flatten()?;
+
+    if let Ok(mut cache) = ICON_CACHE.lock() {
+        let map = cache.get_or_insert_with(HashMap::new);
+
+        // 如果缓存已满，移除最旧的项
+        if map.len() >= MAX_ICON_CACHE_SIZE {
+            if let Ok(mut keys) = ICON_CACHE_KEYS.lock() {
+                if !keys.is_empty() {
+                    let oldest_key = keys.remove(0);
+                    map.remove(&oldest_key);
+                }
+            }
+        }
+
+        map.insert(cache_key.clone(), icon_data.clone());
+
+        if let Ok(mut keys) = ICON_CACHE_KEYS.lock() {
+            keys.push(cache_key);
+        }
+    }
+
+    Some(icon_data)
+}
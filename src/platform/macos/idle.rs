@@ -0,0 +1,20 @@
+//! macOS 用户空闲时间检测模块
+//! 基于 CGEventSourceSecondsSinceLastEventType
+
+use std::time::Duration;
+
+/// kCGEventSourceStateCombinedSessionState
+const COMBINED_SESSION_STATE: i32 = 0;
+/// kCGAnyInputEventType
+const ANY_INPUT_EVENT_TYPE: u32 = !0;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+unsafe extern "C" {
+    fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+}
+
+/// 获取距离最后一次用户输入的时长
+pub fn get_idle_time() -> Result<Duration, String> {
+    let seconds = unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT_TYPE) };
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("无效的空闲时长 {}: {}", seconds, e))
+}
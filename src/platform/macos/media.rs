@@ -3,44 +3,11 @@
 
 use mediaremote_rs::{get_now_playing, is_playing, NowPlayingInfo};
 use objc2::rc::autoreleasepool;
-use serde::{Serialize, Deserialize};
+use crate::platform::{MediaMetadata, PlaybackState};
 use std::sync::{Mutex, Arc};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use base64::{Engine as _, engine::general_purpose};
 
-/// 播放状态信息
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct PlaybackState {
-    /// 是否正在播放
-    pub playing: bool,
-    /// 播放速率 (1.0 = 正常速度)
-    pub playback_rate: f64,
-    /// 已播放时长（秒）
-    pub elapsed_time: f64,
-}
-
-/// 媒体元数据
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct MediaMetadata {
-    /// 应用 Bundle ID
-    pub bundle_identifier: Option<String>,
-    /// 曲目标题
-    pub title: Option<String>,
-    /// 艺术家
-    pub artist: Option<String>,
-    /// 专辑
-    pub album: Option<String>,
-    /// 总时长（秒）
-    pub duration: f64,
-    /// 封面数据 (原始二进制)
-    #[serde(skip)]
-    pub artwork_data: Option<Arc<Vec<u8>>>,
-    /// 封面 MIME 类型
-    pub artwork_mime_type: Option<String>,
-    /// 内容标识符
-    pub content_item_identifier: Option<String>,
-}
-
 /// 媒体信息缓存
 struct MediaCache {
     metadata: Option<MediaMetadata>,
@@ -122,27 +89,22 @@ fn update_cache_from_info(info: &NowPlayingInfo, cache: &mut MediaCache) {
     
     // 封面数据更新逻辑：
     // 1. 如果歌曲没变（key 相同）且已有缓存，优先复用缓存（避免重复解码）
-    // 2. 如果有新的封面数据，解码并使用
+    // 2. 如果有新的封面数据，解码并使用（MIME 类型由 set_artwork 根据文件头识别）
     // 3. 否则清空封面
-    let (artwork_data, artwork_mime_type) = if !key_changed && cache.metadata.as_ref().and_then(|m| m.artwork_data.as_ref()).is_some() {
+    let artwork_data = if !key_changed && cache.metadata.as_ref().and_then(|m| m.artwork_data.as_ref()).is_some() {
         // 歌曲没变，复用缓存的封面
-        let cached_artwork = cache.metadata.as_ref().and_then(|m| m.artwork_data.clone());
-        let cached_mime = cache.metadata.as_ref().and_then(|m| m.artwork_mime_type.clone());
-        (cached_artwork, cached_mime)
+        cache.metadata.as_ref().and_then(|m| m.artwork_data.clone())
     } else if let Some(base64_data) = &info.artwork_data {
         // 有新的封面数据，解码 Base64
         cache.artwork_key = Some(new_artwork_key.clone());
-        match general_purpose::STANDARD.decode(base64_data) {
-            Ok(bytes) => (Some(Arc::new(bytes)), info.artwork_mime_type.clone()),
-            Err(_) => (None, None)
-        }
+        general_purpose::STANDARD.decode(base64_data).ok().map(Arc::new)
     } else {
         // 歌曲变了但没有封面数据，更新 key 并清空封面
         cache.artwork_key = Some(new_artwork_key.clone());
-        (None, None)
+        None
     };
 
-    cache.metadata = Some(MediaMetadata {
+    let mut metadata = MediaMetadata {
         bundle_identifier: if info.bundle_identifier.is_empty() {
             None
         } else {
@@ -156,8 +118,6 @@ fn update_cache_from_info(info: &NowPlayingInfo, cache: &mut MediaCache) {
         artist, // 使用智能选择的艺术家信息
         album: info.album.clone(),
         duration: info.duration.unwrap_or(0.0),
-        artwork_data,
-        artwork_mime_type,
         // 生成内容标识符：使用 bundle_id + title + album 的组合
         content_item_identifier: Some(format!(
             "{}:{}:{}",
@@ -165,12 +125,16 @@ fn update_cache_from_info(info: &NowPlayingInfo, cache: &mut MediaCache) {
             info.title,
             info.album.as_deref().unwrap_or("")
         )),
-    });
+        ..Default::default()
+    };
+    metadata.set_artwork(artwork_data);
+    cache.metadata = Some(metadata);
 
     cache.playback_state = Some(PlaybackState {
         playing: info.playing,
         playback_rate: info.playback_rate.unwrap_or(if info.playing { 1.0 } else { 0.0 }),
         elapsed_time: info.elapsed_time.unwrap_or(0.0),
+        timestamp: SystemTime::now(),
     });
 
     cache.last_update = Instant::now();
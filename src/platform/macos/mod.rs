@@ -1,9 +1,91 @@
 //! macOS 平台实现
 
 mod accessibility;
+pub mod idle;
 pub mod media;
 mod window;
 
 pub use accessibility::*;
-pub use media::{MediaMetadata, PlaybackState, get_media_metadata, get_playback_state};
+pub use idle::get_idle_time;
+pub use media::{get_media_metadata, get_playback_state};
 pub use window::get_frontmost_window_info_sync;
+
+use super::{IdleSource, MediaMetadata, MediaSource, PlatformProvider, PlaybackState, SessionSource, WindowEntry, WindowInfo, WindowSource};
+use objc2::rc::autoreleasepool;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// macOS 平台实现 (Accessibility + MediaRemote)
+pub struct MacosPlatform;
+
+impl WindowSource for MacosPlatform {
+    fn frontmost_window(&self) -> Result<WindowInfo, String> {
+        get_frontmost_window_info_sync()
+    }
+
+    /// 目前仅返回前台窗口
+    fn all_windows(&self) -> Result<Vec<WindowEntry>, String> {
+        // 尚未接入 CGWindowListCopyWindowInfo，不含几何信息
+        match get_frontmost_window_info_sync() {
+            Ok(info) => Ok(vec![WindowEntry {
+                title: info.title,
+                process_name: info.process_name,
+                pid: info.pid,
+                app_id: info.app_id,
+                bounds: None,
+                monitor: None,
+                workspace: None,
+                minimized: false,
+                fullscreen: false,
+                focused: true,
+                z_order: 0,
+                private_browsing: info.private_browsing,
+            }]),
+            Err(_) => Ok(Vec::new()),
+        }
+    }
+}
+
+impl MediaSource for MacosPlatform {
+    fn now_playing(&self) -> Result<Option<(MediaMetadata, PlaybackState)>, String> {
+        autoreleasepool(|_| {
+            let Some(metadata) = get_media_metadata()? else { return Ok(None) };
+            Ok(get_playback_state()?.map(|state| (metadata, state)))
+        })
+    }
+}
+
+impl IdleSource for MacosPlatform {
+    fn idle_time(&self) -> Result<Duration, String> {
+        get_idle_time()
+    }
+}
+
+/// 暂未接入系统的锁屏 / 休眠通知
+impl SessionSource for MacosPlatform {}
+
+impl PlatformProvider for MacosPlatform {
+    fn request_permissions(&self) -> Result<bool, String> {
+        request_accessibility_permission()
+    }
+
+    fn check_permissions(&self) -> bool {
+        check_accessibility_permission()
+    }
+
+    fn window_source(&self) -> Arc<dyn WindowSource> {
+        Arc::new(MacosPlatform)
+    }
+
+    fn media_source(&self) -> Arc<dyn MediaSource> {
+        Arc::new(MacosPlatform)
+    }
+
+    fn idle_source(&self) -> Arc<dyn IdleSource> {
+        Arc::new(MacosPlatform)
+    }
+
+    fn session_source(&self) -> Arc<dyn SessionSource> {
+        Arc::new(MacosPlatform)
+    }
+}
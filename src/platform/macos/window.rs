@@ -99,6 +99,7 @@ pub fn get_frontmost_window_info_sync() -> Result<WindowInfo, String> {
             process_name,
             pid,
             app_id: bundle_id,
+            private_browsing: false,
         };
 
         // 更新缓存
@@ -0,0 +1,43 @@
+//! 媒体会话变化时间跟踪
+//! 供只能读取快照的平台后端计算 [`MediaSession::last_changed`](super::MediaSession::last_changed)
+
+use super::{MediaMetadata, PlaybackState};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::SystemTime;
+
+/// 记录每个会话的曲目与播放状态指纹，以及指纹最近一次变化的时间
+#[derive(Default)]
+pub(crate) struct MediaChangeTracker {
+    seen: HashMap<String, (u64, SystemTime)>,
+}
+
+impl MediaChangeTracker {
+    /// 记录一次会话快照，返回该会话最近一次变化的时间
+    ///
+    /// 播放位置不计入指纹，首次出现的会话视为刚刚变化
+    pub(crate) fn observe(&mut self, id: &str, metadata: &MediaMetadata, state: &PlaybackState) -> SystemTime {
+        let mut hasher = DefaultHasher::new();
+        metadata.content_item_identifier.hash(&mut hasher);
+        metadata.title.hash(&mut hasher);
+        state.playing.hash(&mut hasher);
+        ((state.playback_rate * 100.0) as i64).hash(&mut hasher);
+        let fingerprint = hasher.finish();
+
+        match self.seen.get(id) {
+            Some(&(seen, changed_at)) if seen == fingerprint => changed_at,
+            _ => {
+                let now = SystemTime::now();
+                self.seen.insert(id.to_string(), (fingerprint, now));
+                now
+            }
+        }
+    }
+
+    /// 丢弃不再存在的会话
+    pub(crate) fn retain<'a>(&mut self, ids: impl IntoIterator<Item = &'a str>) {
+        let ids: Vec<&str> = ids.into_iter().collect();
+        self.seen.retain(|id, _| ids.contains(&id.as_str()));
+    }
+}
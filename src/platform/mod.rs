@@ -2,6 +2,8 @@
 //! 提供跨平台的窗口和媒体信息获取接口
 
 use serde::{Serialize, Deserialize};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 #[cfg(target_os = "macos")]
 pub mod macos;
@@ -9,11 +11,16 @@ pub mod macos;
 #[cfg(target_os = "windows")]
 pub mod windows;
 
-
-
 #[cfg(target_os = "linux")]
 pub mod linux;
 
+pub mod artwork;
+#[cfg(any(test, feature = "fake-platform"))]
+pub mod fake;
+pub(crate) mod media_tracker;
+
+pub use artwork::{sniff_image, ImageInfo};
+
 // 重新导出当前平台的实现
 #[cfg(target_os = "macos")]
 #[allow(unused_imports)]
@@ -40,22 +47,307 @@ pub struct WindowInfo {
     pub pid: i32,
     /// 应用 Bundle ID (macOS) 或可执行路径
     pub app_id: Option<String>,
+    /// 平台层（窗口类 / 角色、进程命令行）识别出的浏览器隐私窗口；标题标记由上报层识别
+    ///
+    /// 目前仅 Linux 实现，Windows 与 macOS 上恒为 `false`
+    #[serde(default)]
+    pub private_browsing: bool,
+}
+
+/// 窗口在桌面坐标系中的位置与大小（像素）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowBounds {
+    /// 与另一矩形重叠部分的面积
+    pub fn overlap_area(&self, other: &WindowBounds) -> u64 {
+        let left = self.x.max(other.x) as i64;
+        let top = self.y.max(other.y) as i64;
+        let right = (self.x as i64 + self.width as i64).min(other.x as i64 + other.width as i64);
+        let bottom = (self.y as i64 + self.height as i64).min(other.y as i64 + other.height as i64);
+        if right <= left || bottom <= top {
+            return 0;
+        }
+        ((right - left) * (bottom - top)) as u64
+    }
+
+    /// 所在显示器序号：重叠面积最大的显示器，完全不重叠时返回 `None`
+    pub fn monitor_index(&self, monitors: &[WindowBounds]) -> Option<u32> {
+        monitors
+            .iter()
+            .enumerate()
+            .map(|(index, monitor)| (index, self.overlap_area(monitor)))
+            .filter(|&(_, area)| area > 0)
+            .max_by_key(|&(_, area)| area)
+            .map(|(index, _)| index as u32)
+    }
+}
+
+/// 顶层窗口列表中的一项
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct WindowEntry {
+    /// 窗口标题
+    pub title: String,
+    /// 进程名称
+    pub process_name: String,
+    /// 进程 ID
+    pub pid: i32,
+    /// 应用标识（同 [`WindowInfo::app_id`]）
+    pub app_id: Option<String>,
+    /// 窗口位置与大小
+    pub bounds: Option<WindowBounds>,
+    /// 所在显示器序号
+    pub monitor: Option<u32>,
+    /// 所在虚拟桌面（X11 为 `_NET_WM_DESKTOP` 序号，Windows 为虚拟桌面 GUID），在所有桌面显示时为 `None`
+    pub workspace: Option<String>,
+    /// 是否最小化
+    pub minimized: bool,
+    /// 是否全屏
+    pub fullscreen: bool,
+    /// 是否为前台窗口
+    pub focused: bool,
+    /// 叠放次序，0 为最上层
+    pub z_order: u32,
+    /// 是否为浏览器隐私窗口（同 [`WindowInfo::private_browsing`]）
+    #[serde(default)]
+    pub private_browsing: bool,
+}
+
+/// 播放状态信息
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlaybackState {
+    /// 是否正在播放
+    pub playing: bool,
+    /// 播放速率 (1.0 = 正常速度)
+    pub playback_rate: f64,
+    /// 已播放时长（秒）
+    pub elapsed_time: f64,
+    /// `elapsed_time` 的采样时间（位置锚点），之后的位置按播放速率推算
+    pub timestamp: SystemTime,
+}
+
+impl PlaybackState {
+    /// 推算 `at` 时刻的播放位置（秒）
+    ///
+    /// 暂停时位置不变；`at` 早于锚点时按锚点处理
+    pub fn position_at(&self, at: SystemTime) -> f64 {
+        if !self.playing {
+            return self.elapsed_time;
+        }
+        let since = at.duration_since(self.timestamp).unwrap_or_default().as_secs_f64();
+        self.elapsed_time + since * self.playback_rate
+    }
+}
+
+/// 媒体内容类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+    /// 平台未提供
+    #[default]
+    Unknown,
+    Music,
+    Video,
+    Podcast,
+}
+
+/// 媒体元数据
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MediaMetadata {
+    /// 应用 Bundle ID (macOS)、AUMID (Windows) 或 MPRIS 播放器名 (Linux)
+    pub bundle_identifier: Option<String>,
+    /// 曲目标题
+    pub title: Option<String>,
+    /// 艺术家
+    pub artist: Option<String>,
+    /// 专辑
+    pub album: Option<String>,
+    /// 总时长（秒）
+    pub duration: f64,
+    /// 封面数据 (原始二进制)
+    #[serde(skip)]
+    pub artwork_data: Option<Arc<Vec<u8>>>,
+    /// 封面 MIME 类型（由文件头识别）
+    pub artwork_mime_type: Option<String>,
+    /// 封面宽度（像素）
+    pub artwork_width: Option<u32>,
+    /// 封面高度（像素）
+    pub artwork_height: Option<u32>,
+    /// 内容标识符
+    pub content_item_identifier: Option<String>,
+    /// 内容类型（SMTC PlaybackType / MPRIS 元数据推断）
+    #[serde(default)]
+    pub kind: MediaKind,
+}
+
+impl MediaMetadata {
+    /// 设置封面数据，MIME 类型与尺寸由文件头识别
+    ///
+    /// 无法识别格式的数据会被丢弃，避免向服务端上报错误标注的图片
+    pub fn set_artwork(&mut self, data: Option<Arc<Vec<u8>>>) {
+        match data.and_then(|data| sniff_image(&data).map(|info| (data, info))) {
+            Some((data, info)) => {
+                self.artwork_data = Some(data);
+                self.artwork_mime_type = Some(info.mime_type.to_string());
+                self.artwork_width = Some(info.width);
+                self.artwork_height = Some(info.height);
+            }
+            None => {
+                self.artwork_data = None;
+                self.artwork_mime_type = None;
+                self.artwork_width = None;
+                self.artwork_height = None;
+            }
+        }
+    }
+}
+
+/// 前台窗口变化事件流
+///
+/// 每当焦点切换或前台窗口标题变化时推送一次最新的 [`WindowInfo`]，
+/// 接收端被丢弃后平台侧的监听线程会自行退出。
+pub type WindowEvents = std::sync::mpsc::Receiver<WindowInfo>;
+
+/// 窗口信息来源
+pub trait WindowSource: Send + Sync {
+    /// 获取当前前台窗口信息
+    fn frontmost_window(&self) -> Result<WindowInfo, String>;
+
+    /// 获取所有顶层窗口，按叠放次序从上到下排列
+    fn all_windows(&self) -> Result<Vec<WindowEntry>, String>;
+
+    /// 订阅前台窗口变化
+    ///
+    /// 订阅成功后会先推送一次当前窗口。平台不支持事件通知时返回 `None`，
+    /// 调用方应回退到定时调用 [`WindowSource::frontmost_window`]。
+    fn subscribe(&self) -> Option<WindowEvents> {
+        None
+    }
+}
+
+/// 单个媒体会话（一个播放器或一个 SMTC 会话）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MediaSession {
+    /// 会话标识（MPRIS 总线名 / SMTC AUMID）
+    pub id: String,
+    /// 媒体元数据
+    pub metadata: MediaMetadata,
+    /// 播放状态
+    pub state: PlaybackState,
+    /// 最近一次曲目或播放状态（播放/暂停、速率）变化的时间
+    pub last_changed: SystemTime,
+}
+
+/// 媒体播放变化事件流
+///
+/// 任一会话的曲目、播放/暂停、速率或播放位置跳转时推送一次全部会话，空列表表示已无播放。
+/// 正常播放过程中位置的推进不会触发推送，需要时用 [`PlaybackState::position_at`] 推算。
+pub type MediaEvents = std::sync::mpsc::Receiver<Vec<MediaSession>>;
+
+/// 媒体播放信息来源
+pub trait MediaSource: Send + Sync {
+    /// 获取当前播放的媒体元数据与播放状态，无播放时返回 `None`
+    ///
+    /// 存在多个会话时由平台决定返回哪一个（通常是系统认为的“当前会话”）
+    fn now_playing(&self) -> Result<Option<(MediaMetadata, PlaybackState)>, String>;
+
+    /// 获取所有播放中或暂停的会话
+    ///
+    /// 平台只能提供单个会话时，默认实现把 [`MediaSource::now_playing`] 包装为一个会话
+    fn sessions(&self) -> Result<Vec<MediaSession>, String> {
+        Ok(self.now_playing()?.into_iter().map(|(metadata, state)| MediaSession {
+            id: metadata.bundle_identifier.clone().unwrap_or_default(),
+            last_changed: state.timestamp,
+            metadata,
+            state,
+        }).collect())
+    }
+
+    /// 订阅媒体播放变化
+    ///
+    /// 订阅成功后会先推送一次当前状态。平台不支持事件通知时返回 `None`，
+    /// 调用方应回退到定时调用 [`MediaSource::now_playing`]。
+    fn subscribe(&self) -> Option<MediaEvents> {
+        None
+    }
+}
+
+/// 用户空闲时间来源
+pub trait IdleSource: Send + Sync {
+    /// 距离最后一次键盘 / 鼠标输入的时长
+    fn idle_time(&self) -> Result<Duration, String>;
+}
+
+/// 会话状态变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// 屏幕已锁定
+    Locked,
+    /// 屏幕已解锁
+    Unlocked,
+    /// 系统即将休眠
+    Suspending,
+    /// 系统从休眠中恢复
+    Resumed,
+    /// 网络连接已变化（切换 Wi-Fi、接入 VPN 等），仅用于提示重连
+    NetworkChanged,
+}
+
+/// 会话状态变化事件流
+pub type SessionEvents = std::sync::mpsc::Receiver<SessionEvent>;
+
+/// 会话状态（锁屏 / 休眠）来源
+pub trait SessionSource: Send + Sync {
+    /// 订阅会话状态变化，平台不支持时返回 `None`
+    fn subscribe(&self) -> Option<SessionEvents> {
+        None
+    }
 }
 
 /// 平台功能 trait
 ///
-/// 注意: PlaybackState 和 MediaMetadata 类型由各平台自行定义
-#[allow(unused)]
-pub trait PlatformProvider {
+/// 各平台提供一个实现，`Reporter` 在构造时接收 `Arc<dyn PlatformProvider>`，
+/// 监控流程只通过这里取得的各个 `*Source` 访问系统。
+pub trait PlatformProvider: Send + Sync {
     /// 请求必要的权限
-    fn request_permissions() -> Result<bool, String>;
+    fn request_permissions(&self) -> Result<bool, String>;
 
     /// 检查权限状态
-    fn check_permissions() -> bool;
+    fn check_permissions(&self) -> bool;
 
-    /// 获取当前前台窗口信息
-    fn get_frontmost_window() -> Result<WindowInfo, String>;
+    /// 窗口信息来源
+    fn window_source(&self) -> Arc<dyn WindowSource>;
+
+    /// 媒体播放信息来源
+    fn media_source(&self) -> Arc<dyn MediaSource>;
+
+    /// 用户空闲时间来源
+    fn idle_source(&self) -> Arc<dyn IdleSource>;
+
+    /// 会话状态来源
+    fn session_source(&self) -> Arc<dyn SessionSource>;
+}
+
+/// 当前平台的原生实现
+pub fn native() -> Arc<dyn PlatformProvider> {
+    #[cfg(target_os = "macos")]
+    {
+        Arc::new(macos::MacosPlatform)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        Arc::new(windows::WindowsPlatform)
+    }
 
-    /// 获取所有窗口列表
-    fn get_all_windows() -> Result<Vec<WindowInfo>, String>;
+    #[cfg(target_os = "linux")]
+    {
+        Arc::new(linux::LinuxPlatform)
+    }
 }
@@ -0,0 +1,22 @@
+//! Windows 用户空闲时间检测模块
+//! 基于 GetLastInputInfo
+
+use std::time::Duration;
+use windows::Win32::System::SystemInformation::GetTickCount;
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+/// 获取距离最后一次用户输入的时长
+pub fn get_idle_time() -> Result<Duration, String> {
+    unsafe {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetLastInputInfo(&mut info).as_bool() {
+            return Err("GetLastInputInfo 失败".to_string());
+        }
+        // 两者均为开机以来的毫秒数（约 49.7 天回绕），用 wrapping_sub 处理回绕
+        let idle_ms = GetTickCount().wrapping_sub(info.dwTime);
+        Ok(Duration::from_millis(idle_ms as u64))
+    }
+}
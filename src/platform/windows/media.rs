@@ -1,50 +1,21 @@
 //! Windows 媒体播放信息获取模块
 //! 基于 Windows.Media.Control (SMTC)
 
-use serde::{Serialize, Deserialize};
+use crate::platform::media_tracker::MediaChangeTracker;
+use crate::platform::{MediaKind, MediaMetadata, MediaSession, PlaybackState};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use windows::core::{Result, HSTRING};
 use windows::Media::Control::{
+    GlobalSystemMediaTransportControlsSession,
     GlobalSystemMediaTransportControlsSessionManager,
     GlobalSystemMediaTransportControlsSessionPlaybackInfo,
     GlobalSystemMediaTransportControlsSessionTimelineProperties,
     GlobalSystemMediaTransportControlsSessionPlaybackStatus,
 };
+use windows::Media::MediaPlaybackType;
 use windows::Storage::Streams::DataReader;
 use tokio::runtime::Runtime;
-use base64::Engine;
-use base64::engine::general_purpose::STANDARD as BASE64;
-
-/// 播放状态信息
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct PlaybackState {
-    /// 是否正在播放
-    pub playing: bool,
-    /// 播放速率 (1.0 = 正常速度)
-    pub playback_rate: f64,
-    /// 已播放时长（秒）
-    pub elapsed_time: f64,
-}
-
-/// 媒体元数据
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct MediaMetadata {
-    /// 应用 Bundle ID (Windows 上为 AUMID)
-    pub bundle_identifier: Option<String>,
-    /// 曲目标题
-    pub title: Option<String>,
-    /// 艺术家
-    pub artist: Option<String>,
-    /// 专辑
-    pub album: Option<String>,
-    /// 总时长（秒）
-    pub duration: f64,
-    /// 封面数据 (Base64 编码)
-    pub artwork_data: Option<String>,
-    /// 封面 MIME 类型
-    pub artwork_mime_type: Option<String>,
-    /// 内容标识符
-    pub content_item_identifier: Option<String>,
-}
 
 /// 获取当前播放状态
 pub fn get_playback_state() -> std::result::Result<Option<PlaybackState>, String> {
@@ -77,17 +48,66 @@ pub fn get_media_metadata() -> std::result::Result<Option<MediaMetadata>, String
     }
 }
 
+/// 各会话最近一次变化的时间（SMTC 只提供快照，需要自行比较）
+static SESSION_TRACKER: Mutex<Option<MediaChangeTracker>> = Mutex::new(None);
+
+/// 获取所有播放中或暂停的 SMTC 会话
+pub fn get_media_sessions() -> std::result::Result<Vec<MediaSession>, String> {
+    let rt = Runtime::new().map_err(|e| format!("Failed to create Tokio runtime: {}", e))?;
+    let snapshots = rt.block_on(get_smtc_sessions()).unwrap_or_default();
+
+    let mut guard = SESSION_TRACKER.lock().map_err(|e| format!("缓存锁定失败: {}", e))?;
+    let tracker = guard.get_or_insert_with(MediaChangeTracker::default);
+    let sessions: Vec<MediaSession> = snapshots
+        .into_iter()
+        .map(|(id, metadata, state)| MediaSession {
+            last_changed: tracker.observe(&id, &metadata, &state),
+            id,
+            metadata,
+            state,
+        })
+        .collect();
+    tracker.retain(sessions.iter().map(|s| s.id.as_str()));
+    Ok(sessions)
+}
+
 async fn get_smtc_info() -> Result<Option<(MediaMetadata, PlaybackState)>> {
     let session_manager_async = GlobalSystemMediaTransportControlsSessionManager::RequestAsync()?;
     let session_manager = session_manager_async.await?;
     let current_session = session_manager.GetCurrentSession()?;
+    read_session(&current_session).await
+}
 
+/// 枚举全部会话，返回 (AUMID, 元数据, 播放状态)；读取失败的单个会话会被跳过
+async fn get_smtc_sessions() -> Result<Vec<(String, MediaMetadata, PlaybackState)>> {
+    let session_manager_async = GlobalSystemMediaTransportControlsSessionManager::RequestAsync()?;
+    let session_manager = session_manager_async.await?;
+
+    let mut sessions = Vec::new();
+    for session in session_manager.GetSessions()? {
+        let Ok(id) = session.SourceAppUserModelId() else { continue };
+        if let Ok(Some((metadata, state))) = read_session(&session).await {
+            sessions.push((id.to_string_lossy(), metadata, state));
+        }
+    }
+    Ok(sessions)
+}
+
+/// 读取单个会话；已关闭或停止的会话视为无播放
+async fn read_session(current_session: &GlobalSystemMediaTransportControlsSession) -> Result<Option<(MediaMetadata, PlaybackState)>> {
     // 获取播放信息
     let playback_info: GlobalSystemMediaTransportControlsSessionPlaybackInfo = current_session.GetPlaybackInfo()?;
     let playback_status = playback_info.PlaybackStatus()?;
     
     let is_playing = playback_status == GlobalSystemMediaTransportControlsSessionPlaybackStatus::Playing;
 
+    // 内容类型：部分应用不提供 PlaybackType
+    let kind = match playback_info.PlaybackType().and_then(|t| t.Value()) {
+        Ok(MediaPlaybackType::Music) => MediaKind::Music,
+        Ok(MediaPlaybackType::Video) => MediaKind::Video,
+        _ => MediaKind::Unknown,
+    };
+
     // 如果不是播放状态，且不是暂停状态，可能认为没有活跃媒体
     // 但为了更像 macOS 的行为，只要有 Session 且有内容，即使暂停也应该返回
     // 不过参考代码中只在 Playing 时返回，这里我们放宽一点，Paused 也返回
@@ -107,6 +127,15 @@ async fn get_smtc_info() -> Result<Option<(MediaMetadata, PlaybackState)>> {
     let duration = duration_ticks as f64 / TICKS_PER_SECOND as f64;
     let elapsed_time = elapsed_time_ticks as f64 / TICKS_PER_SECOND as f64;
 
+    // Position 是 LastUpdatedTime 时刻的位置，以它作为锚点（FILETIME 刻度，起点 1601-01-01）
+    const FILETIME_UNIX_EPOCH_TICKS: i64 = 116_444_736_000_000_000;
+    let timestamp = timeline_properties
+        .LastUpdatedTime()
+        .ok()
+        .and_then(|t| u64::try_from(t.UniversalTime - FILETIME_UNIX_EPOCH_TICKS).ok())
+        .map(|ticks| SystemTime::UNIX_EPOCH + std::time::Duration::from_nanos(ticks * 100))
+        .unwrap_or_else(SystemTime::now);
+
     // 获取媒体属性
     let media_properties_operation = current_session.TryGetMediaPropertiesAsync()?;
     let media_properties = media_properties_operation.await?;
@@ -118,7 +147,6 @@ async fn get_smtc_info() -> Result<Option<(MediaMetadata, PlaybackState)>> {
 
     // 获取封面
     let mut artwork_data = None;
-    let mut artwork_mime_type = None;
 
     if let Ok(thumbnail_ref) = media_properties.Thumbnail() {
         if let Ok(thumbnail_stream_async) = thumbnail_ref.OpenReadAsync() {
@@ -131,11 +159,8 @@ async fn get_smtc_info() -> Result<Option<(MediaMetadata, PlaybackState)>> {
                         if let Ok(_) = data_reader.LoadAsync(stream_size_u32)?.await {
                             let mut buffer = vec![0u8; stream_size_u32 as usize];
                             if let Ok(_) = data_reader.ReadBytes(&mut buffer) {
-                                // 转换为 Base64
-                                artwork_data = Some(BASE64.encode(&buffer));
-                                // 简单猜测 MIME，通常是 PNG 或 JPEG，这里假设是 PNG 因为参考代码保存为 .png
-                                // 但实际上可能是 jpg。Windows Thumbnail 流通常有 ContentType 属性，但这里简化处理
-                                artwork_mime_type = Some("image/png".to_string());
+                                // 缩略图可能是 PNG 也可能是 JPEG，MIME 类型由 set_artwork 根据文件头识别
+                                artwork_data = Some(Arc::new(buffer));
                             }
                         }
                     }
@@ -144,21 +169,28 @@ async fn get_smtc_info() -> Result<Option<(MediaMetadata, PlaybackState)>> {
         }
     }
 
-    let metadata = MediaMetadata {
-        bundle_identifier: Some(source_app_name_hstring.to_string_lossy()),
-        title: Some(title_hstring.to_string_lossy()),
+    let bundle_identifier = source_app_name_hstring.to_string_lossy();
+    let title = title_hstring.to_string_lossy();
+    let album = album_hstring.to_string_lossy();
+
+    let mut metadata = MediaMetadata {
+        // 生成内容标识符：与 macOS 一致，使用 AUMID + title + album 的组合
+        content_item_identifier: Some(format!("{}:{}:{}", bundle_identifier, title, album)),
+        bundle_identifier: Some(bundle_identifier),
+        title: Some(title),
         artist: Some(artist_hstring.to_string_lossy()),
-        album: Some(album_hstring.to_string_lossy()),
+        album: Some(album),
         duration,
-        artwork_data,
-        artwork_mime_type,
-        content_item_identifier: None,
+        kind,
+        ..Default::default()
     };
+    metadata.set_artwork(artwork_data);
 
     let state = PlaybackState {
         playing: is_playing,
         playback_rate: 1.0, // 简化处理，假设为 1.0
         elapsed_time,
+        timestamp,
     };
 
     Ok(Some((metadata, state)))
@@ -1,11 +1,17 @@
 //! Windows 平台实现
 
+pub mod idle;
 pub mod media;
 pub mod window;
 
-pub use media::{get_media_metadata, get_playback_state, MediaMetadata, PlaybackState};
+pub use idle::get_idle_time;
+pub use media::{get_media_metadata, get_playback_state, get_media_sessions};
 pub use window::{get_frontmost_window, get_all_windows};
 
+use super::{IdleSource, MediaMetadata, MediaSession, MediaSource, PlatformProvider, PlaybackState, SessionSource, WindowEntry, WindowInfo, WindowSource};
+use std::sync::Arc;
+use std::time::Duration;
+
 /// 请求必要的权限 (Windows 通常不需要像 macOS 那样显式请求权限)
 pub fn request_permissions() -> Result<bool, String> {
     Ok(true)
@@ -15,3 +21,62 @@ pub fn request_permissions() -> Result<bool, String> {
 pub fn check_permissions() -> bool {
     true
 }
+
+/// Windows 平台实现 (Win32 + SMTC)
+pub struct WindowsPlatform;
+
+impl WindowSource for WindowsPlatform {
+    fn frontmost_window(&self) -> Result<WindowInfo, String> {
+        get_frontmost_window()
+    }
+
+    fn all_windows(&self) -> Result<Vec<WindowEntry>, String> {
+        get_all_windows()
+    }
+}
+
+impl MediaSource for WindowsPlatform {
+    fn now_playing(&self) -> Result<Option<(MediaMetadata, PlaybackState)>, String> {
+        let Some(metadata) = get_media_metadata()? else { return Ok(None) };
+        Ok(get_playback_state()?.map(|state| (metadata, state)))
+    }
+
+    fn sessions(&self) -> Result<Vec<MediaSession>, String> {
+        get_media_sessions()
+    }
+}
+
+impl IdleSource for WindowsPlatform {
+    fn idle_time(&self) -> Result<Duration, String> {
+        get_idle_time()
+    }
+}
+
+/// 暂未接入系统的锁屏 / 休眠通知
+impl SessionSource for WindowsPlatform {}
+
+impl PlatformProvider for WindowsPlatform {
+    fn request_permissions(&self) -> Result<bool, String> {
+        request_permissions()
+    }
+
+    fn check_permissions(&self) -> bool {
+        check_permissions()
+    }
+
+    fn window_source(&self) -> Arc<dyn WindowSource> {
+        Arc::new(WindowsPlatform)
+    }
+
+    fn media_source(&self) -> Arc<dyn MediaSource> {
+        Arc::new(WindowsPlatform)
+    }
+
+    fn idle_source(&self) -> Arc<dyn IdleSource> {
+        Arc::new(WindowsPlatform)
+    }
+
+    fn session_source(&self) -> Arc<dyn SessionSource> {
+        Arc::new(WindowsPlatform)
+    }
+}
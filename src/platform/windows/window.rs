@@ -1,11 +1,19 @@
 //! Windows 窗口信息获取模块
 
-use crate::platform::WindowInfo;
+use crate::platform::{WindowBounds, WindowEntry, WindowInfo};
 use std::ffi::OsString;
 use std::os::windows::ffi::OsStringExt;
-use windows::Win32::Foundation::{HWND, CloseHandle};
+use windows::core::BOOL;
+use windows::Win32::Foundation::{HWND, CloseHandle, LPARAM, RECT};
+use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
+use windows::Win32::Graphics::Gdi::{
+    EnumDisplayMonitors, GetMonitorInfoW, MonitorFromWindow, HDC, HMONITOR, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED};
+use windows::Win32::UI::Shell::{IVirtualDesktopManager, VirtualDesktopManager};
 use windows::Win32::UI::WindowsAndMessaging::{
-    GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId,
+    EnumWindows, GetForegroundWindow, GetWindow, GetWindowLongW, GetWindowRect, GetWindowTextW,
+    GetWindowThreadProcessId, IsIconic, IsWindowVisible, GWL_EXSTYLE, GW_OWNER, WS_EX_TOOLWINDOW,
 };
 use windows::Win32::System::Threading::{
     OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
@@ -20,45 +28,11 @@ pub fn get_frontmost_window() -> Result<WindowInfo, String> {
             return Err("No foreground window".to_string());
         }
 
-        let mut window_title: [u16; 255] = [0; 255];
-        GetWindowTextW(h_wnd, &mut window_title);
+        let window_title_str = window_title(h_wnd);
 
         let mut process_id: u32 = 0;
         GetWindowThreadProcessId(h_wnd, Some(&mut process_id));
-
-        let process_handle = OpenProcess(
-            PROCESS_QUERY_INFORMATION | PROCESS_VM_READ,
-            false,
-            process_id
-        );
-
-        let mut process_name: [u16; 255] = [0; 255];
-        let process_name_str = if let Ok(handle) = process_handle {
-            let result = GetModuleBaseNameW(
-                handle,
-                None,
-                &mut process_name
-            );
-            let _ = CloseHandle(handle);
-
-            if result > 0 && process_name[0] != 0 {
-                OsString::from_wide(&process_name)
-                    .to_string_lossy()
-                    .into_owned()
-                    .trim_matches(char::from(0))
-                    .to_string()
-            } else {
-                String::from("Unknown")
-            }
-        } else {
-            String::from("Unknown")
-        };
-
-        let window_title_str = OsString::from_wide(&window_title)
-            .to_string_lossy()
-            .into_owned()
-            .trim_matches(char::from(0))
-            .to_string();
+        let process_name_str = process_name(process_id);
 
         // 获取窗口图标
         let icon_data = get_window_icon(&window_title_str);
@@ -69,16 +43,175 @@ pub fn get_frontmost_window() -> Result<WindowInfo, String> {
             process_name: process_name_str.clone(),
             pid: process_id as i32,
             app_id: Some(process_name_str), // 使用进程名作为 app_id
+            private_browsing: false,
         })
     }
 }
 
-/// 获取所有窗口列表 (目前仅返回前台窗口)
-pub fn get_all_windows() -> Result<Vec<WindowInfo>, String> {
-    // 暂时只实现获取前台窗口
-    match get_frontmost_window() {
-        Ok(info) => Ok(vec![info]),
-        Err(_) => Ok(Vec::new()),
+/// 获取所有顶层窗口，按叠放次序从上到下排列
+///
+/// 只包含任务栏中可见的窗口：跳过不可见、有所有者、工具窗口，以及被 DWM 隐藏
+/// 且位于当前虚拟桌面的窗口（例如挂起的 UWP 应用）。
+pub fn get_all_windows() -> Result<Vec<WindowEntry>, String> {
+    unsafe {
+        // 虚拟桌面查询需要 COM；已初始化时返回 S_FALSE / RPC_E_CHANGED_MODE，均可忽略
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let desktop_manager: Option<IVirtualDesktopManager> =
+            CoCreateInstance(&VirtualDesktopManager, None, CLSCTX_ALL).ok();
+
+        // EnumWindows 按 Z 序从上到下枚举
+        let mut handles: Vec<HWND> = Vec::new();
+        EnumWindows(Some(collect_window), LPARAM(&mut handles as *mut Vec<HWND> as isize))
+            .map_err(|e| format!("EnumWindows 失败: {}", e))?;
+
+        let monitors = enumerate_monitors();
+        let foreground = GetForegroundWindow();
+
+        let mut entries = Vec::new();
+        for h_wnd in handles {
+            if !is_task_window(h_wnd, desktop_manager.as_ref()) {
+                continue;
+            }
+            let title = window_title(h_wnd);
+            if title.is_empty() {
+                continue;
+            }
+
+            let mut process_id: u32 = 0;
+            GetWindowThreadProcessId(h_wnd, Some(&mut process_id));
+            let process_name = process_name(process_id);
+
+            let mut rect = RECT::default();
+            let bounds = GetWindowRect(h_wnd, &mut rect).ok().map(|_| rect_to_bounds(&rect));
+            let minimized = IsIconic(h_wnd).as_bool();
+
+            let monitor = MonitorFromWindow(h_wnd, MONITOR_DEFAULTTONEAREST);
+            let monitor_rect = monitor_rect(monitor);
+            let fullscreen = !minimized && monitor_rect.is_some() && bounds == monitor_rect;
+
+            let workspace = desktop_manager
+                .as_ref()
+                .and_then(|manager| manager.GetWindowDesktopId(h_wnd).ok())
+                .filter(|id| id.to_u128() != 0)
+                .map(|id| format!("{:?}", id));
+
+            entries.push(WindowEntry {
+                title,
+                process_name: process_name.clone(),
+                pid: process_id as i32,
+                app_id: Some(process_name), // 与 get_frontmost_window 一致，使用进程名作为 app_id
+                bounds,
+                monitor: monitors.iter().position(|&m| m == monitor).map(|index| index as u32),
+                workspace,
+                minimized,
+                fullscreen,
+                focused: h_wnd == foreground,
+                z_order: entries.len() as u32,
+                private_browsing: false,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+unsafe extern "system" fn collect_window(h_wnd: HWND, lparam: LPARAM) -> BOOL {
+    let handles = &mut *(lparam.0 as *mut Vec<HWND>);
+    handles.push(h_wnd);
+    BOOL(1)
+}
+
+unsafe extern "system" fn collect_monitor(monitor: HMONITOR, _hdc: HDC, _rect: *mut RECT, lparam: LPARAM) -> BOOL {
+    let monitors = &mut *(lparam.0 as *mut Vec<HMONITOR>);
+    monitors.push(monitor);
+    BOOL(1)
+}
+
+/// 按系统枚举顺序列出显示器，序号即 `WindowEntry::monitor`
+unsafe fn enumerate_monitors() -> Vec<HMONITOR> {
+    let mut monitors: Vec<HMONITOR> = Vec::new();
+    let _ = EnumDisplayMonitors(None, None, Some(collect_monitor), LPARAM(&mut monitors as *mut Vec<HMONITOR> as isize));
+    monitors
+}
+
+unsafe fn monitor_rect(monitor: HMONITOR) -> Option<WindowBounds> {
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    GetMonitorInfoW(monitor, &mut info).as_bool().then(|| rect_to_bounds(&info.rcMonitor))
+}
+
+fn rect_to_bounds(rect: &RECT) -> WindowBounds {
+    WindowBounds {
+        x: rect.left,
+        y: rect.top,
+        width: (rect.right - rect.left).max(0) as u32,
+        height: (rect.bottom - rect.top).max(0) as u32,
+    }
+}
+
+/// 是否为任务栏中会显示的顶层窗口
+unsafe fn is_task_window(h_wnd: HWND, desktop_manager: Option<&IVirtualDesktopManager>) -> bool {
+    if !IsWindowVisible(h_wnd).as_bool() || GetWindow(h_wnd, GW_OWNER).is_ok() {
+        return false;
+    }
+    if GetWindowLongW(h_wnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0 != 0 {
+        return false;
+    }
+
+    // 其他虚拟桌面上的窗口同样被 DWM 隐藏，需要保留
+    let mut cloaked: u32 = 0;
+    let is_cloaked = DwmGetWindowAttribute(
+        h_wnd,
+        DWMWA_CLOAKED,
+        &mut cloaked as *mut u32 as *mut _,
+        std::mem::size_of::<u32>() as u32,
+    ).is_ok() && cloaked != 0;
+    if !is_cloaked {
+        return true;
+    }
+    desktop_manager
+        .and_then(|manager| manager.IsWindowOnCurrentVirtualDesktop(h_wnd).ok())
+        .is_some_and(|on_current| !on_current.as_bool())
+}
+
+unsafe fn window_title(h_wnd: HWND) -> String {
+    let mut window_title: [u16; 255] = [0; 255];
+    GetWindowTextW(h_wnd, &mut window_title);
+    OsString::from_wide(&window_title)
+        .to_string_lossy()
+        .into_owned()
+        .trim_matches(char::from(0))
+        .to_string()
+}
+
+unsafe fn process_name(process_id: u32) -> String {
+    let process_handle = OpenProcess(
+        PROCESS_QUERY_INFORMATION | PROCESS_VM_READ,
+        false,
+        process_id
+    );
+
+    let mut process_name: [u16; 255] = [0; 255];
+    if let Ok(handle) = process_handle {
+        let result = GetModuleBaseNameW(
+            handle,
+            None,
+            &mut process_name
+        );
+        let _ = CloseHandle(handle);
+
+        if result > 0 && process_name[0] != 0 {
+            OsString::from_wide(&process_name)
+                .to_string_lossy()
+                .into_owned()
+                .trim_matches(char::from(0))
+                .to_string()
+        } else {
+            String::from("Unknown")
+        }
+    } else {
+        String::from("Unknown")
     }
 }
 
@@ -6,7 +6,14 @@ use std::fs;
 use std::path::PathBuf;
 use tracing::info;
 
+use super::privacy::PrivacyConfig;
+use super::schedule::ScheduleConfig;
+use super::title_rewrite::TitleRewriteConfig;
 use super::ReporterConfig;
+use super::reporter::{
+    default_announce_pause, default_heartbeat_interval_secs, default_heartbeat_timeout_secs,
+    default_idle_threshold_secs,
+};
 
 const CONFIG_FILE: &str = "config.toml";
 
@@ -17,6 +24,12 @@ pub struct AppConfig {
     pub reporter: ReporterConfig,
     #[serde(default = "default_log_level")]
     pub log_level: String,
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+    #[serde(default)]
+    pub title_rewrite: TitleRewriteConfig,
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
 }
 
 fn default_log_level() -> String {
@@ -29,28 +42,46 @@ impl Default for ReporterConfig {
             enabled: false,
             ws_url: String::new(),
             token: String::new(),
+            auth_mode: Default::default(),
             enable_media_reporting: false,
+            window_list_interval_secs: 0,
+            idle_threshold_secs: default_idle_threshold_secs(),
+            media_selection: Default::default(),
+            report_media_sessions: false,
+            media_filter: Default::default(),
+            private_browsing: Default::default(),
+            window_privacy: Default::default(),
+            media_privacy: Default::default(),
+            announce_pause: default_announce_pause(),
+            heartbeat_interval_secs: default_heartbeat_interval_secs(),
+            heartbeat_timeout_secs: default_heartbeat_timeout_secs(),
+            reconnect: Default::default(),
+            outbox: Default::default(),
+            spool: Default::default(),
+            transport: Default::default(),
+            mqtt: Default::default(),
         }
     }
 }
 
-/// Get config file path (config.toml in user data directory)
-fn get_config_path() -> PathBuf {
+/// Get the user data directory (~/.shikenmatrix, or the working directory as a fallback)
+pub(crate) fn data_dir() -> PathBuf {
     if let Some(home) = dirs::home_dir() {
         let config_dir = home.join(".shikenmatrix");
         if !config_dir.exists() {
             let _ = fs::create_dir_all(&config_dir);
             info!("Created config directory: {}", config_dir.display());
         }
-        let path = config_dir.join(CONFIG_FILE);
-        info!("Config path: {}", path.display());
-        return path;
+        return config_dir;
     }
 
-    let path = std::env::current_dir()
-        .unwrap_or_else(|_| PathBuf::from("."))
-        .join(CONFIG_FILE);
-    info!("Config path (fallback): {}", path.display());
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+/// Get config file path (config.toml in user data directory)
+fn get_config_path() -> PathBuf {
+    let path = data_dir().join(CONFIG_FILE);
+    info!("Config path: {}", path.display());
     path
 }
 
@@ -68,6 +99,9 @@ pub fn load_config() -> AppConfig {
             match toml::from_str(&content) {
                 Ok(config) => {
                     info!("Config loaded successfully: {}", path.display());
+                    let mut config: AppConfig = config;
+                    config.reporter.reconnect = config.reporter.reconnect.sanitized();
+                    super::redact::register_secret(&config.reporter.token);
                     config
                 }
                 Err(e) => {
@@ -92,7 +126,7 @@ pub fn save_config(config: &AppConfig) -> Result<(), String> {
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
     info!("Writing config to: {}", path.display());
-    info!("Config content:\n{}", content);
+    info!("Config content:\n{}", super::redact::redact(&content));
 
     fs::write(&path, content)
         .map_err(|e| format!("Failed to write config file: {}", e))?;
@@ -0,0 +1,169 @@
+//! Protocol handshake
+//! The client opens every connection with `hello`; servers that understand it answer with
+//! `welcome` to switch features off. Old servers ignore `hello`, and everything stays enabled.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+use super::config::data_dir;
+
+/// Bumped when the meaning of existing messages changes
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Message types this client may send, as announced in `hello`
+pub const MESSAGE_TYPES: &[&str] = &[
+    "window_info",
+    "window_list",
+    "media_playback",
+    "media_sessions",
+    "presence",
+    "session_state",
+    "schedule_state",
+    "pause_state",
+    "history_batch",
+    "upload_artwork",
+];
+
+/// Payload encodings this client understands
+pub const ENCODINGS: &[&str] = &["json"];
+
+const DEVICE_ID_FILE: &str = "device_id";
+
+static DEVICE_ID: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct HelloMessage {
+    #[serde(rename = "type")]
+    msg_type: String,
+    protocol_version: u32,
+    client_version: String,
+    /// `macos`, `windows` or `linux`
+    platform: String,
+    arch: String,
+    device_id: String,
+    message_types: Vec<String>,
+    encodings: Vec<String>,
+}
+
+impl HelloMessage {
+    pub(crate) fn new() -> Self {
+        Self {
+            msg_type: "hello".to_string(),
+            protocol_version: PROTOCOL_VERSION,
+            client_version: env!("CARGO_PKG_VERSION").to_string(),
+            platform: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            device_id: device_id().to_string(),
+            message_types: MESSAGE_TYPES.iter().map(|t| t.to_string()).collect(),
+            encodings: ENCODINGS.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+}
+
+/// Server reply to `hello`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(crate) struct WelcomeMessage {
+    #[serde(default)]
+    pub server_version: Option<String>,
+    #[serde(default)]
+    pub protocol_version: Option<u32>,
+    /// Message type -> enabled; types not listed stay enabled
+    #[serde(default)]
+    pub features: HashMap<String, bool>,
+}
+
+/// What the current connection may send
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Negotiated {
+    /// `None` until a `welcome` arrives; old servers never send one
+    welcome: Option<WelcomeMessage>,
+}
+
+impl Negotiated {
+    pub(crate) fn accept(&mut self, welcome: WelcomeMessage) {
+        info!(
+            "Server welcome: version={}, protocol={}, features={:?}",
+            welcome.server_version.as_deref().unwrap_or("unknown"),
+            welcome.protocol_version.map(|v| v.to_string()).unwrap_or_else(|| "unknown".to_string()),
+            welcome.features,
+        );
+        if welcome.protocol_version.is_some_and(|v| v > PROTOCOL_VERSION) {
+            warn!("Server speaks protocol {:?}, client only knows {}", welcome.protocol_version, PROTOCOL_VERSION);
+        }
+        self.welcome = Some(welcome);
+    }
+
+    /// Whether a message type may be sent on this connection
+    ///
+    /// Only the types announced in `hello` can be switched off; control messages such as
+    /// `hello` itself and the internal shutdown signal always go through.
+    pub(crate) fn allows(&self, message_type: &str) -> bool {
+        if !MESSAGE_TYPES.contains(&message_type) {
+            return true;
+        }
+        self.welcome.as_ref()
+            .and_then(|welcome| welcome.features.get(message_type).copied())
+            .unwrap_or(true)
+    }
+}
+
+/// Stable identifier of this installation, generated once and kept in the data directory
+pub fn device_id() -> &'static str {
+    DEVICE_ID.get_or_init(|| {
+        let path = data_dir().join(DEVICE_ID_FILE);
+        if let Ok(id) = fs::read_to_string(&path) {
+            let id = id.trim();
+            if !id.is_empty() {
+                return id.to_string();
+            }
+        }
+
+        // RandomState is seeded from the OS RNG, which is enough for an identifier
+        let random = || RandomState::new().build_hasher().finish();
+        let id = format!("{:016x}{:016x}", random(), random());
+        if let Err(e) = fs::write(&path, &id) {
+            warn!("Failed to store device id: {}", e);
+        }
+        id
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn negotiated(features: &[(&str, bool)]) -> Negotiated {
+        let mut negotiated = Negotiated::default();
+        negotiated.accept(WelcomeMessage {
+            features: features.iter().map(|(name, enabled)| (name.to_string(), *enabled)).collect(),
+            ..Default::default()
+        });
+        negotiated
+    }
+
+    #[test]
+    fn everything_is_allowed_without_welcome() {
+        let negotiated = Negotiated::default();
+        assert!(MESSAGE_TYPES.iter().all(|t| negotiated.allows(t)));
+    }
+
+    #[test]
+    fn welcome_switches_off_listed_types_only() {
+        let negotiated = negotiated(&[("window_list", false), ("presence", true)]);
+        assert!(!negotiated.allows("window_list"));
+        assert!(negotiated.allows("presence"));
+        assert!(negotiated.allows("window_info"));
+    }
+
+    #[test]
+    fn control_messages_cannot_be_switched_off() {
+        let negotiated = negotiated(&[("shutdown", false), ("hello", false)]);
+        assert!(negotiated.allows("shutdown"));
+        assert!(negotiated.allows("hello"));
+    }
+}
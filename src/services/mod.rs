@@ -2,8 +2,28 @@
 //! 包含数据上报、状态管理等业务逻辑
 
 pub mod config;
+pub mod handshake;
+pub mod outbox;
+pub mod pause;
+pub mod privacy;
+pub mod reconnect;
+pub mod redact;
+pub mod private_browsing;
 pub mod reporter;
+pub mod schedule;
+pub mod spool;
+pub mod title_rewrite;
+pub mod transport;
 
 #[allow(unused_imports)]
 pub use config::{load_config, save_reporter_config, get_log_level};
-pub use reporter::{Reporter, ReporterConfig};
+pub use outbox::{OutboxConfig, OutboxStats};
+pub use pause::{load_pause, save_pause, Pause};
+pub use privacy::{PrivacyAction, PrivacyConfig, PrivacyFilter, PrivacyRule};
+pub use reconnect::{ReconnectPolicy, RetryState};
+pub use private_browsing::PrivateBrowsingPolicy;
+pub use reporter::{AuthMode, MediaFilter, MediaPrivacyLevel, MediaSelectionPolicy, MediaSelectionStrategy, Reporter, ReporterConfig, ReporterStatus, WindowPrivacyLevel};
+pub use schedule::{Schedule, ScheduleCategory, ScheduleConfig, ScheduleMode, ScheduleRange, Weekday};
+pub use spool::{SpoolConfig, SpoolEntry};
+pub use title_rewrite::{TitleRewriteConfig, TitleRewriteRule, TitleRewriter, TitleScrubber};
+pub use transport::{MqttConfig, Transport, TransportKind};
@@ -0,0 +1,282 @@
+//! Outbound queue
+//! Holds messages until the connection can send them. State snapshots keep only their latest
+//! value, artwork uploads are deduplicated by content id within a byte budget and everything
+//! else is a bounded FIFO, so an unreachable server neither grows memory nor gets a burst of
+//! stale events on reconnect. Configured via `[reporter.outbox]` in config.toml
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+use tracing::debug;
+
+/// How a queued message is coalesced
+pub(crate) enum Slot {
+    /// A state snapshot: a newer message of the same type replaces the pending one
+    Latest(&'static str),
+    /// A payload identified by `key`, counted against the byte budget
+    Keyed { key: String, bytes: usize },
+    /// An event that is delivered in order, subject to `max_events`
+    Event,
+}
+
+/// Messages the outbox can queue
+pub(crate) trait Outgoing {
+    fn slot(&self) -> Slot;
+}
+
+/// `[reporter.outbox]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutboxConfig {
+    /// Pending events kept besides the latest states; the oldest are dropped first
+    pub max_events: usize,
+    /// Bytes of pending artwork kept; the oldest uploads are dropped first
+    pub max_artwork_bytes: usize,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            max_events: 100,
+            max_artwork_bytes: 8 * 1024 * 1024,
+        }
+    }
+}
+
+/// Counters since the reporter was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutboxStats {
+    /// Messages waiting to be sent
+    pub pending: usize,
+    /// Pending messages replaced by a newer one of the same kind
+    pub coalesced: u64,
+    /// Events dropped because `max_events` was exceeded
+    pub dropped_events: u64,
+    /// Artwork uploads dropped because the byte budget was exceeded
+    pub dropped_artwork: u64,
+}
+
+enum Key {
+    Latest(&'static str),
+    Keyed(String),
+    Event,
+}
+
+struct Entry<T> {
+    key: Key,
+    bytes: usize,
+    message: T,
+}
+
+struct State<T> {
+    queue: VecDeque<Entry<T>>,
+    config: OutboxConfig,
+    stats: OutboxStats,
+    /// Bytes held by `Keyed` entries
+    bytes: usize,
+}
+
+impl<T> State<T> {
+    fn remove(&mut self, index: usize) {
+        if let Some(entry) = self.queue.remove(index) {
+            self.bytes -= entry.bytes;
+        }
+    }
+
+    /// Drop the oldest entries matching `is_kind` while `over` holds
+    fn evict(&mut self, is_kind: fn(&Key) -> bool, over: fn(&Self) -> bool) -> u64 {
+        let mut dropped = 0;
+        while over(self) {
+            let Some(index) = self.queue.iter().position(|entry| is_kind(&entry.key)) else { break };
+            self.remove(index);
+            dropped += 1;
+        }
+        dropped
+    }
+}
+
+pub(crate) struct Outbox<T> {
+    state: Mutex<State<T>>,
+    ready: Notify,
+}
+
+impl<T: Outgoing> Outbox<T> {
+    pub(crate) fn new(config: OutboxConfig) -> Self {
+        Self {
+            state: Mutex::new(State {
+                queue: VecDeque::new(),
+                config,
+                stats: OutboxStats::default(),
+                bytes: 0,
+            }),
+            ready: Notify::new(),
+        }
+    }
+
+    pub(crate) fn set_config(&self, config: OutboxConfig) {
+        if let Ok(mut state) = self.state.lock() {
+            state.config = config;
+        }
+    }
+
+    pub(crate) fn stats(&self) -> OutboxStats {
+        self.state.lock()
+            .map(|state| OutboxStats { pending: state.queue.len(), ..state.stats })
+            .unwrap_or_default()
+    }
+
+    /// Queue a message, replacing or evicting older ones as configured
+    pub(crate) fn push(&self, message: T) {
+        let Ok(mut state) = self.state.lock() else { return };
+        let (key, bytes) = match message.slot() {
+            Slot::Latest(kind) => (Key::Latest(kind), 0),
+            Slot::Keyed { key, bytes } => (Key::Keyed(key), bytes),
+            Slot::Event => (Key::Event, 0),
+        };
+
+        let replaces = |entry: &Entry<T>| match (&entry.key, &key) {
+            (Key::Latest(a), Key::Latest(b)) => a == b,
+            (Key::Keyed(a), Key::Keyed(b)) => a == b,
+            _ => false,
+        };
+        if let Some(index) = state.queue.iter().position(replaces) {
+            state.remove(index);
+            state.stats.coalesced += 1;
+        }
+
+        match key {
+            Key::Keyed(_) if bytes > state.config.max_artwork_bytes => {
+                state.stats.dropped_artwork += 1;
+                debug!("Outbox: payload of {} bytes exceeds the budget, dropped", bytes);
+                return;
+            }
+            Key::Keyed(_) => {
+                state.bytes += bytes;
+                state.queue.push_back(Entry { key, bytes, message });
+                let dropped = state.evict(
+                    |key| matches!(key, Key::Keyed(_)),
+                    |state| state.bytes > state.config.max_artwork_bytes,
+                );
+                state.stats.dropped_artwork += dropped;
+            }
+            Key::Event => {
+                state.queue.push_back(Entry { key, bytes, message });
+                let dropped = state.evict(
+                    |key| matches!(key, Key::Event),
+                    |state| state.queue.iter().filter(|e| matches!(e.key, Key::Event)).count() > state.config.max_events,
+                );
+                state.stats.dropped_events += dropped;
+            }
+            Key::Latest(_) => state.queue.push_back(Entry { key, bytes, message }),
+        }
+        drop(state);
+        self.ready.notify_one();
+    }
+
+    /// Wait for the next message; cancel-safe, nothing is lost if the future is dropped
+    pub(crate) async fn next(&self) -> T {
+        loop {
+            let entry = self.state.lock().ok().and_then(|mut state| {
+                let entry = state.queue.pop_front()?;
+                state.bytes -= entry.bytes;
+                Some(entry)
+            });
+            if let Some(entry) = entry {
+                return entry.message;
+            }
+            self.ready.notified().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::FutureExt;
+
+    #[derive(Debug, PartialEq)]
+    enum Msg {
+        State(&'static str, u32),
+        Artwork(&'static str, usize),
+        Event(u32),
+    }
+
+    impl Outgoing for Msg {
+        fn slot(&self) -> Slot {
+            match self {
+                Self::State(kind, _) => Slot::Latest(kind),
+                Self::Artwork(key, bytes) => Slot::Keyed { key: key.to_string(), bytes: *bytes },
+                Self::Event(_) => Slot::Event,
+            }
+        }
+    }
+
+    fn outbox(max_events: usize, max_artwork_bytes: usize) -> Outbox<Msg> {
+        Outbox::new(OutboxConfig { max_events, max_artwork_bytes })
+    }
+
+    fn drain(outbox: &Outbox<Msg>) -> Vec<Msg> {
+        std::iter::from_fn(|| outbox.next().now_or_never()).collect()
+    }
+
+    #[test]
+    fn latest_state_replaces_the_pending_one() {
+        let outbox = outbox(10, 100);
+        outbox.push(Msg::State("window", 1));
+        outbox.push(Msg::Event(1));
+        outbox.push(Msg::State("media", 1));
+        outbox.push(Msg::State("window", 2));
+
+        let stats = outbox.stats();
+        assert_eq!((stats.pending, stats.coalesced), (3, 1));
+        // The replacement is queued behind everything already pending
+        assert_eq!(drain(&outbox), [Msg::Event(1), Msg::State("media", 1), Msg::State("window", 2)]);
+        assert_eq!(outbox.stats().pending, 0);
+    }
+
+    #[test]
+    fn events_keep_their_order_and_drop_the_oldest() {
+        let outbox = outbox(2, 100);
+        outbox.push(Msg::Event(1));
+        outbox.push(Msg::State("window", 1));
+        outbox.push(Msg::Event(2));
+        outbox.push(Msg::Event(3));
+
+        // States do not count against `max_events`
+        let stats = outbox.stats();
+        assert_eq!((stats.pending, stats.dropped_events, stats.coalesced), (3, 1, 0));
+        assert_eq!(drain(&outbox), [Msg::State("window", 1), Msg::Event(2), Msg::Event(3)]);
+    }
+
+    #[test]
+    fn keyed_payload_replaces_the_same_key() {
+        let outbox = outbox(10, 100);
+        outbox.push(Msg::Artwork("a", 10));
+        outbox.push(Msg::Artwork("b", 10));
+        outbox.push(Msg::Artwork("a", 20));
+
+        assert_eq!(outbox.stats().coalesced, 1);
+        assert_eq!(drain(&outbox), [Msg::Artwork("b", 10), Msg::Artwork("a", 20)]);
+    }
+
+    #[test]
+    fn oldest_payloads_are_evicted_over_the_byte_budget() {
+        let outbox = outbox(10, 25);
+        outbox.push(Msg::Artwork("a", 10));
+        outbox.push(Msg::Event(1));
+        outbox.push(Msg::Artwork("b", 10));
+        outbox.push(Msg::Artwork("c", 10));
+        assert_eq!(outbox.stats().dropped_artwork, 1);
+
+        // Larger than the whole budget: dropped without evicting anything
+        outbox.push(Msg::Artwork("d", 30));
+        assert_eq!(outbox.stats().dropped_artwork, 2);
+        assert_eq!(drain(&outbox), [Msg::Event(1), Msg::Artwork("b", 10), Msg::Artwork("c", 10)]);
+
+        // Sent payloads no longer count against the budget
+        outbox.push(Msg::Artwork("e", 25));
+        assert_eq!(drain(&outbox), [Msg::Artwork("e", 25)]);
+        assert_eq!(outbox.stats().dropped_artwork, 2);
+    }
+}
@@ -0,0 +1,263 @@
+//! Temporary pause ("snooze")
+//! The pause is stored in pause.toml next to config.toml so it survives restarts and can be
+//! set from another process (e.g. `shikenmatrix pause 15m` while the UI is running)
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use super::config::data_dir;
+
+const PAUSE_FILE: &str = "pause.toml";
+
+/// An active pause
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pause {
+    /// Unix time (ms) the pause ends; paused until resumed when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<u64>,
+}
+
+impl Pause {
+    /// Pause until explicitly resumed
+    pub fn indefinite() -> Self {
+        Self { until: None }
+    }
+
+    /// Pause until the given time
+    pub fn until(time: SystemTime) -> Self {
+        let millis = time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+        Self { until: Some(millis) }
+    }
+
+    /// Pause for the given duration from now (until resumed if the end is not representable)
+    pub fn for_duration(duration: Duration) -> Self {
+        SystemTime::now().checked_add(duration).map(Self::until).unwrap_or_else(Self::indefinite)
+    }
+
+    /// Pause until the next local midnight
+    pub fn until_tomorrow() -> Result<Self, String> {
+        let midnight = jiff::Zoned::now()
+            .tomorrow()
+            .and_then(|tomorrow| tomorrow.start_of_day())
+            .map_err(|e| format!("Failed to compute tomorrow: {}", e))?;
+        Ok(Self { until: Some(midnight.timestamp().as_millisecond().max(0) as u64) })
+    }
+
+    /// Parse a CLI argument: nothing for "until resumed", "tomorrow", or a number with an
+    /// `s` / `m` / `h` / `d` suffix (plain numbers are minutes)
+    pub fn parse(arg: Option<&str>) -> Result<Self, String> {
+        let Some(arg) = arg.map(str::trim).filter(|arg| !arg.is_empty()) else {
+            return Ok(Self::indefinite());
+        };
+        if arg.eq_ignore_ascii_case("tomorrow") {
+            return Self::until_tomorrow();
+        }
+
+        let (number, unit) = arg.split_at(arg.find(|c: char| !c.is_ascii_digit()).unwrap_or(arg.len()));
+        let number: u64 = number.parse().map_err(|_| format!("Invalid pause duration: {}", arg))?;
+        let multiplier = match unit {
+            "s" => 1,
+            "" | "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            _ => return Err(format!("Invalid pause duration: {}", arg)),
+        };
+        let secs = number.checked_mul(multiplier).ok_or_else(|| format!("Pause duration too long: {}", arg))?;
+        Ok(Self::for_duration(Duration::from_secs(secs)))
+    }
+
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.until.is_some_and(|until| {
+            now.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0) >= until
+        })
+    }
+}
+
+fn get_pause_path() -> PathBuf {
+    data_dir().join(PAUSE_FILE)
+}
+
+/// Load the stored pause; an expired pause is removed and reported as `None`
+pub fn load_pause() -> Option<Pause> {
+    load_pause_from(&get_pause_path())
+}
+
+/// Store the pause, or remove it when `None`
+pub fn save_pause(pause: Option<Pause>) -> Result<(), String> {
+    save_pause_to(&get_pause_path(), pause).map(|_| ())
+}
+
+/// pause.toml as last loaded or saved by this process
+///
+/// Lets a running reporter pick up pauses set by another process without the file overriding
+/// its own state, e.g. a pause that could not be saved.
+pub struct PauseFile {
+    path: PathBuf,
+    /// File content (`None` when missing) at the last load or save; `None` before the first load
+    seen: Option<Option<String>>,
+}
+
+impl PauseFile {
+    pub fn new() -> Self {
+        Self::at(get_pause_path())
+    }
+
+    fn at(path: PathBuf) -> Self {
+        Self { path, seen: None }
+    }
+
+    /// The stored pause if the file changed since the last call or `save`, `None` otherwise
+    pub fn changed(&mut self) -> Option<Option<Pause>> {
+        let content = fs::read_to_string(&self.path).ok();
+        if self.seen.as_ref() == Some(&content) {
+            return None;
+        }
+        let pause = content.as_deref().and_then(|content| parse_pause(&self.path, content));
+        // An expired pause has been removed by now
+        self.seen = Some(if pause.is_some() { content } else { fs::read_to_string(&self.path).ok() });
+        Some(pause)
+    }
+
+    /// Store the pause, or remove it when `None`
+    pub fn save(&mut self, pause: Option<Pause>) -> Result<(), String> {
+        self.seen = Some(save_pause_to(&self.path, pause)?);
+        Ok(())
+    }
+}
+
+impl Default for PauseFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn load_pause_from(path: &Path) -> Option<Pause> {
+    let content = fs::read_to_string(path).ok()?;
+    parse_pause(path, &content)
+}
+
+fn parse_pause(path: &Path, content: &str) -> Option<Pause> {
+    // A file that cannot be parsed must not leave reporting paused forever
+    let pause: Pause = match toml::from_str(content) {
+        Ok(pause) => pause,
+        Err(e) => {
+            warn!("Ignoring invalid pause file {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    if pause.is_expired(SystemTime::now()) {
+        let _ = fs::remove_file(path);
+        return None;
+    }
+    Some(pause)
+}
+
+/// Written through a temporary file and a rename, so a reader never sees a partial file
+/// (an empty one would read as "paused until resumed"); returns the new file content
+fn save_pause_to(path: &Path, pause: Option<Pause>) -> Result<Option<String>, String> {
+    match pause {
+        Some(pause) => {
+            let content = toml::to_string(&pause)
+                .map_err(|e| format!("Failed to serialize pause: {}", e))?;
+            let tmp_path = path.with_extension("toml.tmp");
+            File::create(&tmp_path)
+                .and_then(|mut file| file.write_all(content.as_bytes()).and_then(|_| file.sync_all()))
+                .and_then(|_| fs::rename(&tmp_path, path))
+                .map_err(|e| format!("Failed to write pause file: {}", e))?;
+            Ok(Some(content))
+        }
+        None => match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove pause file: {}", e))
+            }
+            _ => Ok(None),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("shikenmatrix-{}-{}.toml", name, std::process::id()))
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let path = temp_path("pause-round-trip");
+        let pause = Pause::for_duration(Duration::from_secs(3600));
+        save_pause_to(&path, Some(pause)).unwrap();
+        assert_eq!(load_pause_from(&path), Some(pause));
+        assert!(!path.with_extension("toml.tmp").exists());
+
+        save_pause_to(&path, Some(Pause::indefinite())).unwrap();
+        assert_eq!(load_pause_from(&path), Some(Pause::indefinite()));
+
+        save_pause_to(&path, None).unwrap();
+        assert_eq!(load_pause_from(&path), None);
+        // Resuming when not paused is fine
+        save_pause_to(&path, None).unwrap();
+    }
+
+    #[test]
+    fn invalid_file_is_not_paused() {
+        let path = temp_path("pause-invalid");
+        fs::write(&path, "until = \"tomorrow\"").unwrap();
+        assert_eq!(load_pause_from(&path), None);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn expired_pause_is_removed() {
+        let path = temp_path("pause-expired");
+        save_pause_to(&path, Some(Pause { until: Some(1) })).unwrap();
+        assert_eq!(load_pause_from(&path), None);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn pause_file_reports_only_outside_changes() {
+        let path = temp_path("pause-file");
+        let _ = fs::remove_file(&path);
+        let mut file = PauseFile::at(path.clone());
+
+        // The first check always reports the stored state
+        assert_eq!(file.changed(), Some(None));
+        assert_eq!(file.changed(), None);
+
+        // Our own saves are not reported back
+        let pause = Pause::for_duration(Duration::from_secs(3600));
+        file.save(Some(pause)).unwrap();
+        assert_eq!(file.changed(), None);
+
+        // Another process resuming or pausing is
+        save_pause_to(&path, None).unwrap();
+        assert_eq!(file.changed(), Some(None));
+        save_pause_to(&path, Some(Pause::indefinite())).unwrap();
+        assert_eq!(file.changed(), Some(Some(Pause::indefinite())));
+        assert_eq!(file.changed(), None);
+
+        // An expired pause reads as resumed once, then the missing file is unchanged
+        save_pause_to(&path, Some(Pause { until: Some(1) })).unwrap();
+        assert_eq!(file.changed(), Some(None));
+        assert_eq!(file.changed(), None);
+    }
+
+    #[test]
+    fn failed_save_is_not_remembered() {
+        let dir = temp_path("pause-file-dir");
+        fs::create_dir_all(&dir).unwrap();
+        // A directory in place of the file: reading yields nothing and saving fails
+        let mut file = PauseFile::at(dir.clone());
+        assert_eq!(file.changed(), Some(None));
+        assert!(file.save(Some(Pause::indefinite())).is_err());
+        assert_eq!(file.changed(), None);
+        let _ = fs::remove_file(dir.with_extension("toml.tmp"));
+        let _ = fs::remove_dir(&dir);
+    }
+}
@@ -0,0 +1,286 @@
+//! Privacy rules
+//! Redacts window and media data before it is reported, configured via `[[privacy.rules]]` in config.toml
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::platform::{MediaMetadata, WindowEntry, WindowInfo};
+
+/// Title used by [`PrivacyAction::Placeholder`] when the rule does not set one
+const DEFAULT_PLACEHOLDER: &str = "Private";
+
+/// `[privacy]` section of config.toml
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    #[serde(default)]
+    pub rules: Vec<PrivacyRule>,
+}
+
+/// What to do with a window or media item matched by a rule
+///
+/// Declared from least to most restrictive: when several rules match, the most restrictive
+/// action wins, and among equally restrictive rules the first one in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyAction {
+    /// Report with an empty title (media: no title, artist, album or artwork)
+    BlankTitle,
+    /// Report with the rule's `placeholder` as the title
+    Placeholder,
+    /// Report only the application: no title, no pid (media: only the player and play state)
+    AppOnly,
+    /// Do not report at all
+    Drop,
+}
+
+/// A single `[[privacy.rules]]` entry
+///
+/// Every condition that is set must match; a rule without conditions matches everything.
+/// For media, `process_name` and `app_id` are both compared with the player's bundle identifier
+/// and `title` with the track title.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyRule {
+    /// Process name, compared case-insensitively
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_name: Option<String>,
+    /// Application id (bundle id / AUMID / WM_CLASS), compared case-insensitively
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    /// Regular expression searched for in the title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub action: PrivacyAction,
+    /// Replacement title for [`PrivacyAction::Placeholder`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+}
+
+struct CompiledRule {
+    process_name: Option<String>,
+    app_id: Option<String>,
+    title: Option<Regex>,
+    action: PrivacyAction,
+    placeholder: Option<String>,
+}
+
+impl CompiledRule {
+    fn matches(&self, process_name: &str, app_id: Option<&str>, title: &str) -> bool {
+        self.process_name.as_ref().is_none_or(|p| p.eq_ignore_ascii_case(process_name))
+            && self.app_id.as_ref().is_none_or(|a| app_id.is_some_and(|id| a.eq_ignore_ascii_case(id)))
+            && self.title.as_ref().is_none_or(|re| re.is_match(title))
+    }
+}
+
+/// Compiled privacy rules, applied by the reporter to everything it sends
+#[derive(Default)]
+pub struct PrivacyFilter {
+    rules: Vec<CompiledRule>,
+}
+
+impl PrivacyFilter {
+    /// Compile the rules; fails on the first invalid title regex
+    pub fn new(config: &PrivacyConfig) -> Result<Self, String> {
+        let rules = config.rules.iter().enumerate()
+            .map(|(index, rule)| {
+                let title = rule.title.as_deref()
+                    .map(Regex::new)
+                    .transpose()
+                    .map_err(|e| format!("Invalid title regex in privacy rule #{}: {}", index + 1, e))?;
+                Ok(CompiledRule {
+                    process_name: rule.process_name.clone(),
+                    app_id: rule.app_id.clone(),
+                    title,
+                    action: rule.action,
+                    placeholder: rule.placeholder.clone(),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { rules })
+    }
+
+    /// The winning rule for an item, if any rule matches
+    fn matching_rule(&self, process_name: &str, app_id: Option<&str>, title: &str) -> Option<&CompiledRule> {
+        self.rules.iter()
+            .filter(|rule| rule.matches(process_name, app_id, title))
+            // `max_by_key` keeps the last maximum, so iterate in reverse to prefer the first rule
+            .rev()
+            .max_by_key(|rule| rule.action)
+    }
+
+    /// Redact the focused window; `None` means it must not be reported
+    pub fn apply_window(&self, info: &WindowInfo) -> Option<WindowInfo> {
+        let mut info = info.clone();
+        if let Some(rule) = self.matching_rule(&info.process_name, info.app_id.as_deref(), &info.title) {
+            match rule.action {
+                PrivacyAction::Drop => return None,
+                PrivacyAction::AppOnly => {
+                    info.title.clear();
+                    info.pid = 0;
+                }
+                PrivacyAction::Placeholder => info.title = placeholder(rule),
+                PrivacyAction::BlankTitle => info.title.clear(),
+            }
+        }
+        Some(info)
+    }
+
+    /// Redact an entry of the window list; `None` means it must not be reported
+    pub fn apply_window_entry(&self, mut entry: WindowEntry) -> Option<WindowEntry> {
+        if let Some(rule) = self.matching_rule(&entry.process_name, entry.app_id.as_deref(), &entry.title) {
+            match rule.action {
+                PrivacyAction::Drop => return None,
+                PrivacyAction::AppOnly => {
+                    entry.title.clear();
+                    entry.pid = 0;
+                }
+                PrivacyAction::Placeholder => entry.title = placeholder(rule),
+                PrivacyAction::BlankTitle => entry.title.clear(),
+            }
+        }
+        Some(entry)
+    }
+
+    /// Redact media metadata; `None` means it must not be reported
+    ///
+    /// Any redaction also drops the artwork and replaces the content identifier,
+    /// since both identify the track.
+    pub fn apply_media(&self, metadata: &MediaMetadata) -> Option<MediaMetadata> {
+        let player = metadata.bundle_identifier.as_deref().unwrap_or("");
+        let title = metadata.title.as_deref().unwrap_or("");
+        let Some(rule) = self.matching_rule(player, Some(player), title) else {
+            return Some(metadata.clone());
+        };
+
+        let mut redacted = MediaMetadata {
+            bundle_identifier: metadata.bundle_identifier.clone(),
+            duration: metadata.duration,
+            kind: metadata.kind,
+            content_item_identifier: Some(format!("{}:private", player)),
+            ..Default::default()
+        };
+        match rule.action {
+            PrivacyAction::Drop => return None,
+            PrivacyAction::AppOnly => redacted.duration = 0.0,
+            PrivacyAction::Placeholder => redacted.title = Some(placeholder(rule)),
+            PrivacyAction::BlankTitle => {}
+        }
+        Some(redacted)
+    }
+}
+
+fn placeholder(rule: &CompiledRule) -> String {
+    rule.placeholder.clone().unwrap_or_else(|| DEFAULT_PLACEHOLDER.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(action: PrivacyAction) -> PrivacyRule {
+        PrivacyRule { process_name: None, app_id: None, title: None, action, placeholder: None }
+    }
+
+    fn filter(rules: Vec<PrivacyRule>) -> PrivacyFilter {
+        PrivacyFilter::new(&PrivacyConfig { rules }).expect("valid rules")
+    }
+
+    fn window(process_name: &str, app_id: Option<&str>, title: &str) -> WindowInfo {
+        WindowInfo {
+            title: title.to_string(),
+            icon_data: None,
+            process_name: process_name.to_string(),
+            pid: 42,
+            app_id: app_id.map(str::to_string),
+            private_browsing: false,
+        }
+    }
+
+    #[test]
+    fn unmatched_window_is_reported_unchanged() {
+        let filter = filter(vec![PrivacyRule { process_name: Some("keepass".to_string()), ..rule(PrivacyAction::Drop) }]);
+        let info = window("firefox", None, "Example Domain");
+        assert_eq!(filter.apply_window(&info), Some(info));
+    }
+
+    #[test]
+    fn most_restrictive_rule_wins_over_first_match() {
+        let filter = filter(vec![
+            PrivacyRule { process_name: Some("firefox".to_string()), ..rule(PrivacyAction::BlankTitle) },
+            PrivacyRule { title: Some("Bank".to_string()), ..rule(PrivacyAction::Drop) },
+        ]);
+        assert_eq!(filter.apply_window(&window("firefox", None, "My Bank")), None);
+        assert_eq!(filter.apply_window(&window("firefox", None, "News")).unwrap().title, "");
+    }
+
+    #[test]
+    fn first_rule_wins_among_equally_restrictive() {
+        let filter = filter(vec![
+            PrivacyRule { process_name: Some("slack".to_string()), placeholder: Some("Chat".to_string()), ..rule(PrivacyAction::Placeholder) },
+            PrivacyRule { placeholder: Some("Busy".to_string()), ..rule(PrivacyAction::Placeholder) },
+        ]);
+        assert_eq!(filter.apply_window(&window("slack", None, "#general")).unwrap().title, "Chat");
+        assert_eq!(filter.apply_window(&window("code", None, "main.rs")).unwrap().title, "Busy");
+    }
+
+    #[test]
+    fn app_only_clears_title_and_pid() {
+        let filter = filter(vec![PrivacyRule { app_id: Some("org.example.Mail".to_string()), ..rule(PrivacyAction::AppOnly) }]);
+        let info = filter.apply_window(&window("mail", Some("org.example.Mail"), "Inbox (3)")).unwrap();
+        assert_eq!((info.title.as_str(), info.pid, info.process_name.as_str()), ("", 0, "mail"));
+        // A rule on `app_id` does not match windows without one
+        assert!(filter.apply_window(&window("mail", None, "Inbox (3)")).unwrap().pid != 0);
+    }
+
+    #[test]
+    fn names_are_case_insensitive_but_title_regex_is_not() {
+        let filter = filter(vec![
+            PrivacyRule { process_name: Some("KeePassXC".to_string()), ..rule(PrivacyAction::Drop) },
+            PrivacyRule { app_id: Some("ORG.SIGNAL.SIGNAL".to_string()), ..rule(PrivacyAction::Drop) },
+            PrivacyRule { title: Some("Secret".to_string()), ..rule(PrivacyAction::Drop) },
+            PrivacyRule { title: Some("(?i)incognito".to_string()), ..rule(PrivacyAction::Drop) },
+        ]);
+        assert_eq!(filter.apply_window(&window("keepassxc", None, "Passwords")), None);
+        assert_eq!(filter.apply_window(&window("signal", Some("org.signal.Signal"), "Chats")), None);
+        assert!(filter.apply_window(&window("notes", None, "secret plans")).is_some());
+        assert_eq!(filter.apply_window(&window("notes", None, "Secret plans")), None);
+        assert_eq!(filter.apply_window(&window("browser", None, "New INCOGNITO tab")), None);
+    }
+
+    #[test]
+    fn rule_without_conditions_matches_everything() {
+        let filter = filter(vec![rule(PrivacyAction::BlankTitle)]);
+        assert_eq!(filter.apply_window(&window("anything", None, "Title")).unwrap().title, "");
+    }
+
+    #[test]
+    fn invalid_title_regex_is_reported_with_rule_number() {
+        let config = PrivacyConfig {
+            rules: vec![rule(PrivacyAction::Drop), PrivacyRule { title: Some("(".to_string()), ..rule(PrivacyAction::Drop) }],
+        };
+        let error = PrivacyFilter::new(&config).err().expect("invalid regex");
+        assert!(error.contains("rule #2"), "{}", error);
+    }
+
+    #[test]
+    fn redacted_media_loses_artwork_and_content_id() {
+        let filter = filter(vec![PrivacyRule { title: Some("Lullaby".to_string()), ..rule(PrivacyAction::Placeholder) }]);
+        let metadata = MediaMetadata {
+            bundle_identifier: Some("org.example.Player".to_string()),
+            title: Some("Lullaby".to_string()),
+            artist: Some("Artist".to_string()),
+            duration: 120.0,
+            artwork_data: Some(std::sync::Arc::new(vec![1, 2, 3])),
+            content_item_identifier: Some("org.example.Player:track-1".to_string()),
+            ..Default::default()
+        };
+        let redacted = filter.apply_media(&metadata).unwrap();
+        assert_eq!(redacted.title.as_deref(), Some(DEFAULT_PLACEHOLDER));
+        assert_eq!(redacted.artist, None);
+        assert_eq!(redacted.artwork_data, None);
+        assert_eq!(redacted.content_item_identifier.as_deref(), Some("org.example.Player:private"));
+        assert_eq!(redacted.duration, 120.0);
+
+        let other = MediaMetadata { title: Some("Overture".to_string()), ..metadata };
+        assert_eq!(filter.apply_media(&other), Some(other.clone()));
+    }
+}
@@ -0,0 +1,148 @@
+//! Private / incognito browsing detection
+//! Combines the platform hint (window class / role, process command line) with title markers
+
+use serde::{Deserialize, Serialize};
+
+use crate::platform::{WindowEntry, WindowInfo};
+
+/// Process names / app ids (lowercase substrings) treated as browsers
+const BROWSERS: &[&str] = &[
+    "firefox", "librewolf", "waterfox", "chrome", "chromium", "msedge", "microsoft-edge",
+    "brave", "opera", "vivaldi", "safari", "tor browser",
+];
+
+/// Title markers (lowercase) browsers append to private windows, e.g.
+/// "Page — Mozilla Firefox Private Browsing", "Page - Google Chrome (Incognito)", "Page - [InPrivate] - Microsoft Edge"
+const TITLE_MARKERS: &[&str] = &["private browsing", "incognito", "inprivate", "(private)"];
+
+/// How windows detected as private browsing are reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivateBrowsingPolicy {
+    /// Do not report private windows at all
+    #[default]
+    Suppress,
+    /// Report only the browser: no title, no pid
+    BrowserOnly,
+    /// Detection disabled, private windows are reported like any other
+    Off,
+}
+
+impl PrivateBrowsingPolicy {
+    /// Apply the policy to the focused window; `None` means it must not be reported
+    pub fn apply_window(self, info: &WindowInfo) -> Option<WindowInfo> {
+        let mut info = info.clone();
+        if self != Self::Off && is_private_window(&info.process_name, info.app_id.as_deref(), &info.title, info.private_browsing) {
+            if self == Self::Suppress {
+                return None;
+            }
+            info.title.clear();
+            info.pid = 0;
+        }
+        Some(info)
+    }
+
+    /// Apply the policy to an entry of the window list; `None` means it must not be reported
+    pub fn apply_window_entry(self, mut entry: WindowEntry) -> Option<WindowEntry> {
+        if self != Self::Off && is_private_window(&entry.process_name, entry.app_id.as_deref(), &entry.title, entry.private_browsing) {
+            if self == Self::Suppress {
+                return None;
+            }
+            entry.title.clear();
+            entry.pid = 0;
+        }
+        Some(entry)
+    }
+}
+
+fn is_browser(process_name: &str, app_id: Option<&str>) -> bool {
+    let process_name = process_name.to_lowercase();
+    let app_id = app_id.unwrap_or("").to_lowercase();
+    BROWSERS.iter().any(|browser| process_name.contains(browser) || app_id.contains(browser))
+}
+
+/// Whether a browser window is in private mode
+///
+/// Only browsers are considered, so a document that merely mentions "incognito" is not affected.
+/// Title markers are only searched after the page title (the first " - " / " — " / " – " segment).
+pub fn is_private_window(process_name: &str, app_id: Option<&str>, title: &str, platform_hint: bool) -> bool {
+    if !is_browser(process_name, app_id) {
+        return false;
+    }
+    if platform_hint {
+        return true;
+    }
+    title
+        .to_lowercase()
+        .replace(" — ", " - ")
+        .replace(" – ", " - ")
+        .split(" - ")
+        .skip(1)
+        .any(|segment| TITLE_MARKERS.iter().any(|marker| segment.contains(marker)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_markers_after_the_page_title() {
+        let private = [
+            ("firefox", "Page — Mozilla Firefox Private Browsing"),
+            ("chrome", "Page - Google Chrome (Incognito)"),
+            ("msedge", "Page - [InPrivate] - Microsoft\u{200B} Edge"),
+            ("safari", "Page – Safari (Private)"),
+        ];
+        for (process_name, title) in private {
+            assert!(is_private_window(process_name, None, title, false), "{title}");
+        }
+
+        // Markers inside the page title itself do not count
+        assert!(!is_private_window("chrome", None, "Incognito mode explained - Google Chrome", false));
+        assert!(!is_private_window("firefox", None, "Page — Mozilla Firefox", false));
+        assert!(!is_private_window("firefox", None, "Private Browsing", false));
+    }
+
+    #[test]
+    fn only_browsers_are_considered() {
+        let title = "Notes - InPrivate checklist";
+        assert!(!is_private_window("editor", Some("org.example.Editor"), title, false));
+        assert!(!is_private_window("editor", None, title, true));
+
+        // Matched on the process name or the app id, case-insensitively
+        assert!(is_private_window("Firefox", None, title, false));
+        assert!(is_private_window("flatpak-session", Some("org.Chromium.Chromium"), title, false));
+    }
+
+    #[test]
+    fn platform_hint_does_not_need_a_marker() {
+        assert!(is_private_window("librewolf", None, "Page — LibreWolf", true));
+        assert!(!is_private_window("librewolf", None, "Page — LibreWolf", false));
+    }
+
+    #[test]
+    fn policies_for_window_list_entries() {
+        let entry = WindowEntry {
+            title: "Page - Google Chrome (Incognito)".to_string(),
+            process_name: "chrome".to_string(),
+            pid: 42,
+            app_id: None,
+            bounds: None,
+            monitor: None,
+            workspace: None,
+            minimized: false,
+            fullscreen: false,
+            focused: true,
+            z_order: 0,
+            private_browsing: false,
+        };
+
+        assert!(PrivateBrowsingPolicy::Suppress.apply_window_entry(entry.clone()).is_none());
+
+        let browser_only = PrivateBrowsingPolicy::BrowserOnly.apply_window_entry(entry.clone()).unwrap();
+        assert_eq!((browser_only.title.as_str(), browser_only.pid, browser_only.process_name.as_str()), ("", 0, "chrome"));
+
+        let off = PrivateBrowsingPolicy::Off.apply_window_entry(entry.clone()).unwrap();
+        assert_eq!((off.title, off.pid), (entry.title, 42));
+    }
+}
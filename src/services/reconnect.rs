@@ -0,0 +1,147 @@
+//! Reconnect backoff
+//! Exponential backoff with jitter, configured via `[reporter.reconnect]` in config.toml,
+//! so a fleet of clients does not reconnect in lockstep after a server restart
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime};
+
+/// Lower bound for `initial_delay_ms` and `max_delay_ms`, so a zero delay cannot spin
+pub const MIN_DELAY_MS: u64 = 100;
+
+/// `[reporter.reconnect]`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReconnectPolicy {
+    /// Delay before the first retry, in milliseconds (at least `MIN_DELAY_MS`)
+    pub initial_delay_ms: u64,
+    /// Factor the delay grows by after every failed attempt (values below 1 are treated as 1)
+    pub multiplier: f64,
+    /// Upper bound for the delay, in milliseconds (at least `initial_delay_ms`)
+    pub max_delay_ms: u64,
+    /// Fraction of the delay that is randomized, 0.0 - 1.0; a delay `d` becomes a random
+    /// value in `[d * (1 - jitter), d]`
+    pub jitter: f64,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay_ms: 1000,
+            multiplier: 2.0,
+            max_delay_ms: 60_000,
+            jitter: 0.5,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Clamp values that would retry without waiting or do not make sense; applied when the
+    /// config is loaded
+    pub fn sanitized(self) -> Self {
+        let initial_delay_ms = self.initial_delay_ms.max(MIN_DELAY_MS);
+        Self {
+            initial_delay_ms,
+            multiplier: if self.multiplier.is_finite() { self.multiplier.max(1.0) } else { 1.0 },
+            max_delay_ms: self.max_delay_ms.max(initial_delay_ms),
+            jitter: if self.jitter.is_finite() { self.jitter.clamp(0.0, 1.0) } else { 0.0 },
+        }
+    }
+
+    /// Delay before retry number `attempt` (starting at 1), without jitter
+    pub fn base_delay(&self, attempt: u32) -> Duration {
+        let multiplier = if self.multiplier.is_finite() { self.multiplier.max(1.0) } else { 1.0 };
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_delay_ms as f64 * multiplier.powi(exponent);
+        Duration::from_millis(delay.min(self.max_delay_ms as f64) as u64)
+    }
+
+    /// Delay before retry number `attempt` (starting at 1), with jitter applied
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let jitter = if self.jitter.is_finite() { self.jitter.clamp(0.0, 1.0) } else { 0.0 };
+        self.base_delay(attempt).mul_f64(1.0 - jitter * random_fraction())
+    }
+}
+
+/// Retry progress of the reporter connection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryState {
+    /// Failed attempts since the last successful connection
+    pub attempt: u32,
+    /// When the next attempt starts; `None` while connected or connecting
+    pub next_retry: Option<SystemTime>,
+}
+
+impl RetryState {
+    /// Count a failed attempt and schedule the next one; returns the delay until then.
+    /// Reset with `RetryState::default()` after a successful connection.
+    pub fn failed(&mut self, policy: &ReconnectPolicy) -> Duration {
+        self.attempt = self.attempt.saturating_add(1);
+        let delay = policy.delay(self.attempt);
+        self.next_retry = SystemTime::now().checked_add(delay);
+        delay
+    }
+}
+
+/// Uniform value in `[0, 1)`; RandomState is seeded from the OS RNG, which is enough for jitter
+fn random_fraction() -> f64 {
+    (RandomState::new().build_hasher().finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(initial_delay_ms: u64, multiplier: f64, max_delay_ms: u64, jitter: f64) -> ReconnectPolicy {
+        ReconnectPolicy { initial_delay_ms, multiplier, max_delay_ms, jitter }
+    }
+
+    #[test]
+    fn delay_grows_until_the_cap() {
+        let policy = policy(1000, 2.0, 10_000, 0.0);
+        let delays: Vec<u64> = (1..=6).map(|attempt| policy.delay(attempt).as_millis() as u64).collect();
+        assert_eq!(delays, [1000, 2000, 4000, 8000, 10_000, 10_000]);
+        // Huge attempt counts stay at the cap instead of overflowing
+        assert_eq!(policy.delay(u32::MAX), Duration::from_millis(10_000));
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let policy = policy(1000, 2.0, 60_000, 0.5);
+        for attempt in 1..=8 {
+            let base = policy.base_delay(attempt);
+            for _ in 0..100 {
+                let delay = policy.delay(attempt);
+                assert!(delay <= base && delay >= base / 2, "{delay:?} outside {base:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn sanitized_never_retries_without_waiting() {
+        let sanitized = policy(0, 0.5, 0, 3.0).sanitized();
+        assert_eq!(sanitized, policy(MIN_DELAY_MS, 1.0, MIN_DELAY_MS, 1.0));
+        assert_eq!(sanitized.base_delay(1), Duration::from_millis(MIN_DELAY_MS));
+        assert_eq!(sanitized.base_delay(10), Duration::from_millis(MIN_DELAY_MS));
+
+        assert_eq!(policy(500, f64::NAN, 100, f64::INFINITY).sanitized(), policy(500, 1.0, 500, 0.0));
+        assert_eq!(ReconnectPolicy::default().sanitized(), ReconnectPolicy::default());
+    }
+
+    #[test]
+    fn retry_state_backs_off_and_restarts_after_a_successful_connect() {
+        let policy = policy(1000, 2.0, 60_000, 0.0);
+        let mut retry = RetryState::default();
+        let before = SystemTime::now();
+        let delays: Vec<Duration> = (0..5).map(|_| retry.failed(&policy)).collect();
+        assert_eq!(delays.last(), Some(&Duration::from_millis(16_000)));
+        assert_eq!(retry.attempt, 5);
+        assert!(retry.next_retry.unwrap() >= before + Duration::from_millis(16_000));
+
+        // Connected: the next failure starts over at the initial delay
+        retry = RetryState::default();
+        assert_eq!(retry.failed(&policy), Duration::from_millis(1000));
+        assert_eq!(retry.attempt, 1);
+    }
+}
@@ -2,18 +2,21 @@ use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::hash::{Hash, Hasher};
 use std::collections::{hash_map::DefaultHasher, HashMap};
-use std::sync::{Arc, RwLock};
-use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async_tls_with_config, tungstenite::Message, Connector};
-use futures_util::{SinkExt, StreamExt};
-use url::Url;
+use std::sync::{Arc, Mutex, RwLock};
 use tracing::{info, error, warn};
 
-#[cfg(target_os = "windows")]
-use base64::engine::general_purpose::STANDARD as BASE64;
-use base64::Engine;
-
-use crate::platform::{WindowInfo, MediaMetadata, PlaybackState};
+use super::handshake::{HelloMessage, Negotiated, WelcomeMessage};
+use super::outbox::{Outbox, OutboxConfig, OutboxStats, Outgoing, Slot};
+use super::pause::{Pause, PauseFile};
+use super::privacy::PrivacyFilter;
+use super::reconnect::{ReconnectPolicy, RetryState};
+use super::redact::{self, redact};
+use super::private_browsing::PrivateBrowsingPolicy;
+use super::schedule::{Schedule, ScheduleCategory};
+use super::spool::{self, HistoryBatchMessage, SpoolConfig};
+use super::title_rewrite::TitleRewriter;
+use super::transport::{self, Artwork, Incoming, MqttConfig, Transport, TransportKind};
+use crate::platform::{self, WindowInfo, WindowEntry, MediaKind, MediaMetadata, MediaSession, PlaybackState, PlatformProvider, SessionEvent};
 
 /// Callback types for pushing data to frontend (using usize for thread-safe pointer storage)
 pub type LogCallback = Option<extern "C" fn(level: u8, message: *const std::os::raw::c_char, user_data: usize)>;
@@ -25,18 +28,331 @@ pub struct ReporterConfig {
     pub enabled: bool,
     pub ws_url: String,
     pub token: String,
+    /// How `token` is sent to the server
+    #[serde(default)]
+    pub auth_mode: AuthMode,
     #[serde(default)]
     pub enable_media_reporting: bool,
+    /// Interval in seconds between `window_list` messages (0 = disabled)
+    #[serde(default)]
+    pub window_list_interval_secs: u64,
+    /// Seconds without keyboard/mouse input before the user is reported as idle (0 = disabled)
+    #[serde(default = "default_idle_threshold_secs")]
+    pub idle_threshold_secs: u64,
+    /// Which player is reported as `media_playback` when several are active
+    #[serde(default)]
+    pub media_selection: MediaSelectionPolicy,
+    /// Also send a `media_sessions` message listing every active player
+    #[serde(default)]
+    pub report_media_sessions: bool,
+    /// Which players and content are reported at all
+    #[serde(default)]
+    pub media_filter: MediaFilter,
+    /// What to do with private / incognito browser windows
+    ///
+    /// Only Linux has a platform hint (window class / role, process command line); on Windows
+    /// and macOS detection relies on title markers alone, so a browser that leaves them out of
+    /// its window title is not recognised.
+    #[serde(default)]
+    pub private_browsing: PrivateBrowsingPolicy,
+    /// How much of the focused window / window list is reported
+    #[serde(default)]
+    pub window_privacy: WindowPrivacyLevel,
+    /// How much of the playing media is reported
+    #[serde(default)]
+    pub media_privacy: MediaPrivacyLevel,
+    /// Send a `pause_state` message when reporting is paused / resumed via `Reporter::pause`
+    #[serde(default = "default_announce_pause")]
+    pub announce_pause: bool,
+    /// Seconds between WebSocket pings (0 = disabled)
+    #[serde(default = "default_heartbeat_interval_secs")]
+    pub heartbeat_interval_secs: u64,
+    /// Seconds to wait for a pong before the connection is considered dead and reopened
+    #[serde(default = "default_heartbeat_timeout_secs")]
+    pub heartbeat_timeout_secs: u64,
+    /// Backoff between reconnect attempts
+    #[serde(default)]
+    pub reconnect: ReconnectPolicy,
+    /// Limits for messages waiting to be sent
+    #[serde(default)]
+    pub outbox: OutboxConfig,
+    /// Offline history replayed as `history_batch`
+    #[serde(default)]
+    pub spool: SpoolConfig,
+    /// WebSocket, HTTP or MQTT; chosen by the `ws_url` scheme by default
+    #[serde(default)]
+    pub transport: TransportKind,
+    /// Topics and Home Assistant discovery for the MQTT transport
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+}
+
+/// How the token is sent to the server
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMode {
+    /// `Authorization: Bearer <token>` header
+    #[default]
+    Bearer,
+    /// `Sec-WebSocket-Protocol: shikenmatrix, <token>`; the server must select `shikenmatrix`.
+    /// For servers behind proxies that strip the `Authorization` header
+    Subprotocol,
+    /// `?token=<token>` query parameter, for old servers; ends up in proxy access logs
+    Query,
+}
+
+pub(crate) fn default_idle_threshold_secs() -> u64 {
+    300
+}
+
+pub(crate) fn default_announce_pause() -> bool {
+    true
+}
+
+pub(crate) fn default_heartbeat_interval_secs() -> u64 {
+    30
+}
+
+pub(crate) fn default_heartbeat_timeout_secs() -> u64 {
+    10
+}
+
+/// Heartbeat round-trip times of the current connection
+#[derive(Debug, Clone, Copy, Default)]
+struct Latency {
+    last: Option<std::time::Duration>,
+    /// Exponentially weighted average (α = 1/8, like TCP's SRTT)
+    smoothed: Option<std::time::Duration>,
+}
+
+impl Latency {
+    fn record(&mut self, rtt: std::time::Duration) {
+        self.last = Some(rtt);
+        self.smoothed = Some(match self.smoothed {
+            Some(smoothed) => (smoothed * 7 + rtt) / 8,
+            None => rtt,
+        });
+    }
+}
+
+/// Connection health shared between `run_reporter` and `Reporter::status`
+#[derive(Debug, Clone, Copy, Default)]
+struct ConnectionState {
+    latency: Latency,
+    retry: RetryState,
+}
+
+/// Snapshot returned by `Reporter::status`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReporterStatus {
+    pub connected: bool,
+    /// Round-trip time of the last heartbeat; `None` until a pong arrives
+    pub latency: Option<std::time::Duration>,
+    /// Smoothed round-trip time over the current connection
+    pub smoothed_latency: Option<std::time::Duration>,
+    /// Failed connection attempts since the last successful connection
+    pub reconnect_attempt: u32,
+    /// When the next connection attempt starts; `None` while connected or connecting
+    pub next_retry: Option<std::time::SystemTime>,
+    /// Pending, coalesced and dropped outbound messages
+    pub outbox: OutboxStats,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowPrivacyLevel {
+    /// No window reports at all
+    Off,
+    /// Application only: no title, no pid
+    AppOnly,
+    /// Application and title
+    #[default]
+    Full,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaPrivacyLevel {
+    /// No media reports at all
+    Off,
+    /// Track metadata and play state, artwork is neither uploaded nor linked
+    TrackOnly,
+    /// Everything, including artwork
+    #[default]
+    FullWithArtwork,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaSelectionStrategy {
+    /// Playing sessions win over paused ones, then `player_priority`, then the most recently changed
+    #[default]
+    PreferPlaying,
+    /// `player_priority` order wins, then playing over paused, then the most recently changed
+    Priority,
+    /// The session whose track or play state changed last
+    MostRecent,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MediaSelectionPolicy {
+    #[serde(default)]
+    pub strategy: MediaSelectionStrategy,
+    /// Preferred players, highest first; matched case-insensitively as a substring of the
+    /// session id or bundle identifier (e.g. "spotify", "firefox")
+    #[serde(default)]
+    pub player_priority: Vec<String>,
+}
+
+impl MediaSelectionPolicy {
+    /// Pick the session to report; ties keep the platform's order
+    pub fn select<'a>(&self, sessions: &'a [MediaSession]) -> Option<&'a MediaSession> {
+        let playing = |s: &MediaSession| std::cmp::Reverse(s.state.playing);
+        let recent = |s: &MediaSession| std::cmp::Reverse(s.last_changed);
+        sessions.iter().min_by(|a, b| match self.strategy {
+            MediaSelectionStrategy::PreferPlaying => playing(a).cmp(&playing(b))
+                .then(self.priority_rank(a).cmp(&self.priority_rank(b)))
+                .then(recent(a).cmp(&recent(b))),
+            MediaSelectionStrategy::Priority => self.priority_rank(a).cmp(&self.priority_rank(b))
+                .then(playing(a).cmp(&playing(b)))
+                .then(recent(a).cmp(&recent(b))),
+            MediaSelectionStrategy::MostRecent => recent(a).cmp(&recent(b))
+                .then(playing(a).cmp(&playing(b))),
+        })
+    }
+
+    /// Index of the first matching `player_priority` entry; unlisted players rank last
+    fn priority_rank(&self, session: &MediaSession) -> usize {
+        self.player_priority.iter()
+            .position(|p| player_matches(session, p))
+            .unwrap_or(self.player_priority.len())
+    }
+}
+
+/// `[reporter.media_filter]`: sessions rejected here are ignored before selection,
+/// so they are never reported and never shadow an allowed player
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MediaFilter {
+    /// Only report these players (matched like `player_priority`); every player when empty
+    #[serde(default)]
+    pub allow_players: Vec<String>,
+    /// Never report these players
+    #[serde(default)]
+    pub deny_players: Vec<String>,
+    /// Skip tracks shorter than this many seconds (0 = disabled); unknown durations are kept
+    #[serde(default)]
+    pub min_duration_secs: f64,
+    /// Skip sessions the platform reports as video
+    #[serde(default)]
+    pub skip_videos: bool,
+    /// Skip sessions detected as podcasts
+    #[serde(default)]
+    pub skip_podcasts: bool,
+    /// Skip tracks without an artist (typical for browser videos and notification sounds)
+    #[serde(default)]
+    pub require_artist: bool,
+}
+
+impl MediaFilter {
+    /// Whether a session may be reported
+    pub fn allows(&self, session: &MediaSession) -> bool {
+        let metadata = &session.metadata;
+        let matches_any = |players: &[String]| players.iter().any(|p| player_matches(session, p));
+
+        (self.allow_players.is_empty() || matches_any(&self.allow_players))
+            && !matches_any(&self.deny_players)
+            && (metadata.duration <= 0.0 || metadata.duration >= self.min_duration_secs)
+            && !(self.skip_videos && metadata.kind == MediaKind::Video)
+            && !(self.skip_podcasts && metadata.kind == MediaKind::Podcast)
+            && !(self.require_artist && metadata.artist.as_deref().is_none_or(|a| a.trim().is_empty()))
+    }
+}
+
+/// Case-insensitive substring match against the session id or bundle identifier
+fn player_matches(session: &MediaSession, pattern: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let bundle = session.metadata.bundle_identifier.as_deref().unwrap_or("");
+    !pattern.is_empty()
+        && (session.id.to_lowercase().contains(&pattern) || bundle.to_lowercase().contains(&pattern))
 }
 
 #[derive(Debug, Clone)]
 enum ReporterMessage {
     WindowInfo(WindowInfoMessage),
     MediaPlayback(MediaPlaybackMessage),
+    MediaSessions(MediaSessionsMessage),
+    WindowList(WindowListMessage),
+    Presence(PresenceMessage),
+    SessionState(SessionStateMessage),
+    ScheduleState(ScheduleStateMessage),
+    PauseState(PauseStateMessage),
     UploadArtwork { content_item_identifier: String, artwork_data: Vec<u8>, mime_type: String },
     Shutdown,
 }
 
+impl ReporterMessage {
+    /// Message type as announced in `hello` and toggled by `welcome`
+    fn message_type(&self) -> &'static str {
+        match self {
+            Self::WindowInfo(_) => "window_info",
+            Self::MediaPlayback(_) => "media_playback",
+            Self::MediaSessions(_) => "media_sessions",
+            Self::WindowList(_) => "window_list",
+            Self::Presence(_) => "presence",
+            Self::SessionState(_) => "session_state",
+            Self::ScheduleState(_) => "schedule_state",
+            Self::PauseState(_) => "pause_state",
+            Self::UploadArtwork { .. } => "upload_artwork",
+            Self::Shutdown => "shutdown",
+        }
+    }
+
+    /// JSON sent to the server; artwork and shutdown are handled separately
+    fn to_json(&self) -> Option<String> {
+        let json = match self {
+            Self::WindowInfo(msg) => serde_json::to_string(msg),
+            Self::MediaPlayback(msg) => serde_json::to_string(msg),
+            Self::MediaSessions(msg) => serde_json::to_string(msg),
+            Self::WindowList(msg) => serde_json::to_string(msg),
+            Self::Presence(msg) => serde_json::to_string(msg),
+            Self::SessionState(msg) => serde_json::to_string(msg),
+            Self::ScheduleState(msg) => serde_json::to_string(msg),
+            Self::PauseState(msg) => serde_json::to_string(msg),
+            Self::UploadArtwork { .. } | Self::Shutdown => return None,
+        };
+        json.ok()
+    }
+
+    /// JSON kept in the offline spool; lists and artwork are only useful live
+    fn history_value(&self) -> Option<serde_json::Value> {
+        let value = match self {
+            Self::WindowInfo(msg) => serde_json::to_value(msg),
+            Self::MediaPlayback(msg) => serde_json::to_value(msg),
+            Self::Presence(msg) => serde_json::to_value(msg),
+            Self::SessionState(msg) => serde_json::to_value(msg),
+            Self::ScheduleState(msg) => serde_json::to_value(msg),
+            Self::PauseState(msg) => serde_json::to_value(msg),
+            Self::MediaSessions(_) | Self::WindowList(_) | Self::UploadArtwork { .. } | Self::Shutdown => return None,
+        };
+        value.ok()
+    }
+}
+
+impl Outgoing for ReporterMessage {
+    fn slot(&self) -> Slot {
+        match self {
+            // Transitions are events: a pause for one schedule category must not be replaced
+            // by a resume for another; everything else describes the current state
+            Self::SessionState(_) | Self::ScheduleState(_) | Self::PauseState(_) => Slot::Event,
+            Self::UploadArtwork { content_item_identifier, artwork_data, .. } => Slot::Keyed {
+                key: content_item_identifier.clone(),
+                bytes: artwork_data.len(),
+            },
+            _ => Slot::Latest(self.message_type()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 struct ServerMessage {
     #[serde(rename = "type")]
@@ -63,11 +379,68 @@ struct MediaPlaybackMessage {
 }
 
 #[derive(Debug, Clone, Serialize)]
-struct UploadArtworkMetaMessage {
+struct MediaSessionsMessage {
+    #[serde(rename = "type")]
+    msg_type: String,
+    sessions: Vec<MediaSessionData>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WindowListMessage {
+    #[serde(rename = "type")]
+    msg_type: String,
+    /// Top-level windows, topmost first
+    windows: Vec<WindowEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum PresenceState {
+    Active,
+    Idle,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PresenceMessage {
+    #[serde(rename = "type")]
+    msg_type: String,
+    state: PresenceState,
+    /// Unix time (ms) of the last user input, only set while idle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    idle_since: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SessionStateMessage {
+    #[serde(rename = "type")]
+    msg_type: String,
+    state: SessionEvent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ReportingState {
+    Paused,
+    Resumed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ScheduleStateMessage {
+    #[serde(rename = "type")]
+    msg_type: String,
+    state: ReportingState,
+    /// Data types whose reporting was paused / resumed by the schedule
+    categories: Vec<ScheduleCategory>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PauseStateMessage {
     #[serde(rename = "type")]
     msg_type: String,
-    content_item_identifier: String,
-    mime_type: String,
+    state: ReportingState,
+    /// Unix time (ms) the pause ends, only set while paused with an expiry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    until: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Hash)]
@@ -107,114 +480,187 @@ struct PlaybackStateData {
     playing: bool,
     playback_rate: f64,
     elapsed_time: f64,
+    /// Unix time (ms) at which `elapsed_time` was sampled; servers extrapolate from this anchor
+    position_timestamp: u64,
 }
 
 impl Hash for PlaybackStateData {
+    // Position is deliberately excluded: it advances on its own, seeks are detected by extrapolation
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.playing.hash(state);
         ((self.playback_rate * 100.0) as i64).hash(state);
-        (self.elapsed_time as i64).hash(state);
     }
 }
 
+#[derive(Debug, Clone, Serialize, PartialEq, Hash)]
+struct MediaSessionData {
+    id: String,
+    /// Whether this is the session reported as `media_playback`
+    selected: bool,
+    metadata: MediaMetadataData,
+    playback_state: PlaybackStateData,
+    /// Unix time (ms) of the last track or play state change
+    last_changed: u64,
+}
+
+/// Maximum drift between the reported and the extrapolated position before it counts as a seek
+const SEEK_TOLERANCE_SECS: f64 = 2.0;
+
+/// Whether `new` differs from `last` by more than the natural progress of playback
+fn is_playback_changed(last: Option<&PlaybackState>, new: &PlaybackState) -> bool {
+    let Some(last) = last else { return true };
+    if last.playing != new.playing || (last.playback_rate - new.playback_rate).abs() >= 0.01 {
+        return true;
+    }
+    (last.position_at(new.timestamp) - new.elapsed_time).abs() > SEEK_TOLERANCE_SECS
+}
+
 fn compute_hash<T: Hash>(data: &T) -> u64 {
     let mut hasher = DefaultHasher::new();
     data.hash(&mut hasher);
     hasher.finish()
 }
 
+fn unix_millis(time: std::time::SystemTime) -> u64 {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 #[derive(Clone)]
 pub struct Reporter {
     config: Arc<RwLock<ReporterConfig>>,
-    tx: mpsc::UnboundedSender<ReporterMessage>,
+    outbox: Arc<Outbox<ReporterMessage>>,
     last_window_hash: Arc<AtomicU64>,
     last_media_hash: Arc<AtomicU64>,
+    last_media_sessions_hash: Arc<AtomicU64>,
+    last_window_list_hash: Arc<AtomicU64>,
+    /// Bumped by `request_resync`; the monitoring threads then forget their last seen state too
+    resync_generation: Arc<AtomicU64>,
+    last_sent_playback: Arc<RwLock<Option<PlaybackState>>>,
     artwork_urls: Arc<RwLock<HashMap<String, String>>>,
     is_connected: Arc<AtomicBool>,
+    connection: Arc<RwLock<ConnectionState>>,
     log_callback: Arc<RwLock<LogCallback>>,
     window_callback: Arc<RwLock<WindowDataCallback>>,
     media_callback: Arc<RwLock<MediaDataCallback>>,
     callback_user_data: Arc<AtomicUsize>,
     is_running: Arc<AtomicBool>,
+    /// Screen locked: window/media reporting is paused
+    session_locked: Arc<AtomicBool>,
+    /// System suspending: window/media reporting is paused
+    session_suspended: Arc<AtomicBool>,
+    /// Paused by the user (`pause` / `resume`); authoritative over pause.toml
+    pause: Arc<RwLock<Option<Pause>>>,
+    /// pause.toml, only applied when another process changed it; held while saving and applying
+    pause_file: Arc<Mutex<PauseFile>>,
+    /// Redaction applied to everything before it is reported
+    privacy: Arc<RwLock<PrivacyFilter>>,
+    /// Title transforms applied after the privacy rules, before hashing
+    title_rewriter: Arc<RwLock<TitleRewriter>>,
+    /// Weekday / time ranges during which each data type may be reported
+    schedule: Arc<RwLock<Schedule>>,
+    /// Wakes `run_reporter` to drop the current connection and reconnect without delay
+    reconnect_now: Arc<tokio::sync::Notify>,
+    platform: Arc<dyn PlatformProvider>,
 }
 
 impl Reporter {
     pub fn new(config: ReporterConfig) -> Self {
-        let config = Arc::new(RwLock::new(config));
-        let artwork_urls = Arc::new(RwLock::new(HashMap::new()));
-        let is_connected = Arc::new(AtomicBool::new(false));
-        let is_running = Arc::new(AtomicBool::new(true));
-        
-        let (tx, rx) = mpsc::unbounded_channel();
-
-        let config_clone = config.clone();
-        let artwork_urls_clone = artwork_urls.clone();
-        let is_connected_clone = is_connected.clone();
-        let is_running_clone = is_running.clone();
-        
-        // Use std::thread to create independent runtime (avoids FFI context issues)
-        // Use current_thread runtime to minimize memory usage (saves ~10 threads vs multi_thread)
-        std::thread::spawn(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .expect("Failed to create tokio runtime");
-            rt.block_on(Self::run_reporter(config_clone, rx, artwork_urls_clone, is_connected_clone, is_running_clone));
-        });
-
-        let reporter = Self {
-            config,
-            tx,
-            last_window_hash: Arc::new(AtomicU64::new(0)),
-            last_media_hash: Arc::new(AtomicU64::new(0)),
-            artwork_urls,
-            is_connected,
-            log_callback: Arc::new(RwLock::new(None)),
-            window_callback: Arc::new(RwLock::new(None)),
-            media_callback: Arc::new(RwLock::new(None)),
-            callback_user_data: Arc::new(AtomicUsize::new(0)),
-            is_running,
-        };
-
-        // Start window monitoring in a separate thread
-        reporter.start_window_monitoring();
+        Self::with_platform(config, platform::native())
+    }
 
-        reporter
+    /// Create with a custom platform provider (e.g. `platform::fake::FakePlatform`)
+    pub fn with_platform(config: ReporterConfig, platform: Arc<dyn PlatformProvider>) -> Self {
+        Self::build(config, platform, |run| {
+            // Use std::thread to create independent runtime (avoids FFI context issues)
+            // Use current_thread runtime to minimize memory usage (saves ~10 threads vs multi_thread)
+            std::thread::spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Failed to create tokio runtime");
+                rt.block_on(run);
+            });
+        })
     }
 
     /// For FFI: create with external runtime handle
     pub fn new_with_handle(config: ReporterConfig, handle: tokio::runtime::Handle) -> Self {
+        Self::with_platform_and_handle(config, platform::native(), handle)
+    }
+
+    /// Create with a custom platform provider on an external runtime handle
+    pub fn with_platform_and_handle(
+        config: ReporterConfig,
+        platform: Arc<dyn PlatformProvider>,
+        handle: tokio::runtime::Handle,
+    ) -> Self {
+        Self::build(config, platform, |run| {
+            handle.spawn(run);
+        })
+    }
+
+    fn build(
+        config: ReporterConfig,
+        platform: Arc<dyn PlatformProvider>,
+        spawn: impl FnOnce(std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>),
+    ) -> Self {
+        redact::register_secret(&config.token);
+        let outbox = Arc::new(Outbox::new(config.outbox));
         let config = Arc::new(RwLock::new(config));
         let artwork_urls = Arc::new(RwLock::new(HashMap::new()));
         let is_connected = Arc::new(AtomicBool::new(false));
+        let connection = Arc::new(RwLock::new(ConnectionState::default()));
         let is_running = Arc::new(AtomicBool::new(true));
-        let (tx, rx) = mpsc::unbounded_channel();
+        let reconnect_now = Arc::new(tokio::sync::Notify::new());
 
-        let config_clone = config.clone();
-        let artwork_urls_clone = artwork_urls.clone();
-        let is_connected_clone = is_connected.clone();
-        let is_running_clone = is_running.clone();
-        
-        handle.spawn(async move {
-            Self::run_reporter(config_clone, rx, artwork_urls_clone, is_connected_clone, is_running_clone).await;
-        });
+        spawn(Box::pin(Self::run_reporter(
+            config.clone(),
+            outbox.clone(),
+            artwork_urls.clone(),
+            is_connected.clone(),
+            connection.clone(),
+            is_running.clone(),
+            reconnect_now.clone(),
+        )));
 
         let reporter = Self {
             config,
-            tx,
+            outbox,
             last_window_hash: Arc::new(AtomicU64::new(0)),
             last_media_hash: Arc::new(AtomicU64::new(0)),
+            last_media_sessions_hash: Arc::new(AtomicU64::new(0)),
+            last_window_list_hash: Arc::new(AtomicU64::new(0)),
+            resync_generation: Arc::new(AtomicU64::new(0)),
+            last_sent_playback: Arc::new(RwLock::new(None)),
             artwork_urls,
             is_connected,
+            connection,
             log_callback: Arc::new(RwLock::new(None)),
             window_callback: Arc::new(RwLock::new(None)),
             media_callback: Arc::new(RwLock::new(None)),
             callback_user_data: Arc::new(AtomicUsize::new(0)),
             is_running,
+            session_locked: Arc::new(AtomicBool::new(false)),
+            session_suspended: Arc::new(AtomicBool::new(false)),
+            pause: Arc::new(RwLock::new(None)),
+            pause_file: Arc::new(Mutex::new(PauseFile::new())),
+            privacy: Arc::new(RwLock::new(PrivacyFilter::default())),
+            title_rewriter: Arc::new(RwLock::new(TitleRewriter::default())),
+            schedule: Arc::new(RwLock::new(Schedule::default())),
+            reconnect_now,
+            platform,
         };
 
-        // Start window monitoring in a separate thread
+        // Start window and media monitoring in separate threads
         reporter.start_window_monitoring();
+        reporter.start_media_monitoring();
+        reporter.start_window_list_reporting();
+        reporter.start_presence_monitoring();
+        reporter.start_session_monitoring();
+        reporter.start_schedule_monitoring();
+        reporter.start_pause_monitoring();
 
         reporter
     }
@@ -222,7 +668,7 @@ impl Reporter {
     pub fn stop(&self) {
         self.is_running.store(false, Ordering::Relaxed);
         // Send shutdown message to break out of tokio::select! in run_reporter
-        let _ = self.tx.send(ReporterMessage::Shutdown);
+        self.outbox.push(ReporterMessage::Shutdown);
     }
     
     /// Set callback for logs
@@ -249,8 +695,59 @@ impl Reporter {
         self.callback_user_data.store(user_data, Ordering::Relaxed);
     }
     
+    /// Set the privacy rules applied to window, media and artwork reports
+    pub fn set_privacy_filter(&self, filter: PrivacyFilter) {
+        if let Ok(mut privacy) = self.privacy.write() {
+            *privacy = filter;
+        }
+    }
+
+    /// Set the transforms applied to window titles before they are reported
+    pub fn set_title_rewriter(&self, rewriter: TitleRewriter) {
+        if let Ok(mut title_rewriter) = self.title_rewriter.write() {
+            *title_rewriter = rewriter;
+        }
+    }
+
+    /// Set the weekday / time ranges during which reporting is active
+    pub fn set_schedule(&self, schedule: Schedule) {
+        if let Ok(mut current) = self.schedule.write() {
+            *current = schedule;
+        }
+    }
+
+    /// Pause reporting without closing the connection; persisted so it survives restarts
+    ///
+    /// The pause is applied even if it cannot be persisted, the error is still returned; it then
+    /// lasts until it expires, `resume` is called or another process changes pause.toml.
+    pub fn pause(&self, pause: Pause) -> Result<(), String> {
+        self.set_pause(Some(pause))
+    }
+
+    /// Resume reporting after `pause`; like `pause`, applied even if pause.toml cannot be updated
+    pub fn resume(&self) -> Result<(), String> {
+        self.set_pause(None)
+    }
+
+    fn set_pause(&self, pause: Option<Pause>) -> Result<(), String> {
+        // Holding the file lock keeps the monitor from applying an older file state in between
+        let mut file = self.pause_file.lock().map_err(|_| "Pause file lock poisoned".to_string())?;
+        let saved = file.save(pause);
+        self.apply_pause(pause);
+        saved
+    }
+
+    /// The active pause, if any
+    pub fn paused(&self) -> Option<Pause> {
+        self.pause.read().ok()
+            .and_then(|pause| *pause)
+            .filter(|pause| !pause.is_expired(std::time::SystemTime::now()))
+    }
+
     /// Push log to frontend
     fn push_log(&self, level: u8, message: &str) {
+        let message = redact(message);
+        let message = message.as_ref();
         info!("🔔 push_log called: level={}, message={}", level, message);
         if let Ok(callback) = self.log_callback.read() {
             if let Some(cb) = *callback {
@@ -313,20 +810,28 @@ impl Reporter {
 
     /// Start monitoring window changes in a background thread
     fn start_window_monitoring(&self) {
+        // Poll interval when the platform cannot push focus changes
+        const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
         let reporter_clone = self.clone();
+        let window_source = self.platform.window_source();
+        let mut window_events = window_source.subscribe();
         
         std::thread::spawn(move || {
             // Wait a bit for callbacks to be registered from frontend
             std::thread::sleep(std::time::Duration::from_millis(500));
             
-            reporter_clone.push_log(0, "窗口监控已启动");
+            if window_events.is_some() {
+                reporter_clone.push_log(0, "窗口监控已启动（事件驱动）");
+            } else {
+                reporter_clone.push_log(0, "窗口监控已启动（轮询）");
+            }
             let mut permission_warned = false;
             let mut check_count = 0;
             
             // Allow comparison of Option<T>
-            let mut last_window_info: Option<crate::platform::WindowInfo> = None;
-            let mut last_media_metadata: Option<crate::platform::MediaMetadata> = None;
-            let mut last_playback_state: Option<crate::platform::PlaybackState> = None;
+            let mut last_window_info: Option<WindowInfo> = None;
+            let mut resync_generation = reporter_clone.resync_generation.load(Ordering::Relaxed);
             
             loop {
                 // Check running status
@@ -334,8 +839,33 @@ impl Reporter {
                     reporter_clone.push_log(0, "监控线程正在退出...");
                     break;
                 }
+
+                // A resync (e.g. the schedule resumed) re-reports the window even if it did not change
+                let generation = reporter_clone.resync_generation.load(Ordering::Relaxed);
+                if generation != resync_generation {
+                    resync_generation = generation;
+                    last_window_info = None;
+                }
                 
-                std::thread::sleep(std::time::Duration::from_secs(1));
+                // Wait for the next focus/title change, or poll when no subscription is available
+                let window_result = match window_events.as_ref() {
+                    Some(events) => match events.recv_timeout(POLL_INTERVAL) {
+                        Ok(window_info) => Some(Ok(window_info)),
+                        // Nothing reported yet (e.g. the reporter was disabled): fetch it once
+                        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                            last_window_info.is_none().then(|| window_source.frontmost_window())
+                        }
+                        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                            reporter_clone.push_log(1, "窗口事件订阅已断开，回退到轮询");
+                            window_events = None;
+                            None
+                        }
+                    },
+                    None => {
+                        std::thread::sleep(POLL_INTERVAL);
+                        Some(window_source.frontmost_window())
+                    }
+                };
                 check_count += 1;
                 
                 // Check if reporter is enabled
@@ -343,344 +873,757 @@ impl Reporter {
                     .map(|cfg| cfg.enabled)
                     .unwrap_or(false);
                 
-                if !enabled {
-                    if check_count % 10 == 0 {
+                if !enabled || reporter_clone.is_paused() {
+                    if !enabled && check_count % 10 == 0 {
                         reporter_clone.push_log(0, &format!("窗口监控: reporter 已禁用，跳过检查 #{}", check_count));
                     }
-                    continue; // Skip monitoring if disabled
-                }
-                
-                #[cfg(target_os = "macos")]
-                {
-                    objc2::rc::autoreleasepool(|_| {
-                        // Monitor window info
-                        match crate::platform::macos::get_frontmost_window_info_sync() {
-                            Ok(window_info) => {
-                                if last_window_info.as_ref() != Some(&window_info) {
-                                    let log_msg = format!("获取到窗口信息: {} ({})", window_info.title, window_info.process_name);
-                                    reporter_clone.push_log(0, &log_msg);
-                                    
-                                    // Push window data to frontend (with icon if available)
-                                    reporter_clone.push_window_data(
-                                        &window_info.title, 
-                                        &window_info.process_name, 
-                                        window_info.pid as u32,
-                                        window_info.icon_data.as_deref()
-                                    );
-                                    
-                                    reporter_clone.send_window_info(&window_info);
-                                    permission_warned = false; // Reset warning flag on success
-                                    
-                                    last_window_info = Some(window_info);
-                                }
-                            }
-                            Err(e) => {
-                                if !permission_warned {
-                                    let err_msg = format!("获取窗口信息失败: {}", e);
-                                    reporter_clone.push_log(1, &err_msg);
-                                    permission_warned = true; // Only warn once
-                                }
-                            }
-                        }
-                    
-                        // Monitor media playback (every second)
-                        // DISABLED by default - set ENABLE_MEDIA_REPORTING=1 to enable
-                        if std::env::var("ENABLE_MEDIA_REPORTING").unwrap_or_default() == "1" {
-                            if let Ok(Some(metadata)) = crate::platform::macos::get_media_metadata() {
-                                if let Ok(Some(state)) = crate::platform::macos::get_playback_state() {
-                                    
-                                    let metadata_changed = last_media_metadata.as_ref() != Some(&metadata);
-                                    let state_changed = last_playback_state.as_ref() != Some(&state);
-
-                                    if metadata_changed || state_changed {
-                                        // Get artwork slice directly from Arc (no decoding needed)
-                                        let artwork_slice = metadata.artwork_data.as_deref().map(|v| v.as_slice());
-                                        
-                                        // Push media data to frontend
-                                        let title = metadata.title.as_deref().unwrap_or("未知");
-                                        let artist = metadata.artist.as_deref().unwrap_or("未知");
-                                        let album = metadata.album.as_deref().unwrap_or("未知");
-                                        reporter_clone.push_media_data(
-                                            title, 
-                                            artist, 
-                                            album, 
-                                            metadata.duration, 
-                                            state.elapsed_time, 
-                                            state.playing,
-                                            artwork_slice
-                                        );
-                                        
-                                        reporter_clone.send_media_playback(&metadata, &state);
-
-                                        // Upload artwork if available and not cached (only if metadata changed)
-                                        if metadata_changed {
-                                            if let (Some(artwork_data), Some(mime_type), Some(content_id)) =
-                                                (metadata.artwork_data.as_ref(), metadata.artwork_mime_type.as_ref(), metadata.content_item_identifier.as_ref()) {
-                                                // Check if already cached
-                                                let needs_upload = reporter_clone.artwork_urls.read()
-                                                    .map(|urls| !urls.contains_key(content_id))
-                                                    .unwrap_or(true);
-
-                                                if needs_upload {
-                                                    reporter_clone.upload_artwork(content_id.clone(), artwork_data.to_vec(), mime_type.clone());
-                                                }
-                                            }
-                                        }
-                                        
-                                        last_media_metadata = Some(metadata);
-                                        last_playback_state = Some(state);
-                                    }
-                                }
-                            }
-                        }
-                    });
+                    last_window_info = None;
+                    continue; // Skip monitoring if disabled, paused, locked or suspending
                 }
                 
-                #[cfg(target_os = "windows")]
-                {
-                    // Monitor window info
-                    match crate::platform::windows::get_frontmost_window() {
-                        Ok(window_info) => {
-                            if last_window_info.as_ref() != Some(&window_info) {
-                                let log_msg = format!("获取到窗口信息: {} ({})", window_info.title, window_info.process_name);
-                                reporter_clone.push_log(0, &log_msg);
-                                
-                                // Push window data to frontend (with icon if available)
-                                reporter_clone.push_window_data(
-                                    &window_info.title, 
-                                    &window_info.process_name, 
-                                    window_info.pid as u32, 
-                                    window_info.icon_data.as_ref().map(|v| v.as_slice())
-                                );
-                                
-                                reporter_clone.send_window_info(&window_info);
-                                permission_warned = false; // Reset warning flag on success
-                                
-                                last_window_info = Some(window_info);
-                            }
-                        }
-                        Err(e) => {
-                            if !permission_warned {
-                                let err_msg = format!("获取窗口信息失败: {}", e);
-                                reporter_clone.push_log(1, &err_msg);
-                                permission_warned = true;
-                            }
-                        }
+                // Monitor window info
+                match window_result {
+                    Some(Ok(window_info)) if last_window_info.as_ref() != Some(&window_info) => {
+                        let log_msg = format!("获取到窗口信息: {} ({})", window_info.title, window_info.process_name);
+                        reporter_clone.push_log(0, &log_msg);
+                        
+                        // Push window data to frontend (with icon if available)
+                        reporter_clone.push_window_data(
+                            &window_info.title, 
+                            &window_info.process_name, 
+                            window_info.pid as u32,
+                            window_info.icon_data.as_deref()
+                        );
+                        
+                        reporter_clone.send_window_info(&window_info);
+                        permission_warned = false; // Reset warning flag on success
+                        
+                        last_window_info = Some(window_info);
                     }
-
-                    // Monitor media playback (every second)
-                    // DISABLED by default - set ENABLE_MEDIA_REPORTING=1 to enable
-                    if std::env::var("ENABLE_MEDIA_REPORTING").unwrap_or_default() == "1" {
-                        if let Ok(Some(metadata)) = crate::platform::windows::get_media_metadata() {
-                            if let Ok(Some(state)) = crate::platform::windows::get_playback_state() {
-
-                                let metadata_changed = last_media_metadata.as_ref() != Some(&metadata);
-                                let state_changed = last_playback_state.as_ref() != Some(&state);
-
-                                if metadata_changed || state_changed {
-                                    // Decode Base64 artwork if available
-                                    let artwork_vec = metadata.artwork_data.as_ref().and_then(|b64| {
-                                        BASE64.decode(b64).ok()
-                                    });
-                                    let artwork_slice = artwork_vec.as_ref().map(|v: &Vec<u8>| v.as_slice());
-
-                                    // Push media data to frontend
-                                    let title = metadata.title.as_deref().unwrap_or("未知");
-                                    let artist = metadata.artist.as_deref().unwrap_or("未知");
-                                    let album = metadata.album.as_deref().unwrap_or("未知");
-                                    reporter_clone.push_media_data(
-                                        title,
-                                        artist,
-                                        album,
-                                        metadata.duration,
-                                        state.elapsed_time,
-                                        state.playing,
-                                        artwork_slice.as_deref()
-                                    );
-
-                                    // Note: Windows doesn't send_media_playback or upload_artwork
-                                    // due to platform differences in media API structure
-
-                                    last_media_metadata = Some(metadata);
-                                    last_playback_state = Some(state);
-                                }
-                            }
-                        }
+                    Some(Err(e)) if !permission_warned => {
+                        let err_msg = format!("获取窗口信息失败: {}", e);
+                        reporter_clone.push_log(1, &err_msg);
+                        permission_warned = true; // Only warn once
                     }
+                    _ => {}
                 }
             }
         });
     }
 
-    pub fn is_connected(&self) -> bool {
-        self.is_connected.load(Ordering::Relaxed)
-    }
+    /// Start monitoring media playback in a background thread
+    ///
+    /// Subscribes to platform media events when available; otherwise polls every second.
+    fn start_media_monitoring(&self) {
+        const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
 
-    async fn run_reporter(
-        config: Arc<RwLock<ReporterConfig>>,
-        mut rx: mpsc::UnboundedReceiver<ReporterMessage>,
-        artwork_urls: Arc<RwLock<HashMap<String, String>>>,
-        is_connected: Arc<AtomicBool>,
-        is_running: Arc<AtomicBool>,
-    ) {
-        let mut reconnect_attempts = 0;
-        const MAX_RECONNECT_ATTEMPTS: u32 = 5;
-        const RECONNECT_INTERVAL: u64 = 3000;
+        // DISABLED by default - set ENABLE_MEDIA_REPORTING=1 to enable
+        if std::env::var("ENABLE_MEDIA_REPORTING").unwrap_or_default() != "1" {
+            return;
+        }
 
-        loop {
-            // Check if stop requested
-            if !is_running.load(Ordering::Relaxed) {
-                info!("Reporter stopping (run_reporter loop)");
-                break;
-            }
+        let reporter_clone = self.clone();
+        let media_source = self.platform.media_source();
+        let mut media_events = media_source.subscribe();
 
-            let cfg = config.read().unwrap().clone();
+        std::thread::spawn(move || {
+            // Wait a bit for callbacks to be registered from frontend
+            std::thread::sleep(std::time::Duration::from_millis(500));
 
-            if !cfg.enabled {
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                continue;
+            if media_events.is_some() {
+                reporter_clone.push_log(0, "媒体监控已启动（事件驱动）");
+            } else {
+                reporter_clone.push_log(0, "媒体监控已启动（轮询）");
             }
 
-            let ws_url_str = cfg.ws_url
-                .replace("http://", "ws://")
-                .replace("https://", "wss://");
+            let mut last_media_metadata: Option<MediaMetadata> = None;
+            let mut last_playback_state: Option<PlaybackState> = None;
+            // Set while reporting is off, so the current state is fetched once it resumes
+            let mut needs_resync = false;
+            let mut resync_generation = reporter_clone.resync_generation.load(Ordering::Relaxed);
+
+            loop {
+                if !reporter_clone.is_running.load(Ordering::Relaxed) {
+                    break;
+                }
 
-            let ws_url = match Url::parse(&ws_url_str) {
-                Ok(mut url) => {
-                    url.query_pairs_mut().append_pair("token", &cfg.token);
-                    url
+                let generation = reporter_clone.resync_generation.load(Ordering::Relaxed);
+                if generation != resync_generation {
+                    resync_generation = generation;
+                    last_media_metadata = None;
+                    last_playback_state = None;
+                    needs_resync = true;
                 }
-                Err(e) => {
-                    error!("Invalid WebSocket URL: {}", e);
-                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+
+                // Wait for the next media change, or poll when no subscription is available
+                let sessions = match media_events.as_ref() {
+                    Some(events) => match events.recv_timeout(POLL_INTERVAL) {
+                        Ok(sessions) => sessions,
+                        Err(std::sync::mpsc::RecvTimeoutError::Timeout) if needs_resync => {
+                            match media_source.sessions() {
+                                Ok(sessions) => sessions,
+                                Err(_) => continue,
+                            }
+                        }
+                        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+                        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                            reporter_clone.push_log(1, "媒体事件订阅已断开，回退到轮询");
+                            media_events = None;
+                            continue;
+                        }
+                    },
+                    None => {
+                        std::thread::sleep(POLL_INTERVAL);
+                        match media_source.sessions() {
+                            Ok(sessions) => sessions,
+                            Err(_) => continue,
+                        }
+                    }
+                };
+
+                let Ok((enabled, policy, filter, report_sessions)) = reporter_clone.config.read()
+                    .map(|cfg| (cfg.enabled, cfg.media_selection.clone(), cfg.media_filter.clone(), cfg.report_media_sessions))
+                else { continue };
+                if !enabled || reporter_clone.is_paused() {
+                    // Report the current state again once re-enabled
+                    last_media_metadata = None;
+                    last_playback_state = None;
+                    needs_resync = true;
                     continue;
                 }
-            };
+                needs_resync = false;
 
-            info!("Connecting to WebSocket: {}", ws_url);
-            is_connected.store(false, Ordering::Relaxed);
+                let sessions: Vec<MediaSession> = sessions.into_iter()
+                    .filter(|session| filter.allows(session))
+                    .collect();
+                let selected = policy.select(&sessions);
+                if report_sessions {
+                    reporter_clone.send_media_sessions(&sessions, selected.map(|s| s.id.as_str()));
+                }
 
-            // Create TLS connector that forces HTTP/1.1 (required for WebSocket over HTTPS)
-            let connector = Connector::Rustls(Arc::new(
-                rustls::ClientConfig::builder()
-                    .with_root_certificates(rustls::RootCertStore::from_iter(
-                        webpki_roots::TLS_SERVER_ROOTS.iter().cloned()
-                    ))
-                    .with_no_client_auth()
-            ));
+                let Some(MediaSession { metadata, state, .. }) = selected.cloned() else { continue };
+                // Redact before anything (including artwork) is reported; dropped players are skipped
+                let Some(metadata) = reporter_clone.privacy.read().ok().and_then(|p| p.apply_media(&metadata)) else { continue };
+                let metadata_changed = last_media_metadata.as_ref() != Some(&metadata);
+                let state_changed = is_playback_changed(last_playback_state.as_ref(), &state);
+                if !metadata_changed && !state_changed {
+                    continue;
+                }
 
-            let connect_result = tokio::time::timeout(
-                tokio::time::Duration::from_secs(15),
-                connect_async_tls_with_config(ws_url.as_str(), None, false, Some(connector))
-            ).await;
+                // Get artwork slice directly from Arc (no decoding needed)
+                let artwork_slice = metadata.artwork_data.as_deref().map(|v| v.as_slice());
 
-            match connect_result {
-                Ok(Ok((ws_stream, response))) => {
-                    info!("✅ WebSocket connected! Status: {}", response.status());
-                    is_connected.store(true, Ordering::Relaxed);
-                    reconnect_attempts = 0;
-
-                    let (mut write, mut read) = ws_stream.split();
-                    let is_connected_clone_inner = is_connected.clone();
-
-                    loop {
-                        tokio::select! {
-                            Some(msg) = rx.recv() => {
-                                match msg {
-                                    ReporterMessage::WindowInfo(window_msg) => {
-                                        if let Ok(json) = serde_json::to_string(&window_msg) {
-                                            if let Err(e) = write.send(Message::Text(json.into())).await {
-                                                error!("Failed to send window message: {}", e);
-                                                break;
-                                            }
-                                        }
-                                    }
-                                    ReporterMessage::MediaPlayback(media_msg) => {
-                                        if let Ok(json) = serde_json::to_string(&media_msg) {
-                                            if let Err(e) = write.send(Message::Text(json.into())).await {
-                                                error!("Failed to send media message: {}", e);
-                                                break;
-                                            }
-                                        }
+                // Push media data to frontend
+                let title = metadata.title.as_deref().unwrap_or("未知");
+                let artist = metadata.artist.as_deref().unwrap_or("未知");
+                let album = metadata.album.as_deref().unwrap_or("未知");
+                reporter_clone.push_media_data(
+                    title, 
+                    artist, 
+                    album, 
+                    metadata.duration, 
+                    state.elapsed_time, 
+                    state.playing,
+                    artwork_slice
+                );
+
+                reporter_clone.send_media_playback(&metadata, &state);
+
+                // Upload artwork if available and not cached (only if metadata changed)
+                if metadata_changed {
+                    if let (Some(artwork_data), Some(mime_type), Some(content_id)) =
+                        (metadata.artwork_data.as_ref(), metadata.artwork_mime_type.as_ref(), metadata.content_item_identifier.as_ref()) {
+                        // Check if already cached
+                        let needs_upload = reporter_clone.artwork_urls.read()
+                            .map(|urls| !urls.contains_key(content_id))
+                            .unwrap_or(true);
+
+                        if needs_upload {
+                            reporter_clone.upload_artwork(content_id.clone(), artwork_data.to_vec(), mime_type.clone());
+                        }
+                    }
+                }
+
+                last_media_metadata = Some(metadata);
+                last_playback_state = Some(state);
+            }
+        });
+    }
+
+    /// Periodically report all top-level windows (opt-in via `window_list_interval_secs`)
+    fn start_window_list_reporting(&self) {
+        // How often to re-check the config while the feature is off
+        const IDLE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+        let reporter_clone = self.clone();
+        let window_source = self.platform.window_source();
+
+        std::thread::spawn(move || {
+            let mut last_report: Option<std::time::Instant> = None;
+
+            while reporter_clone.is_running.load(Ordering::Relaxed) {
+                std::thread::sleep(IDLE_CHECK_INTERVAL);
+
+                let (enabled, interval_secs) = reporter_clone.config.read()
+                    .map(|cfg| (cfg.enabled, cfg.window_list_interval_secs))
+                    .unwrap_or((false, 0));
+                if !enabled || interval_secs == 0 || reporter_clone.is_paused() {
+                    continue;
+                }
+                let interval = std::time::Duration::from_secs(interval_secs);
+                if last_report.is_some_and(|t| t.elapsed() < interval) {
+                    continue;
+                }
+                last_report = Some(std::time::Instant::now());
+
+                match window_source.all_windows() {
+                    Ok(windows) => reporter_clone.send_window_list(windows),
+                    Err(e) => warn!("Failed to enumerate windows: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Track user idle time and report a `presence` message whenever active/idle flips
+    fn start_presence_monitoring(&self) {
+        const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+        let reporter_clone = self.clone();
+        let idle_source = self.platform.idle_source();
+
+        std::thread::spawn(move || {
+            let mut last_state: Option<PresenceState> = None;
+            let mut error_warned = false;
+
+            while reporter_clone.is_running.load(Ordering::Relaxed) {
+                std::thread::sleep(CHECK_INTERVAL);
+
+                let (enabled, threshold_secs) = reporter_clone.config.read()
+                    .map(|cfg| (cfg.enabled, cfg.idle_threshold_secs))
+                    .unwrap_or((false, 0));
+                if !enabled || threshold_secs == 0 || reporter_clone.paused().is_some() {
+                    // Report the current state again once re-enabled or resumed
+                    last_state = None;
+                    continue;
+                }
+
+                let idle_time = match idle_source.idle_time() {
+                    Ok(idle_time) => idle_time,
+                    Err(e) => {
+                        if !error_warned {
+                            reporter_clone.push_log(1, &format!("获取空闲时间失败: {}", e));
+                            error_warned = true;
+                        }
+                        continue;
+                    }
+                };
+                error_warned = false;
+
+                let state = if idle_time >= std::time::Duration::from_secs(threshold_secs) {
+                    PresenceState::Idle
+                } else {
+                    PresenceState::Active
+                };
+                if last_state == Some(state) {
+                    continue;
+                }
+                last_state = Some(state);
+
+                let idle_since = (state == PresenceState::Idle).then(|| {
+                    std::time::SystemTime::now()
+                        .checked_sub(idle_time)
+                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|d| d.as_millis() as u64)
+                        .unwrap_or(0)
+                });
+                reporter_clone.push_log(0, match state {
+                    PresenceState::Active => "👤 用户恢复活动",
+                    PresenceState::Idle => "💤 用户进入空闲状态",
+                });
+                reporter_clone.send_presence(state, idle_since);
+            }
+        });
+    }
+
+    /// React to screen lock and suspend/resume notifications from the platform
+    fn start_session_monitoring(&self) {
+        let Some(events) = self.platform.session_source().subscribe() else { return };
+        let reporter_clone = self.clone();
+
+        std::thread::spawn(move || {
+            reporter_clone.push_log(0, "会话状态监控已启动");
+
+            while reporter_clone.is_running.load(Ordering::Relaxed) {
+                let event = match events.recv_timeout(std::time::Duration::from_secs(1)) {
+                    Ok(event) => event,
+                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                        reporter_clone.push_log(1, "会话状态订阅已断开");
+                        break;
+                    }
+                };
+
+                match event {
+                    SessionEvent::Locked => {
+                        reporter_clone.session_locked.store(true, Ordering::Relaxed);
+                        reporter_clone.push_log(0, "🔒 屏幕已锁定，暂停上报");
+                    }
+                    SessionEvent::Unlocked => {
+                        reporter_clone.session_locked.store(false, Ordering::Relaxed);
+                        reporter_clone.push_log(0, "🔓 屏幕已解锁，恢复上报");
+                        reporter_clone.request_resync();
+                    }
+                    SessionEvent::Suspending => {
+                        reporter_clone.session_suspended.store(true, Ordering::Relaxed);
+                        reporter_clone.push_log(0, "💤 系统即将休眠，暂停上报");
+                    }
+                    SessionEvent::Resumed => {
+                        reporter_clone.session_suspended.store(false, Ordering::Relaxed);
+                        reporter_clone.push_log(0, "⏰ 系统已恢复，立即重连");
+                        // The connection most likely died during sleep: reconnect before resending state
+                        reporter_clone.reconnect_now.notify_one();
+                        reporter_clone.request_resync();
+                    }
+                    SessionEvent::NetworkChanged => {
+                        // Only a reconnect hint, not a session state the server cares about
+                        reporter_clone.network_changed();
+                        continue;
+                    }
+                }
+                reporter_clone.send_session_state(event);
+            }
+        });
+    }
+
+    /// Announce schedule transitions with a `schedule_state` message so the server can tell
+    /// "paused by schedule" apart from a client that went silent
+    fn start_schedule_monitoring(&self) {
+        const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+        let reporter_clone = self.clone();
+
+        std::thread::spawn(move || {
+            // `None` until the first check, so a schedule that starts out paused is announced too
+            let mut last_active: Option<[bool; 3]> = None;
+
+            while reporter_clone.is_running.load(Ordering::Relaxed) {
+                let enabled = reporter_clone.config.read().map(|cfg| cfg.enabled).unwrap_or(false);
+                if !enabled {
+                    last_active = None;
+                    std::thread::sleep(CHECK_INTERVAL);
+                    continue;
+                }
+
+                let active = ScheduleCategory::ALL.map(|category| reporter_clone.is_scheduled(category));
+                let previous = last_active.unwrap_or([true; 3]);
+                last_active = Some(active);
+
+                let paused: Vec<ScheduleCategory> = ScheduleCategory::ALL.into_iter()
+                    .zip(previous.into_iter().zip(active))
+                    .filter(|(_, (was, is))| *was && !*is)
+                    .map(|(category, _)| category)
+                    .collect();
+                let resumed: Vec<ScheduleCategory> = ScheduleCategory::ALL.into_iter()
+                    .zip(previous.into_iter().zip(active))
+                    .filter(|(_, (was, is))| !*was && *is)
+                    .map(|(category, _)| category)
+                    .collect();
+
+                if !paused.is_empty() {
+                    reporter_clone.push_log(0, &format!("⏸️ 按计划暂停上报: {:?}", paused));
+                    reporter_clone.send_schedule_state(ReportingState::Paused, paused);
+                }
+                if !resumed.is_empty() {
+                    reporter_clone.push_log(0, &format!("▶️ 按计划恢复上报: {:?}", resumed));
+                    reporter_clone.send_schedule_state(ReportingState::Resumed, resumed);
+                    reporter_clone.request_resync();
+                }
+
+                std::thread::sleep(CHECK_INTERVAL);
+            }
+        });
+    }
+
+    /// Follow changes other processes make to pause.toml (so `shikenmatrix pause` takes effect)
+    /// and end pauses that have expired
+    fn start_pause_monitoring(&self) {
+        const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+        let reporter_clone = self.clone();
+
+        std::thread::spawn(move || {
+            while reporter_clone.is_running.load(Ordering::Relaxed) {
+                if let Ok(mut file) = reporter_clone.pause_file.lock() {
+                    let current = reporter_clone.pause.read().ok().and_then(|pause| *pause);
+                    if let Some(pause) = file.changed() {
+                        // Paused or resumed by another process
+                        reporter_clone.apply_pause(pause);
+                    } else if current.is_some_and(|pause| pause.is_expired(std::time::SystemTime::now())) {
+                        if let Err(e) = file.save(None) {
+                            warn!("Failed to remove expired pause: {}", e);
+                        }
+                        reporter_clone.apply_pause(None);
+                    }
+                }
+                std::thread::sleep(CHECK_INTERVAL);
+            }
+        });
+    }
+
+    /// Switch to a new pause state, announcing and resyncing on change
+    fn apply_pause(&self, pause: Option<Pause>) {
+        let Ok(mut current) = self.pause.write() else { return };
+        if *current == pause {
+            return;
+        }
+        *current = pause;
+        drop(current);
+
+        match pause {
+            Some(Pause { until: Some(until) }) => {
+                self.push_log(0, &format!("⏸️ 暂停上报，至 {}", jiff::Timestamp::from_millisecond(until as i64)
+                    .map(|ts| ts.to_zoned(jiff::tz::TimeZone::system()).strftime("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default()));
+            }
+            Some(Pause { until: None }) => self.push_log(0, "⏸️ 暂停上报，直到手动恢复"),
+            None => {
+                self.push_log(0, "▶️ 恢复上报");
+                self.request_resync();
+            }
+        }
+
+        let announce = self.config.read().map(|cfg| cfg.announce_pause).unwrap_or(false);
+        if announce {
+            let msg = ReporterMessage::PauseState(PauseStateMessage {
+                msg_type: "pause_state".to_string(),
+                state: if pause.is_some() { ReportingState::Paused } else { ReportingState::Resumed },
+                until: pause.and_then(|pause| pause.until),
+            });
+            self.enqueue(msg);
+        }
+    }
+
+    /// Whether reporting is paused by the user, the screen lock or a suspend
+    fn is_paused(&self) -> bool {
+        self.paused().is_some() || self.is_session_paused()
+    }
+
+    /// Whether the schedule currently allows reporting `category`
+    fn is_scheduled(&self, category: ScheduleCategory) -> bool {
+        self.schedule.read()
+            .map(|schedule| schedule.is_active(category, jiff::Timestamp::now()))
+            .unwrap_or(true)
+    }
+
+    /// Whether reporting is paused because the screen is locked or the system is suspending
+    fn is_session_paused(&self) -> bool {
+        self.session_locked.load(Ordering::Relaxed) || self.session_suspended.load(Ordering::Relaxed)
+    }
+
+    /// Forget what was last sent so the monitoring threads report the full current state again
+    fn request_resync(&self) {
+        self.last_window_hash.store(0, Ordering::Relaxed);
+        self.last_media_hash.store(0, Ordering::Relaxed);
+        self.last_media_sessions_hash.store(0, Ordering::Relaxed);
+        self.last_window_list_hash.store(0, Ordering::Relaxed);
+        if let Ok(mut last) = self.last_sent_playback.write() {
+            *last = None;
+        }
+        self.resync_generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.is_connected.load(Ordering::Relaxed)
+    }
+
+    /// Connection state, heartbeat latency and reconnect progress
+    pub fn status(&self) -> ReporterStatus {
+        let connection = self.connection.read().map(|connection| *connection).unwrap_or_default();
+        ReporterStatus {
+            connected: self.is_connected(),
+            latency: connection.latency.last,
+            smoothed_latency: connection.latency.smoothed,
+            reconnect_attempt: connection.retry.attempt,
+            next_retry: connection.retry.next_retry,
+            outbox: self.outbox.stats(),
+        }
+    }
+
+    /// Hint that the network changed (e.g. Wi-Fi switched, VPN connected)
+    ///
+    /// Cuts a pending reconnect backoff short; a live connection is left alone,
+    /// the heartbeat notices if it died.
+    pub fn network_changed(&self) {
+        let waiting = self.connection.read().map(|c| c.retry.next_retry.is_some()).unwrap_or(false);
+        if waiting {
+            self.push_log(0, "🌐 网络已变化，立即重连");
+            self.reconnect_now.notify_one();
+        }
+    }
+
+    /// Send spooled events as `history_batch` messages; returns how many were delivered
+    async fn replay_spool(transport: &mut dyn Transport, config: &SpoolConfig) -> Result<usize, String> {
+        if !config.enabled {
+            return Ok(0);
+        }
+        let history = spool::load(config);
+        let mut sent = 0;
+        for batch in history.chunks(config.batch_size.max(1)) {
+            let msg = HistoryBatchMessage {
+                msg_type: "history_batch",
+                events: batch,
+                remaining: history.len() - sent - batch.len(),
+            };
+            let json = serde_json::to_string(&msg).map_err(|e| e.to_string())?;
+            transport.send_text(json).await.map_err(|e| format!("Failed to send history batch: {}", e))?;
+            sent += batch.len();
+            if let Some(last) = batch.last() {
+                if let Err(e) = spool::commit(last.seq) {
+                    warn!("{}", e);
+                }
+            }
+        }
+        Ok(sent)
+    }
+
+    async fn run_reporter(
+        config: Arc<RwLock<ReporterConfig>>,
+        outbox: Arc<Outbox<ReporterMessage>>,
+        artwork_urls: Arc<RwLock<HashMap<String, String>>>,
+        is_connected: Arc<AtomicBool>,
+        connection: Arc<RwLock<ConnectionState>>,
+        is_running: Arc<AtomicBool>,
+        reconnect_now: Arc<tokio::sync::Notify>,
+    ) {
+        let mut retry = RetryState::default();
+
+        loop {
+            // Check if stop requested
+            if !is_running.load(Ordering::Relaxed) {
+                info!("Reporter stopping (run_reporter loop)");
+                break;
+            }
+
+            let cfg = config.read().unwrap().clone();
+
+            if !cfg.enabled {
+                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                continue;
+            }
+
+            let mut transport = match transport::build(&cfg) {
+                Ok(transport) => transport,
+                Err(e) => {
+                    error!("{}", e);
+                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+            is_connected.store(false, Ordering::Relaxed);
+
+            let connect_result = tokio::time::timeout(
+                tokio::time::Duration::from_secs(15),
+                transport.connect()
+            ).await;
+
+            match connect_result {
+                Ok(Ok(())) => {
+                    // Features are negotiated per connection; until a `welcome` arrives everything is on
+                    let mut negotiated = Negotiated::default();
+                    let greeting = async {
+                        let hello = serde_json::to_string(&HelloMessage::new()).map_err(|e| e.to_string())?;
+                        transport.send_text(hello).await.map_err(|e| format!("Failed to send hello: {}", e))?;
+                        // Only now connected: the HTTP transport has no handshake, `hello` is its first request.
+                        // Set before the replay, so events from here on are sent live instead of being
+                        // spooled behind the replayed ones and sent again after the next reconnect
+                        is_connected.store(true, Ordering::Relaxed);
+                        // Replay what happened while offline before the live state
+                        Self::replay_spool(transport.as_mut(), &cfg.spool).await
+                    };
+                    let greeted = match greeting.await {
+                        Ok(replayed) => {
+                            if replayed > 0 {
+                                info!("Replayed {} spooled events", replayed);
+                            }
+                            true
+                        }
+                        Err(e) => {
+                            error!("{}", e);
+                            false
+                        }
+                    };
+                    if greeted {
+                        retry = RetryState::default();
+                        if let Ok(mut connection) = connection.write() {
+                            connection.retry = retry;
+                        }
+                    }
+
+                    // Heartbeat: one ping in flight at a time, a missing pong means the connection is dead
+                    if let Ok(mut connection) = connection.write() {
+                        connection.latency = Latency::default();
+                    }
+                    let heartbeat_enabled = cfg.heartbeat_interval_secs > 0 && transport.supports_ping();
+                    let heartbeat_period = tokio::time::Duration::from_secs(cfg.heartbeat_interval_secs.max(1));
+                    let heartbeat_timeout = tokio::time::Duration::from_secs(cfg.heartbeat_timeout_secs.max(1));
+                    let mut heartbeat = tokio::time::interval_at(tokio::time::Instant::now() + heartbeat_period, heartbeat_period);
+                    heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                    let mut ping_seq: u64 = 0;
+                    let mut pending_ping: Option<(u64, tokio::time::Instant)> = None;
+
+                    let mut reconnect_immediately = false;
+
+                    if greeted {
+                        loop {
+                            tokio::select! {
+                                biased;
+
+                                _ = reconnect_now.notified() => {
+                                    info!("Reconnect requested, dropping current connection");
+                                    reconnect_immediately = true;
+                                    break;
+                                }
+                                _ = tokio::time::sleep_until(
+                                    pending_ping.map(|(_, sent)| sent + heartbeat_timeout).unwrap_or_else(tokio::time::Instant::now)
+                                ), if pending_ping.is_some() => {
+                                    warn!("No pong within {:?}, reconnecting", heartbeat_timeout);
+                                    break;
+                                }
+                                _ = heartbeat.tick(), if heartbeat_enabled && pending_ping.is_none() => {
+                                    ping_seq += 1;
+                                    if let Err(e) = transport.ping(ping_seq.to_be_bytes().to_vec()).await {
+                                        error!("Failed to send ping: {}", e);
+                                        break;
                                     }
-                                    ReporterMessage::UploadArtwork { content_item_identifier, artwork_data, mime_type } => {
-                                        let meta_msg = UploadArtworkMetaMessage {
-                                            msg_type: "upload_artwork_meta".to_string(),
-                                            content_item_identifier: content_item_identifier.clone(),
-                                            mime_type,
-                                        };
-                                        if let Ok(meta_json) = serde_json::to_string(&meta_msg) {
-                                            if write.send(Message::Text(meta_json.into())).await.is_ok() {
-                                                if let Err(e) = write.send(Message::Binary(artwork_data.into())).await {
-                                                    error!("Failed to send artwork: {}", e);
-                                                    break;
-                                                }
-                                                info!("Artwork uploaded: {}", content_item_identifier);
+                                    pending_ping = Some((ping_seq, tokio::time::Instant::now()));
+                                }
+                                msg = outbox.next() => {
+                                    if !negotiated.allows(msg.message_type()) {
+                                        continue;
+                                    }
+                                    match msg {
+                                        ReporterMessage::UploadArtwork { content_item_identifier, artwork_data, mime_type } => {
+                                            let image_info = platform::sniff_image(&artwork_data);
+                                            let artwork = Artwork {
+                                                content_item_identifier: content_item_identifier.clone(),
+                                                mime_type: mime_type.clone(),
+                                                width: image_info.map(|i| i.width),
+                                                height: image_info.map(|i| i.height),
+                                                data: artwork_data.clone(),
+                                            };
+                                            if let Err(e) = transport.send_artwork(artwork).await {
+                                                error!("Failed to send artwork: {}", e);
+                                                // Retried after reconnecting, like the other messages
+                                                outbox.push(ReporterMessage::UploadArtwork { content_item_identifier, artwork_data, mime_type });
+                                                break;
+                                            }
+                                            info!("Artwork uploaded: {}", content_item_identifier);
+                                        }
+                                        ReporterMessage::Shutdown => {
+                                            info!("Reporter shutdown signal received in run loop");
+                                            is_connected.store(false, Ordering::Relaxed);
+                                            // Close the connection cleanly if possible
+                                            transport.close().await;
+                                            return; // Exit the run_reporter function completely
+                                        }
+                                        msg => {
+                                            let Some(json) = msg.to_json() else { continue };
+                                            if let Err(e) = transport.send_text(json).await {
+                                                error!("Failed to send {} message: {}", msg.message_type(), e);
+                                                // State messages are coalesced, so a newer one replaces this on retry
+                                                outbox.push(msg);
+                                                break;
                                             }
                                         }
-                                    }
-                                    ReporterMessage::Shutdown => {
-                                        info!("Reporter shutdown signal received in run loop");
-                                        is_connected_clone_inner.store(false, Ordering::Relaxed);
-                                        // Close WebSocket connection cleanly if possible
-                                        let _ = write.close().await;
-                                        return; // Exit the run_reporter function completely
                                     }
                                 }
-                            }
-                            Some(msg) = read.next() => {
-                                match msg {
-                                    Ok(Message::Text(text)) => {
-                                        info!("Received: {}", text);
-                                        if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
-                                            if server_msg.msg_type == "artwork_uploaded" {
-                                                if let (Some(content_id), Some(url)) = (server_msg.content_item_identifier, server_msg.artwork_url) {
-                                                    if let Ok(mut urls) = artwork_urls.write() {
-                                                        urls.insert(content_id, url);
+                                msg = transport.recv() => {
+                                    match msg {
+                                        Ok(Incoming::Text(text)) => {
+                                            info!("Received: {}", text);
+                                            if let Ok(server_msg) = serde_json::from_str::<ServerMessage>(&text) {
+                                                match server_msg.msg_type.as_str() {
+                                                    "artwork_uploaded" => {
+                                                        if let (Some(content_id), Some(url)) = (server_msg.content_item_identifier, server_msg.artwork_url) {
+                                                            if let Ok(mut urls) = artwork_urls.write() {
+                                                                urls.insert(content_id, url);
+                                                            }
+                                                        }
                                                     }
+                                                    "welcome" => match serde_json::from_str::<WelcomeMessage>(&text) {
+                                                        Ok(welcome) => negotiated.accept(welcome),
+                                                        Err(e) => warn!("Ignoring malformed welcome: {}", e),
+                                                    },
+                                                    _ => {}
                                                 }
                                             }
                                         }
+                                        Ok(Incoming::Pong(payload)) => {
+                                            if let Some((seq, sent)) = pending_ping {
+                                                if payload == seq.to_be_bytes() {
+                                                    pending_ping = None;
+                                                    if let Ok(mut connection) = connection.write() {
+                                                        connection.latency.record(sent.elapsed());
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        Ok(Incoming::Closed) => {
+                                            warn!("Connection closed by server");
+                                            break;
+                                        }
+                                        Err(e) => {
+                                            error!("Connection error: {}", e);
+                                            break;
+                                        }
                                     }
-                                    Ok(Message::Close(_)) => {
-                                        warn!("WebSocket closed by server");
-                                        break;
-                                    }
-                                    Err(e) => {
-                                        error!("WebSocket error: {}", e);
-                                        break;
-                                    }
-                                    _ => {}
                                 }
                             }
                         }
                     }
                     is_connected.store(false, Ordering::Relaxed);
+                    if let Ok(mut connection) = connection.write() {
+                        connection.latency.last = None;
+                    }
+
+                    if reconnect_immediately {
+                        retry = RetryState::default();
+                        continue;
+                    }
                 }
                 Ok(Err(e)) => {
-                    error!("❌ WebSocket connection failed: {}", e);
+                    error!("❌ Connection failed: {}", e);
                     is_connected.store(false, Ordering::Relaxed);
                 }
                 Err(_) => {
-                    error!("❌ WebSocket connection timeout (15s)");
+                    error!("❌ Connection timeout (15s)");
                     is_connected.store(false, Ordering::Relaxed);
                 }
             }
 
-            reconnect_attempts += 1;
-            if reconnect_attempts >= MAX_RECONNECT_ATTEMPTS {
-                error!("Max reconnect attempts reached, waiting 30s");
-                tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
-                reconnect_attempts = 0;
-            } else {
-                info!("Reconnecting {}/{}...", reconnect_attempts, MAX_RECONNECT_ATTEMPTS);
-                tokio::time::sleep(tokio::time::Duration::from_millis(RECONNECT_INTERVAL)).await;
+            let delay = retry.failed(&cfg.reconnect);
+            info!("Reconnecting in {:?} (attempt {})...", delay, retry.attempt);
+            if let Ok(mut connection) = connection.write() {
+                connection.retry = retry;
+            }
+            // A resume from sleep or a network change cuts the wait short
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = reconnect_now.notified() => {
+                    info!("Reconnect requested, skipping backoff");
+                    retry = RetryState::default();
+                }
+            }
+            if let Ok(mut connection) = connection.write() {
+                connection.retry.next_retry = None;
             }
         }
     }
 
     #[allow(dead_code)]
     pub fn update_config(&self, config: ReporterConfig) {
+        redact::register_secret(&config.token);
+        self.outbox.set_config(config.outbox);
         if let Ok(mut cfg) = self.config.write() {
             *cfg = config;
             info!("Configuration updated");
@@ -688,12 +1631,23 @@ impl Reporter {
     }
 
     pub fn send_window_info(&self, info: &WindowInfo) {
+        let level = self.window_privacy_level();
+        if level == WindowPrivacyLevel::Off || !self.is_scheduled(ScheduleCategory::Window) {
+            return;
+        }
+        let Some(info) = self.private_browsing_policy().apply_window(info) else { return };
+        let Some(info) = self.privacy.read().ok().and_then(|p| p.apply_window(&info)) else { return };
+        // Rewrite before hashing so cosmetic changes (e.g. unread counters) are not re-sent
+        let (title, pid) = match level {
+            WindowPrivacyLevel::AppOnly => (String::new(), 0),
+            _ => (self.rewrite_title(info.app_id.as_deref(), &info.process_name, &info.title), info.pid as u32),
+        };
         let data = WindowInfoData {
-            title: info.title.clone(),
+            title,
             process_name: info.process_name.clone(),
             icon_url: None,
             app_id: info.app_id.clone(),
-            pid: info.pid as u32,
+            pid,
         };
 
         let new_hash = compute_hash(&data);
@@ -707,20 +1661,120 @@ impl Reporter {
                 msg_type: "window_info".to_string(),
                 data,
             });
-            if let Err(e) = self.tx.send(msg) {
-                let err_msg = format!("发送窗口信息到通道失败: {}", e);
-                self.push_log(2, &err_msg);
-            }
+            self.enqueue(msg);
         } else {
             // Window hasn't changed, skip sending
         }
     }
 
-    pub fn send_media_playback(&self, metadata: &MediaMetadata, state: &PlaybackState) {
+    fn window_privacy_level(&self) -> WindowPrivacyLevel {
+        self.config.read()
+            .map(|cfg| cfg.window_privacy)
+            .unwrap_or(WindowPrivacyLevel::Off)
+    }
+
+    fn media_privacy_level(&self) -> MediaPrivacyLevel {
+        self.config.read()
+            .map(|cfg| cfg.media_privacy)
+            .unwrap_or(MediaPrivacyLevel::Off)
+    }
+
+    fn private_browsing_policy(&self) -> PrivateBrowsingPolicy {
+        self.config.read()
+            .map(|cfg| cfg.private_browsing)
+            .unwrap_or_default()
+    }
+
+    fn rewrite_title(&self, app_id: Option<&str>, process_name: &str, title: &str) -> String {
+        match self.title_rewriter.read() {
+            Ok(rewriter) => rewriter.rewrite(app_id, process_name, title),
+            Err(_) => title.to_string(),
+        }
+    }
+
+    pub fn send_window_list(&self, windows: Vec<WindowEntry>) {
+        let level = self.window_privacy_level();
+        if level == WindowPrivacyLevel::Off || !self.is_scheduled(ScheduleCategory::Window) {
+            return;
+        }
+        let private_browsing = self.private_browsing_policy();
+        let Ok(privacy) = self.privacy.read() else { return };
+        let windows: Vec<WindowEntry> = windows.into_iter()
+            .filter_map(|entry| private_browsing.apply_window_entry(entry))
+            .filter_map(|entry| privacy.apply_window_entry(entry))
+            .map(|mut entry| {
+                if level == WindowPrivacyLevel::AppOnly {
+                    entry.title.clear();
+                    entry.pid = 0;
+                } else {
+                    entry.title = self.rewrite_title(entry.app_id.as_deref(), &entry.process_name, &entry.title);
+                }
+                entry
+            })
+            .collect();
+        drop(privacy);
+
+        let new_hash = compute_hash(&windows);
+        let old_hash = self.last_window_list_hash.swap(new_hash, Ordering::Relaxed);
+
+        if new_hash != old_hash {
+            let msg = ReporterMessage::WindowList(WindowListMessage {
+                msg_type: "window_list".to_string(),
+                windows,
+            });
+            self.enqueue(msg);
+        }
+    }
+
+    fn send_presence(&self, state: PresenceState, idle_since: Option<u64>) {
+        if !self.is_scheduled(ScheduleCategory::Presence) {
+            return;
+        }
+        let msg = ReporterMessage::Presence(PresenceMessage {
+            msg_type: "presence".to_string(),
+            state,
+            idle_since,
+        });
+        self.enqueue(msg);
+    }
+
+    /// Queue a message for sending; while disconnected it is also recorded in the offline spool
+    fn enqueue(&self, msg: ReporterMessage) {
+        if !self.is_connected() {
+            let config = self.config.read().map(|cfg| cfg.spool).unwrap_or_default();
+            if let Some(value) = msg.history_value().filter(|_| config.enabled) {
+                // Connected in the meantime: the replay may already have loaded the spool
+                if let Err(e) = spool::record(&config, value, || self.is_connected()) {
+                    warn!("{}", e);
+                }
+            }
+        }
+        self.outbox.push(msg);
+    }
+
+    fn send_session_state(&self, state: SessionEvent) {
+        let msg = ReporterMessage::SessionState(SessionStateMessage {
+            msg_type: "session_state".to_string(),
+            state,
+        });
+        self.enqueue(msg);
+    }
+
+    fn send_schedule_state(&self, state: ReportingState, categories: Vec<ScheduleCategory>) {
+        let msg = ReporterMessage::ScheduleState(ScheduleStateMessage {
+            msg_type: "schedule_state".to_string(),
+            state,
+            categories,
+        });
+        self.enqueue(msg);
+    }
+
+    fn metadata_data(&self, metadata: &MediaMetadata) -> MediaMetadataData {
         let artwork_url = metadata.content_item_identifier.as_ref()
+            .filter(|_| self.media_privacy_level() == MediaPrivacyLevel::FullWithArtwork)
             .and_then(|id| self.artwork_urls.read().ok()?.get(id).cloned());
 
-        let metadata_data = MediaMetadataData {
+        MediaMetadataData {
             bundle_identifier: metadata.bundle_identifier.clone(),
             title: metadata.title.clone(),
             artist: metadata.artist.clone(),
@@ -728,32 +1782,641 @@ impl Reporter {
             duration: metadata.duration,
             artwork_url,
             content_item_identifier: metadata.content_item_identifier.clone(),
-        };
+        }
+    }
 
-        let state_data = PlaybackStateData {
+    fn playback_state_data(state: &PlaybackState) -> PlaybackStateData {
+        PlaybackStateData {
             playing: state.playing,
             playback_rate: state.playback_rate,
             elapsed_time: state.elapsed_time,
-        };
+            position_timestamp: unix_millis(state.timestamp),
+        }
+    }
+
+    /// Report every active session; only sent when a track, play state or the selection changes
+    pub fn send_media_sessions(&self, sessions: &[MediaSession], selected_id: Option<&str>) {
+        if self.media_privacy_level() == MediaPrivacyLevel::Off || !self.is_scheduled(ScheduleCategory::Media) {
+            return;
+        }
+        let Ok(privacy) = self.privacy.read() else { return };
+        let sessions: Vec<MediaSessionData> = sessions.iter()
+            .filter_map(|session| Some((session, privacy.apply_media(&session.metadata)?)))
+            .map(|(session, metadata)| MediaSessionData {
+                id: session.id.clone(),
+                selected: selected_id == Some(session.id.as_str()),
+                metadata: self.metadata_data(&metadata),
+                playback_state: Self::playback_state_data(&session.state),
+                last_changed: unix_millis(session.last_changed),
+            })
+            .collect();
+        drop(privacy);
+
+        let new_hash = compute_hash(&sessions);
+        let old_hash = self.last_media_sessions_hash.swap(new_hash, Ordering::Relaxed);
+
+        if new_hash != old_hash {
+            let msg = ReporterMessage::MediaSessions(MediaSessionsMessage {
+                msg_type: "media_sessions".to_string(),
+                sessions,
+            });
+            self.enqueue(msg);
+        }
+    }
+
+    pub fn send_media_playback(&self, metadata: &MediaMetadata, state: &PlaybackState) {
+        if self.media_privacy_level() == MediaPrivacyLevel::Off || !self.is_scheduled(ScheduleCategory::Media) {
+            return;
+        }
+        let metadata_data = self.metadata_data(metadata);
+        let state_data = Self::playback_state_data(state);
 
         let new_hash = compute_hash(&(&metadata_data, &state_data));
         let old_hash = self.last_media_hash.swap(new_hash, Ordering::Relaxed);
 
-        if new_hash != old_hash {
+        // Same track and play state: only report again on a real seek
+        let seeked = self.last_sent_playback.read()
+            .map(|last| is_playback_changed(last.as_ref(), state))
+            .unwrap_or(true);
+
+        if new_hash != old_hash || seeked {
+            if let Ok(mut last) = self.last_sent_playback.write() {
+                *last = Some(state.clone());
+            }
+
             let msg = ReporterMessage::MediaPlayback(MediaPlaybackMessage {
                 msg_type: "media_playback".to_string(),
                 metadata: metadata_data,
                 playback_state: state_data,
             });
-            let _ = self.tx.send(msg);
+            self.enqueue(msg);
         }
     }
 
     pub fn upload_artwork(&self, content_item_identifier: String, artwork_data: Vec<u8>, mime_type: String) {
-        let _ = self.tx.send(ReporterMessage::UploadArtwork {
+        if self.media_privacy_level() != MediaPrivacyLevel::FullWithArtwork || !self.is_scheduled(ScheduleCategory::Media) {
+            return;
+        }
+        self.enqueue(ReporterMessage::UploadArtwork {
             content_item_identifier,
             artwork_data,
             mime_type,
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::fake::FakePlatform;
+    use crate::services::schedule::{ScheduleConfig, ScheduleMode, ScheduleRange};
+    use crate::services::title_rewrite::TitleRewriteConfig;
+    use futures_util::FutureExt;
+    use std::time::{Duration, Instant, SystemTime};
+
+    /// Long enough for the monitoring threads' startup delay and one schedule check
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    /// How long to wait before concluding that nothing was sent
+    const QUIET: Duration = Duration::from_millis(1500);
+
+    /// A reporter on `fake` that never connects, so every send stays in the outbox
+    fn reporter(fake: &FakePlatform) -> Reporter {
+        reporter_with(fake, |_| {})
+    }
+
+    fn reporter_on(fake: &FakePlatform, url: &str) -> Reporter {
+        reporter_with(fake, |config| config.ws_url = url.to_string())
+    }
+
+    fn reporter_with(fake: &FakePlatform, configure: impl FnOnce(&mut ReporterConfig)) -> Reporter {
+        // Media monitoring is opt-in via the environment
+        std::env::set_var("ENABLE_MEDIA_REPORTING", "1");
+        let mut config = ReporterConfig {
+            enabled: true,
+            ws_url: "ws://127.0.0.1:9".to_string(),
+            ..Default::default()
+        };
+        configure(&mut config);
+        Reporter::with_platform(config, Arc::new(fake.clone()))
+    }
+
+    fn drain(reporter: &Reporter) -> Vec<ReporterMessage> {
+        std::iter::from_fn(|| reporter.outbox.next().now_or_never()).collect()
+    }
+
+    /// Drain the outbox until `pick` matches a message, or panic after `TIMEOUT`
+    fn wait_for<T>(reporter: &Reporter, mut pick: impl FnMut(&ReporterMessage) -> Option<T>) -> T {
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            if let Some(found) = drain(reporter).iter().find_map(&mut pick) {
+                return found;
+            }
+            assert!(Instant::now() < deadline, "expected message was not sent");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    /// Wait `QUIET` and return everything sent in the meantime
+    fn sent_within_quiet(reporter: &Reporter) -> Vec<ReporterMessage> {
+        std::thread::sleep(QUIET);
+        drain(reporter)
+    }
+
+    /// Wait until both the focused window and the playing track have been sent
+    fn wait_for_window_and_media(reporter: &Reporter) -> (String, String) {
+        let (mut window, mut media) = (None, None);
+        wait_for(reporter, |msg| {
+            window = window.take().or_else(|| window_title(msg));
+            media = media.take().or_else(|| media_title(msg));
+            window.clone().zip(media.clone())
+        })
+    }
+
+    fn window_title(msg: &ReporterMessage) -> Option<String> {
+        match msg {
+            ReporterMessage::WindowInfo(info) => Some(info.data.title.clone()),
+            _ => None,
+        }
+    }
+
+    fn media_title(msg: &ReporterMessage) -> Option<String> {
+        match msg {
+            ReporterMessage::MediaPlayback(playback) => playback.metadata.title.clone(),
+            _ => None,
+        }
+    }
+
+    fn schedule_state(msg: &ReporterMessage) -> Option<ReportingState> {
+        match msg {
+            ReporterMessage::ScheduleState(state) => Some(state.state),
+            _ => None,
+        }
+    }
+
+    /// Pauses every category all day
+    fn quiet_all_day() -> Schedule {
+        Schedule::new(&ScheduleConfig {
+            timezone: None,
+            mode: ScheduleMode::Deny,
+            ranges: vec![ScheduleRange { days: vec![], start: "00:00".to_string(), end: "00:00".to_string(), categories: vec![] }],
+        })
+        .expect("valid schedule")
+    }
+
+    fn window(title: &str) -> WindowInfo {
+        WindowInfo {
+            title: title.to_string(),
+            icon_data: None,
+            process_name: "editor".to_string(),
+            pid: 42,
+            app_id: Some("org.example.Editor".to_string()),
+            private_browsing: false,
+        }
+    }
+
+    fn track(title: &str) -> (MediaMetadata, PlaybackState) {
+        let metadata = MediaMetadata {
+            bundle_identifier: Some("org.example.Player".to_string()),
+            title: Some(title.to_string()),
+            artist: Some("Artist".to_string()),
+            duration: 180.0,
+            ..Default::default()
+        };
+        let state = PlaybackState { playing: true, playback_rate: 1.0, elapsed_time: 0.0, timestamp: SystemTime::now() };
+        (metadata, state)
+    }
+
+    #[test]
+    fn window_change_is_sent() {
+        let fake = FakePlatform::new();
+        fake.set_event_driven(true);
+        fake.set_frontmost_window(Some(window("notes.txt")));
+        let reporter = reporter(&fake);
+
+        assert_eq!(wait_for(&reporter, window_title), "notes.txt");
+
+        fake.set_frontmost_window(Some(window("todo.txt")));
+        assert_eq!(wait_for(&reporter, window_title), "todo.txt");
+        reporter.stop();
+    }
+
+    #[test]
+    fn unchanged_window_is_not_resent() {
+        let fake = FakePlatform::new();
+        fake.set_event_driven(true);
+        fake.set_frontmost_window(Some(window("notes.txt")));
+        let reporter = reporter(&fake);
+        wait_for(&reporter, window_title);
+
+        // Same window reported again by the platform
+        fake.set_frontmost_window(Some(window("notes.txt")));
+        let sent = sent_within_quiet(&reporter);
+        assert!(sent.iter().all(|msg| window_title(msg).is_none()), "duplicate window_info sent");
+        reporter.stop();
+    }
+
+    #[test]
+    fn media_change_is_sent() {
+        let fake = FakePlatform::new();
+        fake.set_event_driven(true);
+        fake.set_now_playing(Some(track("First")));
+        let reporter = reporter(&fake);
+
+        assert_eq!(wait_for(&reporter, media_title), "First");
+
+        let second = track("Second");
+        fake.set_now_playing(Some(second.clone()));
+        assert_eq!(wait_for(&reporter, media_title), "Second");
+
+        // Same track and play state again
+        fake.set_now_playing(Some(second));
+        let sent = sent_within_quiet(&reporter);
+        assert!(sent.iter().all(|msg| media_title(msg).is_none()), "duplicate media_playback sent");
+        reporter.stop();
+    }
+
+    #[test]
+    fn schedule_resume_resends_current_state() {
+        let fake = FakePlatform::new();
+        fake.set_event_driven(true);
+        fake.set_frontmost_window(Some(window("notes.txt")));
+        let first = track("First");
+        fake.set_now_playing(Some(first.clone()));
+        let reporter = reporter(&fake);
+        wait_for_window_and_media(&reporter);
+
+        reporter.set_schedule(quiet_all_day());
+        assert_eq!(wait_for(&reporter, schedule_state), ReportingState::Paused);
+
+        // Changes while paused are not reported, and end on the state reported before the pause
+        fake.set_frontmost_window(Some(window("todo.txt")));
+        fake.set_frontmost_window(Some(window("notes.txt")));
+        fake.set_now_playing(Some(track("Second")));
+        fake.set_now_playing(Some(first));
+        let sent = sent_within_quiet(&reporter);
+        assert!(sent.iter().all(|msg| window_title(msg).is_none() && media_title(msg).is_none()), "sent while paused");
+
+        reporter.set_schedule(Schedule::default());
+        assert_eq!(wait_for_window_and_media(&reporter), ("notes.txt".to_string(), "First".to_string()));
+        reporter.stop();
+    }
+
+    /// Minimal HTTP server answering artwork uploads with `artwork_uploaded` and everything else with 204
+    fn serve_artwork_urls() -> String {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/report", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { return };
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                let mut length = 0;
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).is_err() || line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = value.trim().parse().unwrap_or(0);
+                        }
+                    }
+                }
+                let mut body = vec![0; length];
+                let _ = reader.read_exact(&mut body);
+
+                let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+                let query: HashMap<String, String> = url::Url::parse(&format!("http://localhost{}", target))
+                    .map(|url| url.query_pairs().into_owned().collect())
+                    .unwrap_or_default();
+                let response = match query.get("content_item_identifier") {
+                    Some(id) => {
+                        let reply = serde_json::json!({
+                            "type": "artwork_uploaded",
+                            "content_item_identifier": id,
+                            "artwork_url": format!("https://cdn.example/{}.png", id),
+                        })
+                        .to_string();
+                        format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", reply.len(), reply)
+                    }
+                    None => "HTTP/1.1 204 No Content\r\nconnection: close\r\n\r\n".to_string(),
+                };
+                let _ = reader.into_inner().write_all(response.as_bytes());
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn artwork_uploaded_reply_sets_artwork_url() {
+        let fake = FakePlatform::new();
+        fake.set_event_driven(true);
+        let (mut metadata, state) = track("Cover");
+        metadata.content_item_identifier = Some("player-track-1".to_string());
+        metadata.artwork_mime_type = Some("image/png".to_string());
+        metadata.artwork_data = Some(Arc::new(vec![0x89, b'P', b'N', b'G']));
+        let reporter = reporter_on(&fake, &serve_artwork_urls());
+        fake.set_now_playing(Some((metadata.clone(), state)));
+
+        let deadline = Instant::now() + TIMEOUT;
+        while reporter.metadata_data(&metadata).artwork_url.is_none() {
+            assert!(Instant::now() < deadline, "artwork_url was not recorded");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert_eq!(reporter.metadata_data(&metadata).artwork_url.as_deref(), Some("https://cdn.example/player-track-1.png"));
+        reporter.stop();
+    }
+
+    fn window_list(msg: &ReporterMessage) -> Option<Vec<WindowEntry>> {
+        match msg {
+            ReporterMessage::WindowList(list) => Some(list.windows.clone()),
+            _ => None,
+        }
+    }
+
+    fn entry(title: &str, process_name: &str, z_order: u32, focused: bool) -> WindowEntry {
+        WindowEntry {
+            title: title.to_string(),
+            process_name: process_name.to_string(),
+            pid: 100 + z_order as i32,
+            app_id: None,
+            bounds: None,
+            monitor: Some(0),
+            workspace: None,
+            minimized: false,
+            fullscreen: false,
+            focused,
+            z_order,
+            private_browsing: false,
+        }
+    }
+
+    #[test]
+    fn window_list_is_sent_topmost_first_and_only_on_change() {
+        let fake = FakePlatform::new();
+        fake.set_windows(vec![
+            entry("notes.txt", "editor", 0, true),
+            entry("Example - Private Browsing", "firefox", 1, false),
+            entry("Terminal", "terminal", 2, false),
+        ]);
+        let reporter = reporter_with(&fake, |config| config.window_list_interval_secs = 1);
+
+        // Private browsing windows are left out, the rest keeps its order and focus
+        let windows = wait_for(&reporter, window_list);
+        let summary: Vec<(&str, u32, bool)> = windows.iter().map(|w| (w.title.as_str(), w.z_order, w.focused)).collect();
+        assert_eq!(summary, [("notes.txt", 0, true), ("Terminal", 2, false)]);
+
+        // Enumerated again every interval, but only sent when something changed
+        std::thread::sleep(Duration::from_millis(1000));
+        let sent = sent_within_quiet(&reporter);
+        assert!(sent.iter().all(|msg| window_list(msg).is_none()), "unchanged window_list sent again");
+
+        fake.set_windows(vec![entry("Terminal", "terminal", 0, true), entry("notes.txt", "editor", 1, false)]);
+        let windows = wait_for(&reporter, window_list);
+        let summary: Vec<(&str, u32, bool)> = windows.iter().map(|w| (w.title.as_str(), w.z_order, w.focused)).collect();
+        assert_eq!(summary, [("Terminal", 0, true), ("notes.txt", 1, false)]);
+        reporter.stop();
+    }
+
+    fn presence(msg: &ReporterMessage) -> Option<(PresenceState, Option<u64>)> {
+        match msg {
+            ReporterMessage::Presence(presence) => Some((presence.state, presence.idle_since)),
+            _ => None,
+        }
+    }
+
+    fn unix_millis(time: SystemTime) -> u64 {
+        time.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis() as u64
+    }
+
+    #[test]
+    fn presence_follows_the_idle_threshold() {
+        let fake = FakePlatform::new();
+        fake.set_idle_time(Duration::from_secs(10));
+        let reporter = reporter_with(&fake, |config| config.idle_threshold_secs = 60);
+
+        assert_eq!(wait_for(&reporter, presence), (PresenceState::Active, None));
+
+        // Still below the threshold: nothing new to report
+        fake.set_idle_time(Duration::from_secs(59));
+        let sent = sent_within_quiet(&reporter);
+        assert!(sent.iter().all(|msg| presence(msg).is_none()), "presence sent without a transition");
+
+        // Reaching the threshold counts as idle, dated back to the last input
+        let before = unix_millis(SystemTime::now() - Duration::from_secs(60));
+        fake.set_idle_time(Duration::from_secs(60));
+        let (state, idle_since) = wait_for(&reporter, presence);
+        let after = unix_millis(SystemTime::now() - Duration::from_secs(60));
+        assert_eq!(state, PresenceState::Idle);
+        let idle_since = idle_since.expect("idle presence carries idle_since");
+        assert!((before..=after).contains(&idle_since), "{idle_since} not in {before}..={after}");
+
+        // Staying idle is not a transition either
+        fake.set_idle_time(Duration::from_secs(120));
+        let sent = sent_within_quiet(&reporter);
+        assert!(sent.iter().all(|msg| presence(msg).is_none()), "presence sent without a transition");
+
+        fake.set_idle_time(Duration::ZERO);
+        assert_eq!(wait_for(&reporter, presence), (PresenceState::Active, None));
+        reporter.stop();
+    }
+
+    fn session_state(msg: &ReporterMessage) -> Option<SessionEvent> {
+        match msg {
+            ReporterMessage::SessionState(state) => Some(state.state),
+            _ => None,
+        }
+    }
+
+    /// Wait until both `session_state` and the focused window have been sent, in any order
+    fn wait_for_session_and_window(reporter: &Reporter) -> (SessionEvent, String) {
+        let (mut state, mut window) = (None, None);
+        wait_for(reporter, |msg| {
+            state = state.take().or_else(|| session_state(msg));
+            window = window.take().or_else(|| window_title(msg));
+            state.zip(window.clone())
+        })
+    }
+
+    #[test]
+    fn lock_and_suspend_pause_reporting_until_unlock_and_resume() {
+        let fake = FakePlatform::new();
+        fake.set_frontmost_window(Some(window("notes.txt")));
+        let reporter = reporter(&fake);
+        assert_eq!(wait_for(&reporter, window_title), "notes.txt");
+
+        for (pause, resume) in [
+            (SessionEvent::Locked, SessionEvent::Unlocked),
+            (SessionEvent::Suspending, SessionEvent::Resumed),
+        ] {
+            fake.emit_session_event(pause);
+            assert_eq!(wait_for(&reporter, session_state), pause);
+
+            // Nothing is reported while locked or asleep
+            fake.set_frontmost_window(Some(window("secret.txt")));
+            let sent = sent_within_quiet(&reporter);
+            assert!(sent.iter().all(|msg| window_title(msg).is_none()), "window_info sent while {pause:?}");
+
+            // Back on the unchanged window: only a resync sends it again
+            fake.set_frontmost_window(Some(window("notes.txt")));
+            fake.emit_session_event(resume);
+            assert_eq!(wait_for_session_and_window(&reporter), (resume, "notes.txt".to_string()));
+        }
+        reporter.stop();
+    }
+
+    fn session(player: &str, title: &str, playing: bool, changed_secs_ago: u64) -> MediaSession {
+        let last_changed = SystemTime::now() - Duration::from_secs(changed_secs_ago);
+        MediaSession {
+            id: format!("org.mpris.MediaPlayer2.{player}"),
+            metadata: MediaMetadata {
+                bundle_identifier: Some(player.to_string()),
+                title: Some(title.to_string()),
+                artist: Some("Artist".to_string()),
+                duration: 180.0,
+                ..Default::default()
+            },
+            state: PlaybackState {
+                playing,
+                playback_rate: if playing { 1.0 } else { 0.0 },
+                elapsed_time: 0.0,
+                timestamp: last_changed,
+            },
+            last_changed,
+        }
+    }
+
+    /// A playing browser video and a Spotify track that was paused more recently
+    fn playing_and_recently_paused() -> Vec<MediaSession> {
+        vec![session("firefox", "Video", true, 60), session("spotify", "Paused song", false, 5)]
+    }
+
+    /// Title of the `media_playback` a reporter configured by `configure` sends for `sessions`
+    fn reported_title(sessions: Vec<MediaSession>, configure: impl FnOnce(&mut ReporterConfig)) -> String {
+        let fake = FakePlatform::new();
+        fake.set_media_sessions(sessions);
+        let reporter = reporter_with(&fake, configure);
+        let title = wait_for(&reporter, media_title);
+        reporter.stop();
+        title
+    }
+
+    #[test]
+    fn prefer_playing_picks_the_playing_session_over_a_newer_paused_one() {
+        let title = reported_title(playing_and_recently_paused(), |config| {
+            config.media_selection = MediaSelectionPolicy {
+                strategy: MediaSelectionStrategy::PreferPlaying,
+                player_priority: vec!["spotify".to_string()],
+            };
+        });
+        assert_eq!(title, "Video");
+    }
+
+    #[test]
+    fn priority_picks_the_preferred_player_even_when_paused() {
+        let title = reported_title(playing_and_recently_paused(), |config| {
+            config.media_selection = MediaSelectionPolicy {
+                strategy: MediaSelectionStrategy::Priority,
+                player_priority: vec!["Spotify".to_string(), "firefox".to_string()],
+            };
+        });
+        assert_eq!(title, "Paused song");
+    }
+
+    #[test]
+    fn most_recent_picks_the_last_changed_session() {
+        let title = reported_title(playing_and_recently_paused(), |config| {
+            config.media_selection = MediaSelectionPolicy {
+                strategy: MediaSelectionStrategy::MostRecent,
+                player_priority: vec!["firefox".to_string()],
+            };
+        });
+        assert_eq!(title, "Paused song");
+    }
+
+    fn players(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn media_filter_allow_and_deny_lists() {
+        let spotify = session("spotify", "Song", true, 0);
+        let firefox = session("firefox", "Video", true, 0);
+
+        assert!(MediaFilter::default().allows(&spotify));
+
+        let allow = MediaFilter { allow_players: players(&["Spotify"]), ..Default::default() };
+        assert!(allow.allows(&spotify));
+        assert!(!allow.allows(&firefox));
+
+        // Deny wins over allow, and matches the session id as well as the bundle identifier
+        let deny = MediaFilter {
+            allow_players: players(&["spotify", "firefox"]),
+            deny_players: players(&["MediaPlayer2.firefox"]),
+            ..Default::default()
+        };
+        assert!(deny.allows(&spotify));
+        assert!(!deny.allows(&firefox));
+
+        // Empty patterns match nothing
+        let empty = MediaFilter { deny_players: players(&[""]), ..Default::default() };
+        assert!(empty.allows(&spotify));
+    }
+
+    #[test]
+    fn media_filter_kinds_and_metadata() {
+        let with = |edit: fn(&mut MediaMetadata)| {
+            let mut session = session("player", "Track", true, 0);
+            edit(&mut session.metadata);
+            session
+        };
+        let video = with(|m| m.kind = MediaKind::Video);
+        let podcast = with(|m| m.kind = MediaKind::Podcast);
+        let music = with(|m| m.kind = MediaKind::Music);
+
+        let filter = MediaFilter { skip_videos: true, skip_podcasts: true, ..Default::default() };
+        assert!(!filter.allows(&video));
+        assert!(!filter.allows(&podcast));
+        assert!(filter.allows(&music));
+        assert!(MediaFilter::default().allows(&video));
+
+        let filter = MediaFilter { min_duration_secs: 30.0, require_artist: true, ..Default::default() };
+        assert!(!filter.allows(&with(|m| m.duration = 5.0)));
+        assert!(filter.allows(&with(|m| m.duration = 30.0)));
+        // Unknown duration is kept
+        assert!(filter.allows(&with(|m| m.duration = 0.0)));
+        assert!(!filter.allows(&with(|m| m.artist = None)));
+        assert!(!filter.allows(&with(|m| m.artist = Some("  ".to_string()))));
+    }
+
+    #[test]
+    fn filtered_session_is_never_selected() {
+        // Playing would win under every strategy, but the filter removes it before selection
+        let title = reported_title(playing_and_recently_paused(), |config| {
+            config.media_selection.player_priority = players(&["firefox"]);
+            config.media_filter.deny_players = players(&["firefox"]);
+        });
+        assert_eq!(title, "Paused song");
+    }
+
+    #[test]
+    fn unread_counter_change_is_not_resent() {
+        let fake = FakePlatform::new();
+        let reporter = reporter(&fake);
+        reporter.set_title_rewriter(TitleRewriter::new(&TitleRewriteConfig::default()).unwrap());
+
+        fake.set_frontmost_window(Some(window("(3) Inbox")));
+        assert_eq!(wait_for(&reporter, window_title), "Inbox");
+
+        // Same title once the counter is stripped, so it hashes the same
+        fake.set_frontmost_window(Some(window("(4) Inbox")));
+        let sent = sent_within_quiet(&reporter);
+        assert!(sent.iter().all(|msg| window_title(msg).is_none()), "window_info sent for a counter change");
+        reporter.stop();
+    }
+}
@@ -0,0 +1,328 @@
+//! Reporting schedule
+//! Restricts reporting to (or pauses it during) weekday / time ranges, configured via
+//! `[schedule]` in config.toml
+
+use jiff::civil;
+use jiff::tz::TimeZone;
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Kinds of data the schedule can gate independently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleCategory {
+    /// Focused window and window list
+    Window,
+    /// Media playback, media sessions and artwork
+    Media,
+    /// Active / idle presence
+    Presence,
+}
+
+impl ScheduleCategory {
+    pub const ALL: [ScheduleCategory; 3] = [Self::Window, Self::Media, Self::Presence];
+}
+
+/// How the ranges are interpreted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleMode {
+    /// Report only inside the ranges (e.g. working hours)
+    #[default]
+    Allow,
+    /// Pause reporting inside the ranges (e.g. quiet hours in the evening)
+    Deny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl From<civil::Weekday> for Weekday {
+    fn from(day: civil::Weekday) -> Self {
+        match day {
+            civil::Weekday::Monday => Self::Mon,
+            civil::Weekday::Tuesday => Self::Tue,
+            civil::Weekday::Wednesday => Self::Wed,
+            civil::Weekday::Thursday => Self::Thu,
+            civil::Weekday::Friday => Self::Fri,
+            civil::Weekday::Saturday => Self::Sat,
+            civil::Weekday::Sunday => Self::Sun,
+        }
+    }
+}
+
+/// `[schedule]` section of config.toml
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// IANA time zone the ranges are expressed in, e.g. "Europe/Berlin"; the system zone when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub mode: ScheduleMode,
+    /// No ranges means no restriction
+    #[serde(default)]
+    pub ranges: Vec<ScheduleRange>,
+}
+
+/// A single `[[schedule.ranges]]` entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRange {
+    /// Days the range starts on; every day when empty
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    /// "HH:MM", inclusive
+    pub start: String,
+    /// "HH:MM", exclusive; an end before the start continues into the next day,
+    /// an end equal to the start covers the whole day
+    pub end: String,
+    /// Data types the range applies to; every type when empty
+    #[serde(default)]
+    pub categories: Vec<ScheduleCategory>,
+}
+
+struct CompiledRange {
+    days: Vec<Weekday>,
+    /// Minutes since midnight
+    start: u16,
+    end: u16,
+    categories: Vec<ScheduleCategory>,
+}
+
+impl CompiledRange {
+    fn applies_to(&self, category: ScheduleCategory) -> bool {
+        self.categories.is_empty() || self.categories.contains(&category)
+    }
+
+    fn on_day(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    fn contains(&self, day: Weekday, previous_day: Weekday, minute: u16) -> bool {
+        if self.start < self.end {
+            self.on_day(day) && (self.start..self.end).contains(&minute)
+        } else {
+            // Wraps past midnight (or covers the whole day when start == end)
+            (self.on_day(day) && minute >= self.start) || (self.on_day(previous_day) && minute < self.end)
+        }
+    }
+}
+
+/// Compiled schedule, consulted by the reporter before anything is enqueued
+#[derive(Default)]
+pub struct Schedule {
+    timezone: Option<TimeZone>,
+    mode: ScheduleMode,
+    ranges: Vec<CompiledRange>,
+}
+
+impl Schedule {
+    /// Compile the schedule; fails on an unknown time zone or a malformed time
+    pub fn new(config: &ScheduleConfig) -> Result<Self, String> {
+        let timezone = config.timezone.as_deref()
+            .map(|name| TimeZone::get(name).map_err(|e| format!("Invalid schedule timezone \"{}\": {}", name, e)))
+            .transpose()?;
+        let ranges = config.ranges.iter().enumerate()
+            .map(|(index, range)| {
+                let parse = |value: &str| {
+                    parse_minutes(value).ok_or_else(|| {
+                        format!("Invalid time \"{}\" in schedule range #{}, expected HH:MM", value, index + 1)
+                    })
+                };
+                Ok(CompiledRange {
+                    days: range.days.clone(),
+                    start: parse(&range.start)?,
+                    end: parse(&range.end)?,
+                    categories: range.categories.clone(),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { timezone, mode: config.mode, ranges })
+    }
+
+    /// Whether `category` may be reported at `now`
+    ///
+    /// In allow mode a category that no range mentions is never restricted,
+    /// so a schedule for one data type leaves the others alone.
+    pub fn is_active(&self, category: ScheduleCategory, now: Timestamp) -> bool {
+        let mut ranges = self.ranges.iter().filter(|range| range.applies_to(category)).peekable();
+        if ranges.peek().is_none() {
+            return true;
+        }
+
+        let timezone = self.timezone.clone().unwrap_or_else(TimeZone::system);
+        let local = now.to_zoned(timezone);
+        let day = Weekday::from(local.weekday());
+        let previous_day = Weekday::from(local.weekday().previous());
+        let minute = local.hour() as u16 * 60 + local.minute() as u16;
+
+        let inside = ranges.any(|range| range.contains(day, previous_day, minute));
+        match self.mode {
+            ScheduleMode::Allow => inside,
+            ScheduleMode::Deny => !inside,
+        }
+    }
+}
+
+/// "HH:MM" to minutes since midnight; "24:00" is accepted as an end of day
+fn parse_minutes(value: &str) -> Option<u16> {
+    let (hours, minutes) = value.trim().split_once(':')?;
+    let hours: u16 = hours.parse().ok()?;
+    let minutes: u16 = minutes.parse().ok()?;
+    match (hours, minutes) {
+        (24, 0) => Some(24 * 60),
+        (0..=23, 0..=59) => Some(hours * 60 + minutes),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-10-16 is a Friday, 2026-10-19 a Monday
+    const FRI: &str = "2026-10-16";
+    const SAT: &str = "2026-10-17";
+    const SUN: &str = "2026-10-18";
+    const MON: &str = "2026-10-19";
+
+    fn range(days: &[Weekday], start: &str, end: &str, categories: &[ScheduleCategory]) -> ScheduleRange {
+        ScheduleRange {
+            days: days.to_vec(),
+            start: start.to_string(),
+            end: end.to_string(),
+            categories: categories.to_vec(),
+        }
+    }
+
+    fn schedule(mode: ScheduleMode, ranges: Vec<ScheduleRange>) -> Schedule {
+        Schedule::new(&ScheduleConfig { timezone: Some("UTC".to_string()), mode, ranges }).unwrap()
+    }
+
+    /// `date` at `time` UTC
+    fn at(date: &str, time: &str) -> Timestamp {
+        format!("{date}T{time}:00Z").parse().unwrap()
+    }
+
+    fn active_at(schedule: &Schedule, date: &str, time: &str) -> bool {
+        schedule.is_active(ScheduleCategory::Window, at(date, time))
+    }
+
+    #[test]
+    fn no_ranges_means_no_restriction() {
+        let schedule = schedule(ScheduleMode::Allow, Vec::new());
+        assert!(active_at(&schedule, SAT, "03:00"));
+    }
+
+    #[test]
+    fn weekday_boundaries() {
+        let workdays = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri];
+        let schedule = schedule(ScheduleMode::Allow, vec![range(&workdays, "09:00", "17:00", &[])]);
+
+        assert!(!active_at(&schedule, MON, "08:59"));
+        assert!(active_at(&schedule, MON, "09:00"));
+        assert!(active_at(&schedule, FRI, "16:59"));
+        assert!(!active_at(&schedule, FRI, "17:00"));
+        assert!(!active_at(&schedule, SAT, "10:00"));
+        assert!(!active_at(&schedule, SUN, "23:59"));
+    }
+
+    #[test]
+    fn range_wraps_past_midnight_into_the_next_day() {
+        let schedule = schedule(ScheduleMode::Deny, vec![range(&[Weekday::Fri], "22:00", "02:00", &[])]);
+
+        assert!(active_at(&schedule, FRI, "21:59"));
+        assert!(!active_at(&schedule, FRI, "22:00"));
+        assert!(!active_at(&schedule, SAT, "01:59"));
+        assert!(active_at(&schedule, SAT, "02:00"));
+        // Only the Friday night range exists: Thursday night and Saturday night are unaffected
+        assert!(active_at(&schedule, FRI, "01:00"));
+        assert!(active_at(&schedule, SAT, "23:00"));
+    }
+
+    #[test]
+    fn start_equal_to_end_covers_a_whole_day() {
+        let midnight = schedule(ScheduleMode::Allow, vec![range(&[Weekday::Sat], "00:00", "00:00", &[])]);
+        assert!(!active_at(&midnight, FRI, "23:59"));
+        assert!(active_at(&midnight, SAT, "00:00"));
+        assert!(active_at(&midnight, SAT, "23:59"));
+        assert!(!active_at(&midnight, SUN, "00:00"));
+
+        // Any other time: 24 hours from the start
+        let morning = schedule(ScheduleMode::Allow, vec![range(&[Weekday::Sat], "09:00", "09:00", &[])]);
+        assert!(!active_at(&morning, SAT, "08:59"));
+        assert!(active_at(&morning, SUN, "08:59"));
+        assert!(!active_at(&morning, SUN, "09:00"));
+    }
+
+    #[test]
+    fn end_of_day_is_accepted_as_an_end() {
+        let schedule = schedule(ScheduleMode::Allow, vec![range(&[], "22:00", "24:00", &[])]);
+        assert!(active_at(&schedule, MON, "23:59"));
+        assert!(!active_at(&schedule, MON, "00:00"));
+    }
+
+    #[test]
+    fn ranges_are_evaluated_in_the_configured_timezone() {
+        let config = |timezone: &str| ScheduleConfig {
+            timezone: Some(timezone.to_string()),
+            mode: ScheduleMode::Allow,
+            ranges: vec![range(&[Weekday::Mon], "09:00", "17:00", &[])],
+        };
+        let berlin = Schedule::new(&config("Europe/Berlin")).unwrap();
+        let new_york = Schedule::new(&config("America/New_York")).unwrap();
+
+        // Monday 07:30 UTC is 09:30 in Berlin (CEST) and 03:30 in New York (EDT)
+        let now = at("2026-07-06", "07:30");
+        assert!(berlin.is_active(ScheduleCategory::Window, now));
+        assert!(!new_york.is_active(ScheduleCategory::Window, now));
+
+        // Monday 14:00 in Sydney is still Sunday in UTC
+        let sydney = Schedule::new(&config("Australia/Sydney")).unwrap();
+        assert!(sydney.is_active(ScheduleCategory::Window, at(SUN, "23:00")));
+    }
+
+    #[test]
+    fn ranges_only_restrict_their_categories() {
+        let schedule = schedule(ScheduleMode::Allow, vec![
+            range(&[], "09:00", "17:00", &[ScheduleCategory::Window]),
+            range(&[], "18:00", "20:00", &[ScheduleCategory::Window, ScheduleCategory::Media]),
+        ]);
+        let now = at(MON, "18:30");
+        assert!(schedule.is_active(ScheduleCategory::Window, now));
+        assert!(schedule.is_active(ScheduleCategory::Media, now));
+
+        let now = at(MON, "10:00");
+        assert!(schedule.is_active(ScheduleCategory::Window, now));
+        assert!(!schedule.is_active(ScheduleCategory::Media, now));
+        // No range mentions presence, so it is never restricted
+        assert!(schedule.is_active(ScheduleCategory::Presence, at(MON, "03:00")));
+
+        let quiet = self::schedule(ScheduleMode::Deny, vec![range(&[], "22:00", "07:00", &[ScheduleCategory::Presence])]);
+        let night = at(MON, "23:00");
+        assert!(!quiet.is_active(ScheduleCategory::Presence, night));
+        assert!(quiet.is_active(ScheduleCategory::Window, night));
+    }
+
+    #[test]
+    fn invalid_config_is_rejected() {
+        let config = |timezone: &str, end: &str| ScheduleConfig {
+            timezone: Some(timezone.to_string()),
+            mode: ScheduleMode::Allow,
+            ranges: vec![range(&[], "09:00", end, &[])],
+        };
+        assert!(Schedule::new(&config("UTC", "17:00")).is_ok());
+        assert!(Schedule::new(&config("Mars/Olympus_Mons", "17:00")).err().unwrap().contains("timezone"));
+        for end in ["24:01", "17:60", "5pm", ""] {
+            assert!(Schedule::new(&config("UTC", end)).err().unwrap().contains("range #1"), "{end}");
+        }
+    }
+}
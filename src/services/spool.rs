@@ -0,0 +1,282 @@
+//! Offline history spool
+//! While disconnected, state changes are appended to spool.jsonl next to config.toml and replayed
+//! as `history_batch` messages after the next connect, so servers computing time-tracking
+//! statistics see what happened offline. Configured via `[reporter.spool]` in config.toml
+//!
+//! Each event is one JSON line written with a single synced `write`; a line torn by a crash is
+//! skipped on load. Trimming rewrites the file through a temporary file and a rename. Events carry
+//! an increasing sequence number, so a replay removes exactly what it sent even if events were
+//! recorded or trimmed in the meantime.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use super::config::data_dir;
+
+const SPOOL_FILE: &str = "spool.jsonl";
+
+/// Serializes access to the spool file; holds the last sequence number handed out once known
+static SPOOL_LOCK: Mutex<Option<u64>> = Mutex::new(None);
+
+/// `[reporter.spool]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpoolConfig {
+    /// Record events while disconnected and replay them on reconnect
+    pub enabled: bool,
+    /// Size limit of the spool file; the oldest events are dropped first
+    pub max_bytes: u64,
+    /// Events older than this are dropped
+    pub max_age_hours: u64,
+    /// Events per `history_batch` message
+    pub batch_size: usize,
+}
+
+impl Default for SpoolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_bytes: 4 * 1024 * 1024,
+            max_age_hours: 7 * 24,
+            batch_size: 200,
+        }
+    }
+}
+
+/// A recorded event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpoolEntry {
+    /// Increases with every recorded event
+    pub seq: u64,
+    /// Unix time (ms) the event happened
+    pub timestamp: u64,
+    /// The message as it would have been sent live
+    pub message: serde_json::Value,
+}
+
+/// `history_batch` message carrying replayed events, oldest first
+#[derive(Debug, Serialize)]
+pub(crate) struct HistoryBatchMessage<'a> {
+    #[serde(rename = "type")]
+    pub msg_type: &'static str,
+    pub events: &'a [SpoolEntry],
+    /// Events still spooled after this batch
+    pub remaining: usize,
+}
+
+fn get_spool_path() -> PathBuf {
+    data_dir().join(SPOOL_FILE)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// Append an event unless `connected()` returns true; trims the spool when it grows past `max_bytes`
+///
+/// `connected` is checked while the spool is locked: an event is either recorded before a replay
+/// loads the spool, or delivered live afterwards, never both.
+pub fn record(config: &SpoolConfig, message: serde_json::Value, connected: impl FnOnce() -> bool) -> Result<(), String> {
+    record_in(&get_spool_path(), config, message, connected)
+}
+
+/// Load the spooled events, oldest first; expired events are dropped from the file
+pub fn load(config: &SpoolConfig) -> Vec<SpoolEntry> {
+    load_from(&get_spool_path(), config)
+}
+
+/// Remove the events up to and including `seq` after they were delivered; events recorded
+/// since `load` are kept
+pub fn commit(seq: u64) -> Result<(), String> {
+    commit_in(&get_spool_path(), seq)
+}
+
+fn record_in(
+    path: &Path,
+    config: &SpoolConfig,
+    message: serde_json::Value,
+    connected: impl FnOnce() -> bool,
+) -> Result<(), String> {
+    if !config.enabled {
+        return Ok(());
+    }
+    let mut last_seq = SPOOL_LOCK.lock().map_err(|_| "Spool lock poisoned".to_string())?;
+    if connected() {
+        return Ok(());
+    }
+    let seq = last_seq.unwrap_or_else(|| read_entries(path).iter().map(|entry| entry.seq).max().unwrap_or(0)) + 1;
+    *last_seq = Some(seq);
+
+    let entry = SpoolEntry { seq, timestamp: now_millis(), message };
+    let mut line = serde_json::to_string(&entry).map_err(|e| format!("Failed to serialize spool entry: {}", e))?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open spool: {}", e))?;
+
+    // A crash may have left a torn last line; start on a fresh line so only that one is lost
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if len > 0 {
+        let mut last = [0u8; 1];
+        if file.seek(SeekFrom::Start(len - 1)).and_then(|_| file.read_exact(&mut last)).is_ok() && last[0] != b'\n' {
+            line.insert(0, '\n');
+        }
+    }
+    file.write_all(line.as_bytes())
+        .and_then(|_| file.sync_data())
+        .map_err(|e| format!("Failed to write spool: {}", e))?;
+
+    if len + line.len() as u64 > config.max_bytes {
+        // Trim to three quarters so the next few events do not trigger another rewrite
+        let entries = read_entries(path);
+        let total = entries.len();
+        let budget = config.max_bytes / 4 * 3;
+        let mut size = 0u64;
+        let keep = entries.iter().rev()
+            .take_while(|entry| {
+                size += serde_json::to_string(entry).map(|s| s.len() as u64 + 1).unwrap_or(0);
+                size <= budget
+            })
+            .count();
+        warn!("Spool exceeds {} bytes, dropping {} oldest events", config.max_bytes, total - keep);
+        write_entries(path, &entries[total - keep..])?;
+    }
+    Ok(())
+}
+
+fn load_from(path: &Path, config: &SpoolConfig) -> Vec<SpoolEntry> {
+    let Ok(_guard) = SPOOL_LOCK.lock() else { return Vec::new() };
+    if !path.exists() {
+        return Vec::new();
+    }
+
+    let entries = read_entries(path);
+    let cutoff = now_millis().saturating_sub(config.max_age_hours.saturating_mul(60 * 60 * 1000));
+    let total = entries.len();
+    let fresh: Vec<SpoolEntry> = entries.into_iter().filter(|entry| entry.timestamp >= cutoff).collect();
+    if fresh.len() != total {
+        if let Err(e) = write_entries(path, &fresh) {
+            warn!("{}", e);
+        }
+    }
+    fresh
+}
+
+fn commit_in(path: &Path, seq: u64) -> Result<(), String> {
+    let _guard = SPOOL_LOCK.lock().map_err(|_| "Spool lock poisoned".to_string())?;
+    let entries = read_entries(path);
+    let pending: Vec<SpoolEntry> = entries.into_iter().filter(|entry| entry.seq > seq).collect();
+    write_entries(path, &pending)
+}
+
+/// Parse the spool, skipping lines that do not parse (e.g. torn by a crash)
+fn read_entries(path: &Path) -> Vec<SpoolEntry> {
+    let Ok(file) = File::open(path) else { return Vec::new() };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+/// Replace the spool atomically, or remove it when empty
+fn write_entries(path: &Path, entries: &[SpoolEntry]) -> Result<(), String> {
+    if entries.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove spool: {}", e)),
+            _ => Ok(()),
+        };
+    }
+
+    let mut content = String::new();
+    for entry in entries {
+        if let Ok(line) = serde_json::to_string(entry) {
+            content.push_str(&line);
+            content.push('\n');
+        }
+    }
+    let tmp_path = path.with_extension("jsonl.tmp");
+    File::create(&tmp_path)
+        .and_then(|mut file| file.write_all(content.as_bytes()).and_then(|_| file.sync_all()))
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .map_err(|e| format!("Failed to rewrite spool: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> SpoolConfig {
+        SpoolConfig { enabled: true, ..Default::default() }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("shikenmatrix-{}-{}.jsonl", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn offline() -> bool {
+        false
+    }
+
+    fn titles(entries: &[SpoolEntry]) -> Vec<&str> {
+        entries.iter().filter_map(|entry| entry.message["title"].as_str()).collect()
+    }
+
+    #[test]
+    fn commit_keeps_events_recorded_during_replay() {
+        let path = temp_path("spool-commit");
+        for title in ["a", "b"] {
+            record_in(&path, &config(), json!({ "title": title }), offline).unwrap();
+        }
+        let replayed = load_from(&path, &config());
+        record_in(&path, &config(), json!({ "title": "c" }), offline).unwrap();
+
+        commit_in(&path, replayed.last().unwrap().seq).unwrap();
+        assert_eq!(titles(&load_from(&path, &config())), ["c"]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn commit_after_trim_does_not_drop_unsent_events() {
+        let path = temp_path("spool-trim");
+        // Each line is 60-61 bytes: the seventh event trims the spool to the newest five
+        let small = SpoolConfig { max_bytes: 419, ..config() };
+        for title in ["a", "b", "c"] {
+            record_in(&path, &small, json!({ "title": title }), offline).unwrap();
+        }
+        let replayed = load_from(&path, &small);
+        assert_eq!(titles(&replayed), ["a", "b", "c"]);
+
+        // Trimming drops the oldest events while the replay is in flight
+        for title in ["d", "e", "f", "g"] {
+            record_in(&path, &small, json!({ "title": title }), offline).unwrap();
+        }
+        assert_eq!(titles(&load_from(&path, &small)), ["c", "d", "e", "f", "g"]);
+
+        commit_in(&path, replayed.last().unwrap().seq).unwrap();
+        assert_eq!(titles(&load_from(&path, &small)), ["d", "e", "f", "g"]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn events_are_not_recorded_once_connected() {
+        let path = temp_path("spool-connected");
+        record_in(&path, &config(), json!({ "title": "a" }), offline).unwrap();
+        record_in(&path, &config(), json!({ "title": "b" }), || true).unwrap();
+        assert_eq!(titles(&load_from(&path, &config())), ["a"]);
+        let _ = fs::remove_file(&path);
+    }
+}
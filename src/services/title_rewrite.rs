@@ -0,0 +1,212 @@
+//! Window title rewriting
+//! Strips noise and personal data from titles before they are hashed and reported,
+//! configured via `[title_rewrite]` in config.toml
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Built-in title transforms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TitleScrubber {
+    /// Leading unread counters: "(3) Slack" -> "Slack"
+    UnreadCounter,
+    /// Trailing browser names: "Docs — Mozilla Firefox" -> "Docs"
+    BrowserSuffix,
+    /// URLs are reduced to their host: "https://example.com/a?b" -> "example.com"
+    Urls,
+    /// E-mail addresses are replaced with "[email]"
+    Emails,
+    /// Directories are stripped from file system paths: "/home/me/notes.txt" -> "notes.txt"
+    Paths,
+}
+
+impl TitleScrubber {
+    /// Regex and replacement implementing the scrubber
+    fn substitution(self) -> (&'static str, &'static str) {
+        match self {
+            Self::UnreadCounter => (r"^\s*[(\[]\d+\+?[)\]]\s*", ""),
+            Self::BrowserSuffix => (
+                r"\s+[-—–]\s+(?:Mozilla Firefox|Firefox|Google Chrome|Chromium|Microsoft\x{200B}? Edge|Brave|Opera|Vivaldi|Safari)$",
+                "",
+            ),
+            Self::Urls => (r"\b[a-zA-Z][a-zA-Z0-9+.-]*://(?:[^@/\s]+@)?([^/\s?#]+)\S*", "$1"),
+            Self::Emails => (r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b", "[email]"),
+            Self::Paths => (r"(^|\s)(?:[A-Za-z]:\\|~?/)(?:[^\s/\\]+[/\\])+", "$1"),
+        }
+    }
+}
+
+fn default_scrubbers() -> Vec<TitleScrubber> {
+    vec![
+        TitleScrubber::UnreadCounter,
+        TitleScrubber::BrowserSuffix,
+        TitleScrubber::Urls,
+        TitleScrubber::Emails,
+        TitleScrubber::Paths,
+    ]
+}
+
+/// `[title_rewrite]` section of config.toml
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TitleRewriteConfig {
+    /// Built-in scrubbers, applied in order after the user rules
+    #[serde(default = "default_scrubbers")]
+    pub scrubbers: Vec<TitleScrubber>,
+    #[serde(default)]
+    pub rules: Vec<TitleRewriteRule>,
+}
+
+impl Default for TitleRewriteConfig {
+    fn default() -> Self {
+        Self {
+            scrubbers: default_scrubbers(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// A single `[[title_rewrite.rules]]` regex substitution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TitleRewriteRule {
+    /// Only apply to this application (compared case-insensitively with the app id, or the
+    /// process name for windows without one); applies to every window when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    /// Regular expression; every match is replaced
+    pub pattern: String,
+    /// Replacement, may reference capture groups as `$1` / `${name}`
+    #[serde(default)]
+    pub replacement: String,
+}
+
+struct RewriteStep {
+    app_id: Option<String>,
+    regex: Regex,
+    replacement: String,
+}
+
+/// Compiled title transform chain: user rules in file order, then the built-in scrubbers
+#[derive(Default)]
+pub struct TitleRewriter {
+    steps: Vec<RewriteStep>,
+}
+
+impl TitleRewriter {
+    /// Compile the chain; fails on the first invalid user regex
+    pub fn new(config: &TitleRewriteConfig) -> Result<Self, String> {
+        let mut steps = Vec::with_capacity(config.rules.len() + config.scrubbers.len());
+        for (index, rule) in config.rules.iter().enumerate() {
+            let regex = Regex::new(&rule.pattern)
+                .map_err(|e| format!("Invalid pattern in title rewrite rule #{}: {}", index + 1, e))?;
+            steps.push(RewriteStep {
+                app_id: rule.app_id.clone(),
+                regex,
+                replacement: rule.replacement.clone(),
+            });
+        }
+        for scrubber in &config.scrubbers {
+            let (pattern, replacement) = scrubber.substitution();
+            let regex = Regex::new(pattern)
+                .map_err(|e| format!("Invalid built-in pattern for {:?}: {}", scrubber, e))?;
+            steps.push(RewriteStep {
+                app_id: None,
+                regex,
+                replacement: replacement.to_string(),
+            });
+        }
+        Ok(Self { steps })
+    }
+
+    /// Run the chain over a window title
+    pub fn rewrite(&self, app_id: Option<&str>, process_name: &str, title: &str) -> String {
+        let app = app_id.unwrap_or(process_name);
+        let mut title = title.to_string();
+        for step in &self.steps {
+            if step.app_id.as_ref().is_some_and(|id| !id.eq_ignore_ascii_case(app)) {
+                continue;
+            }
+            if let std::borrow::Cow::Owned(rewritten) = step.regex.replace_all(&title, step.replacement.as_str()) {
+                title = rewritten;
+            }
+        }
+        title.trim().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewriter(rules: Vec<TitleRewriteRule>) -> TitleRewriter {
+        TitleRewriter::new(&TitleRewriteConfig { rules, ..Default::default() }).unwrap()
+    }
+
+    fn rule(app_id: Option<&str>, pattern: &str, replacement: &str) -> TitleRewriteRule {
+        TitleRewriteRule {
+            app_id: app_id.map(str::to_string),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn builtin_scrubbers() {
+        let rewriter = rewriter(Vec::new());
+        let cases = [
+            ("(3) Slack", "Slack"),
+            ("[12+] Inbox", "Inbox"),
+            ("Release (2) notes", "Release (2) notes"),
+            ("Docs — Mozilla Firefox", "Docs"),
+            ("Docs - Google Chrome", "Docs"),
+            ("Chrome - Editor", "Chrome - Editor"),
+            ("https://me@example.com/a?b=1#c - Reader", "example.com - Reader"),
+            ("Mail from alice@example.org", "Mail from [email]"),
+            ("/home/me/notes.txt - Editor", "notes.txt - Editor"),
+            ("Editing ~/projects/app/main.rs", "Editing main.rs"),
+            (r"C:\Users\me\report.docx - Word", "report.docx - Word"),
+            ("(5) https://mail.example.com/u/0 - Firefox", "mail.example.com"),
+        ];
+        for (title, expected) in cases {
+            assert_eq!(rewriter.rewrite(None, "app", title), expected, "{title}");
+        }
+    }
+
+    #[test]
+    fn scrubbers_can_be_turned_off() {
+        let rewriter = TitleRewriter::new(&TitleRewriteConfig { scrubbers: Vec::new(), rules: Vec::new() }).unwrap();
+        assert_eq!(rewriter.rewrite(None, "app", "(3) Slack"), "(3) Slack");
+        assert_eq!(TitleRewriter::default().rewrite(None, "app", " (3) Slack "), "(3) Slack");
+    }
+
+    #[test]
+    fn user_rules_run_before_the_scrubbers() {
+        let rewriter = rewriter(vec![rule(None, r"^Project (\w+): (.*)$", "$2 [$1]")]);
+        // The rule sees the original title, the scrubbers then strip the counter it moved to the front
+        assert_eq!(rewriter.rewrite(None, "app", "Project apollo: (3) Chat"), "Chat [apollo]");
+        assert_eq!(rewriter.rewrite(None, "app", "(3) Project apollo: Chat"), "Project apollo: Chat");
+    }
+
+    #[test]
+    fn user_rules_can_be_scoped_to_an_app() {
+        let rewriter = rewriter(vec![rule(Some("org.example.Mail"), r"\s*\(.*\)$", "")]);
+        assert_eq!(rewriter.rewrite(Some("ORG.EXAMPLE.MAIL"), "mail", "Inbox (work)"), "Inbox");
+        assert_eq!(rewriter.rewrite(Some("org.example.Editor"), "mail", "Inbox (work)"), "Inbox (work)");
+
+        // Without an app id the process name is compared instead
+        let rewriter = self::rewriter(vec![rule(Some("thunderbird"), r"^Draft: ", "")]);
+        assert_eq!(rewriter.rewrite(None, "Thunderbird", "Draft: Hello"), "Hello");
+        assert_eq!(rewriter.rewrite(None, "editor", "Draft: Hello"), "Draft: Hello");
+    }
+
+    #[test]
+    fn invalid_user_regex_is_rejected() {
+        let err = TitleRewriter::new(&TitleRewriteConfig {
+            rules: vec![rule(None, "ok", ""), rule(None, "(unclosed", "")],
+            ..Default::default()
+        })
+        .err()
+        .unwrap();
+        assert!(err.contains("rule #2"), "{err}");
+    }
+}
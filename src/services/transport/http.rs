@@ -0,0 +1,170 @@
+//! HTTP transport
+//! Every message is POSTed as JSON to the configured URL; artwork is POSTed as the raw image with
+//! its metadata in the query string. JSON in a response body (a message or an array of messages,
+//! e.g. `welcome` or `artwork_uploaded`) is handed back to the reporter as if the server had sent it.
+
+use futures_util::future::BoxFuture;
+use reqwest::{header, Client, RequestBuilder, StatusCode};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tracing::{info, warn};
+use url::Url;
+
+use super::{Artwork, Incoming, Transport};
+use crate::services::redact::redact;
+use crate::services::reporter::AuthMode;
+
+/// Attempts per message before the connection is considered down
+const MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry, doubled for every further one
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub struct HttpTransport {
+    url: Url,
+    token: String,
+    auth_mode: AuthMode,
+    client: Client,
+    /// Messages from response bodies, not yet returned by `recv`
+    replies: VecDeque<String>,
+    /// Whether a request succeeded since `connect`; until then every rejection fails the connection
+    greeted: bool,
+}
+
+impl HttpTransport {
+    pub fn new(url: Url, token: String, auth_mode: AuthMode) -> Result<Self, String> {
+        if auth_mode == AuthMode::Subprotocol {
+            warn!("auth_mode = \"subprotocol\" only applies to WebSocket, sending a bearer token instead");
+        }
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("shikenmatrix/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        Ok(Self { url, token, auth_mode, client, replies: VecDeque::new(), greeted: false })
+    }
+
+    fn request(&self, url: Url) -> RequestBuilder {
+        let mut url = url;
+        if self.auth_mode == AuthMode::Query && !self.token.is_empty() {
+            url.query_pairs_mut().append_pair("token", &self.token);
+        }
+        let request = self.client.post(url);
+        match self.auth_mode {
+            AuthMode::Query => request,
+            _ if self.token.is_empty() => request,
+            _ => request.bearer_auth(&self.token),
+        }
+    }
+
+    /// POST with retries on network errors, timeouts, 408, 429 and 5xx
+    ///
+    /// Other client errors are not retried: 401 / 403 fail the connection, anything else drops
+    /// the message, since sending it again would be rejected the same way. Before the first
+    /// success (the `hello`) any of them fails the connection, so a wrong URL answered with
+    /// 404 goes through the reconnect backoff instead of looking connected.
+    async fn post(&mut self, url: Url, content_type: &str, body: Vec<u8>) -> Result<(), String> {
+        let mut last_error = String::new();
+        for attempt in 1..=MAX_ATTEMPTS {
+            if attempt > 1 {
+                tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt - 2)).await;
+            }
+
+            let result = self.request(url.clone())
+                .header(header::CONTENT_TYPE, content_type)
+                .body(body.clone())
+                .send()
+                .await;
+            let response = match result {
+                Ok(response) => response,
+                Err(e) => {
+                    last_error = redact(&e.to_string()).into_owned();
+                    continue;
+                }
+            };
+
+            let status = response.status();
+            if status.is_success() {
+                self.greeted = true;
+                let text = response.text().await.unwrap_or_default();
+                self.queue_replies(&text);
+                return Ok(());
+            }
+            match status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    return Err(format!("Server rejected the token ({})", status));
+                }
+                StatusCode::REQUEST_TIMEOUT | StatusCode::TOO_MANY_REQUESTS => {}
+                _ if status.is_server_error() => {}
+                _ if !self.greeted => {
+                    return Err(format!("Server rejected the first request ({}), check the URL", status));
+                }
+                _ => {
+                    warn!("Server rejected message ({}), dropping it", status);
+                    return Ok(());
+                }
+            }
+            last_error = format!("HTTP {}", status);
+        }
+        Err(format!("POST failed after {} attempts: {}", MAX_ATTEMPTS, last_error))
+    }
+
+    fn queue_replies(&mut self, body: &str) {
+        let body = body.trim();
+        if body.is_empty() {
+            return;
+        }
+        match serde_json::from_str::<serde_json::Value>(body) {
+            Ok(serde_json::Value::Array(messages)) => {
+                self.replies.extend(messages.iter().map(|message| message.to_string()));
+            }
+            Ok(message @ serde_json::Value::Object(_)) => self.replies.push_back(message.to_string()),
+            _ => {}
+        }
+    }
+}
+
+impl Transport for HttpTransport {
+    fn connect(&mut self) -> BoxFuture<'_, Result<(), String>> {
+        // Stateless: the `hello` sent next is the first request and fails if the server is down
+        Box::pin(async move {
+            info!("Using HTTP transport: {} (auth: {:?})", redact(self.url.as_str()), self.auth_mode);
+            self.greeted = false;
+            Ok(())
+        })
+    }
+
+    fn send_text(&mut self, json: String) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async move {
+            let url = self.url.clone();
+            self.post(url, "application/json", json.into_bytes()).await
+        })
+    }
+
+    fn send_artwork(&mut self, artwork: Artwork) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async move {
+            let mut url = self.url.clone();
+            {
+                let mut query = url.query_pairs_mut();
+                query.append_pair("type", "upload_artwork");
+                query.append_pair("content_item_identifier", &artwork.content_item_identifier);
+                if let Some(width) = artwork.width {
+                    query.append_pair("width", &width.to_string());
+                }
+                if let Some(height) = artwork.height {
+                    query.append_pair("height", &height.to_string());
+                }
+            }
+            self.post(url, &artwork.mime_type, artwork.data).await
+        })
+    }
+
+    fn recv(&mut self) -> BoxFuture<'_, Result<Incoming, String>> {
+        Box::pin(async move {
+            match self.replies.pop_front() {
+                Some(reply) => Ok(Incoming::Text(reply)),
+                None => std::future::pending().await,
+            }
+        })
+    }
+}
@@ -0,0 +1,130 @@
+//! Transports
+//! How reporter messages reach the server or broker. `Reporter::run_reporter` owns reconnects, negotiation,
+//! heartbeats and the outbox; a transport only opens the connection and moves payloads.
+
+pub mod http;
+pub mod mqtt;
+pub mod websocket;
+
+pub use http::HttpTransport;
+pub use mqtt::{MqttConfig, MqttTransport};
+pub use websocket::WebSocketTransport;
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use url::Url;
+
+use super::reporter::ReporterConfig;
+
+/// `transport` in `[reporter]`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportKind {
+    /// By URL scheme: `ws://` / `wss://` use WebSocket, `http://` / `https://` use HTTP,
+    /// `mqtt://` / `mqtts://` use MQTT
+    #[default]
+    Auto,
+    /// WebSocket; `http://` / `https://` URLs are rewritten to `ws://` / `wss://`
+    #[serde(rename = "websocket")]
+    WebSocket,
+    /// One HTTP POST per message
+    Http,
+    /// Retained per-device topics on an MQTT broker, configured via `[reporter.mqtt]`
+    Mqtt,
+}
+
+/// Something the server sent
+#[derive(Debug)]
+pub enum Incoming {
+    /// A JSON message (`welcome`, `artwork_uploaded`, ...)
+    Text(String),
+    /// Answer to `Transport::ping`
+    Pong(Vec<u8>),
+    /// The server closed the connection
+    Closed,
+}
+
+/// An artwork upload
+#[derive(Debug, Clone)]
+pub struct Artwork {
+    pub content_item_identifier: String,
+    pub mime_type: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub data: Vec<u8>,
+}
+
+/// A connection to the server
+///
+/// Methods return boxed futures so transports can be chosen at runtime as `Box<dyn Transport>`.
+pub trait Transport: Send {
+    /// Open the connection; failures are retried by the reporter with backoff
+    fn connect(&mut self) -> BoxFuture<'_, Result<(), String>>;
+
+    /// Send one JSON message
+    fn send_text(&mut self, json: String) -> BoxFuture<'_, Result<(), String>>;
+
+    /// Upload artwork for `artwork_uploaded` to answer with a URL
+    fn send_artwork(&mut self, artwork: Artwork) -> BoxFuture<'_, Result<(), String>>;
+
+    /// Next message from the server; must be cancel-safe. Transports that only receive
+    /// replies to their own requests stay pending until a reply is available
+    fn recv(&mut self) -> BoxFuture<'_, Result<Incoming, String>>;
+
+    /// Whether `ping` is answered with `Incoming::Pong`; the heartbeat is off otherwise
+    fn supports_ping(&self) -> bool {
+        false
+    }
+
+    fn ping(&mut self, _payload: Vec<u8>) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Close the connection cleanly if possible
+    fn close(&mut self) -> BoxFuture<'_, ()> {
+        Box::pin(async {})
+    }
+}
+
+/// Create the transport selected by `transport` / the URL scheme
+pub fn build(config: &ReporterConfig) -> Result<Box<dyn Transport>, String> {
+    let kind = match config.transport {
+        TransportKind::Auto => match config.ws_url.split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase()) {
+            Some(scheme) if scheme == "ws" || scheme == "wss" => TransportKind::WebSocket,
+            Some(scheme) if scheme == "http" || scheme == "https" => TransportKind::Http,
+            Some(scheme) if scheme == "mqtt" || scheme == "mqtts" => TransportKind::Mqtt,
+            _ => return Err(format!("Unsupported server URL \"{}\", expected ws(s)://, http(s):// or mqtt(s)://", config.ws_url)),
+        },
+        kind => kind,
+    };
+
+    match kind {
+        TransportKind::Http => {
+            let url = Url::parse(&config.ws_url).map_err(|e| format!("Invalid HTTP URL: {}", e))?;
+            Ok(Box::new(HttpTransport::new(url, config.token.clone(), config.auth_mode)?))
+        }
+        TransportKind::Mqtt => {
+            let url = Url::parse(&config.ws_url).map_err(|e| format!("Invalid MQTT URL: {}", e))?;
+            Ok(Box::new(MqttTransport::new(url, config.token.clone(), config.mqtt.clone())?))
+        }
+        _ => {
+            let ws_url = config.ws_url
+                .replace("http://", "ws://")
+                .replace("https://", "wss://");
+            let url = Url::parse(&ws_url).map_err(|e| format!("Invalid WebSocket URL: {}", e))?;
+            Ok(Box::new(WebSocketTransport::new(url, config.token.clone(), config.auth_mode)))
+        }
+    }
+}
+
+/// TLS settings shared by the transports: webpki roots, ring provider
+pub(crate) fn tls_config() -> Arc<rustls::ClientConfig> {
+    Arc::new(
+        rustls::ClientConfig::builder()
+            .with_root_certificates(rustls::RootCertStore::from_iter(
+                webpki_roots::TLS_SERVER_ROOTS.iter().cloned()
+            ))
+            .with_no_client_auth()
+    )
+}
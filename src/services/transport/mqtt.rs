@@ -0,0 +1,338 @@
+//! MQTT transport
+//! Every message is published to `<topic_prefix>/<device id>/<type>`; state messages are retained
+//! so subscribers such as Home Assistant see the current state as soon as they connect. The broker
+//! publishes `offline` to `.../status` as the last will when the client disappears without closing.
+//! Configured via `[reporter.mqtt]` in config.toml
+
+use futures_util::future::BoxFuture;
+use percent_encoding::percent_decode_str;
+use rumqttc::{AsyncClient, Event, EventLoop, LastWill, MqttOptions, Packet, QoS, TlsConfiguration};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::info;
+use url::Url;
+
+use super::{tls_config, Artwork, Incoming, Transport};
+use crate::services::handshake::device_id;
+use crate::services::redact::redact;
+
+/// Message types that are events rather than state, published without the retain flag
+const EVENT_TYPES: &[&str] = &["session_state", "history_batch"];
+/// Username sent when only a password / token is configured
+const DEFAULT_USERNAME: &str = "shikenmatrix";
+/// Requests buffered for the event loop task
+const REQUEST_CAPACITY: usize = 64;
+/// The broker only sends acknowledgements
+const MAX_INCOMING_PACKET: usize = 64 * 1024;
+/// Large enough for artwork
+const MAX_OUTGOING_PACKET: usize = 16 * 1024 * 1024;
+/// Time the event loop gets to flush `offline` and the disconnect on close
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// `[reporter.mqtt]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    /// Topics are `<topic_prefix>/<device id>/<message type>`
+    pub topic_prefix: String,
+    /// Device name in Home Assistant; empty uses `ShikenMatrix <device id prefix>`
+    pub device_name: String,
+    /// Seconds between keep-alive pings when idle (0 = disabled)
+    pub keep_alive_secs: u64,
+    /// Publish Home Assistant MQTT discovery config on connect
+    pub discovery: bool,
+    /// Topic prefix Home Assistant watches for discovery config
+    pub discovery_prefix: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            topic_prefix: "shikenmatrix".to_string(),
+            device_name: String::new(),
+            keep_alive_secs: 30,
+            discovery: false,
+            discovery_prefix: "homeassistant".to_string(),
+        }
+    }
+}
+
+/// A Home Assistant entity announced via discovery
+struct Entity {
+    component: &'static str,
+    object_id: &'static str,
+    name: &'static str,
+    /// Message type whose topic holds the state
+    message_type: &'static str,
+    value_template: &'static str,
+    attributes_template: Option<&'static str>,
+    icon: &'static str,
+}
+
+/// Sensor states are limited to 255 characters
+const ENTITIES: &[Entity] = &[
+    Entity {
+        component: "sensor",
+        object_id: "window",
+        name: "Window",
+        message_type: "window_info",
+        value_template: "{{ value_json.data.title[:255] }}",
+        attributes_template: Some("{{ value_json.data | tojson }}"),
+        icon: "mdi:application",
+    },
+    Entity {
+        component: "sensor",
+        object_id: "application",
+        name: "Application",
+        message_type: "window_info",
+        value_template: "{{ value_json.data.process_name[:255] }}",
+        attributes_template: None,
+        icon: "mdi:apps",
+    },
+    Entity {
+        component: "sensor",
+        object_id: "media",
+        name: "Media",
+        message_type: "media_playback",
+        value_template: "{{ (value_json.metadata.title or '')[:255] }}",
+        attributes_template: Some("{{ value_json.metadata | tojson }}"),
+        icon: "mdi:music",
+    },
+    Entity {
+        component: "binary_sensor",
+        object_id: "media_playing",
+        name: "Media playing",
+        message_type: "media_playback",
+        value_template: "{{ 'ON' if value_json.playback_state.playing else 'OFF' }}",
+        attributes_template: None,
+        icon: "mdi:play",
+    },
+    Entity {
+        component: "sensor",
+        object_id: "presence",
+        name: "Presence",
+        message_type: "presence",
+        value_template: "{{ value_json.state }}",
+        attributes_template: None,
+        icon: "mdi:account",
+    },
+];
+
+#[derive(Deserialize)]
+struct MessageType {
+    #[serde(rename = "type")]
+    msg_type: String,
+}
+
+pub struct MqttTransport {
+    options: MqttOptions,
+    config: MqttConfig,
+    /// Redacted broker URL for logs
+    broker: String,
+    /// `<topic_prefix>/<device id>`
+    base_topic: String,
+    client: Option<AsyncClient>,
+    /// Error that ended the event loop task
+    errors: Option<mpsc::UnboundedReceiver<String>>,
+    task: Option<JoinHandle<()>>,
+    /// Content id of the artwork currently retained on `.../artwork`
+    ///
+    /// The broker never answers with a URL, so the reporter keeps offering the artwork on every
+    /// metadata change; only a different track is published again.
+    published_artwork: Option<String>,
+}
+
+impl MqttTransport {
+    pub fn new(url: Url, token: String, config: MqttConfig) -> Result<Self, String> {
+        let tls = match url.scheme() {
+            "mqtt" => false,
+            "mqtts" => true,
+            scheme => return Err(format!("Unsupported MQTT URL scheme \"{}\", expected mqtt:// or mqtts://", scheme)),
+        };
+        let host = url.host_str().ok_or_else(|| "MQTT URL has no host".to_string())?;
+        let port = url.port().unwrap_or(if tls { 8883 } else { 1883 });
+
+        let device = device_id();
+        let base_topic = format!("{}/{}", config.topic_prefix.trim_end_matches('/'), device);
+        let mut options = MqttOptions::new(format!("shikenmatrix-{}", device), host, port);
+        options
+            .set_keep_alive(Duration::from_secs(config.keep_alive_secs))
+            .set_max_packet_size(MAX_INCOMING_PACKET, MAX_OUTGOING_PACKET)
+            .set_last_will(LastWill::new(format!("{}/status", base_topic), "offline", QoS::AtLeastOnce, true));
+
+        // Credentials from the URL; `token` is the password unless the URL has one
+        let username = percent_decode_str(url.username()).decode_utf8_lossy().into_owned();
+        let password = match url.password() {
+            Some(password) => percent_decode_str(password).decode_utf8_lossy().into_owned(),
+            None => token,
+        };
+        if !username.is_empty() || !password.is_empty() {
+            let username = if username.is_empty() { DEFAULT_USERNAME.to_string() } else { username };
+            options.set_credentials(username, password);
+        }
+        if tls {
+            options.set_transport(rumqttc::Transport::Tls(TlsConfiguration::Rustls(tls_config())));
+        }
+
+        Ok(Self {
+            options,
+            config,
+            broker: redact(url.as_str()).into_owned(),
+            base_topic,
+            client: None,
+            errors: None,
+            task: None,
+            published_artwork: None,
+        })
+    }
+
+    fn topic(&self, name: &str) -> String {
+        format!("{}/{}", self.base_topic, name)
+    }
+
+    async fn publish(&self, topic: String, retain: bool, payload: Vec<u8>) -> Result<(), String> {
+        let client = self.client.as_ref().ok_or_else(|| "MQTT not connected".to_string())?;
+        client.publish(topic, QoS::AtLeastOnce, retain, payload).await.map_err(|e| e.to_string())
+    }
+
+    /// Home Assistant discovery config: one retained message per entity
+    fn discovery_messages(&self) -> Vec<(String, serde_json::Value)> {
+        let node_id = format!("shikenmatrix_{}", device_id());
+        let name = match self.config.device_name.trim() {
+            "" => format!("ShikenMatrix {}", device_id().chars().take(8).collect::<String>()),
+            name => name.to_string(),
+        };
+        let device = json!({
+            "identifiers": [node_id],
+            "name": name,
+            "manufacturer": "ShikenMatrix",
+            "model": std::env::consts::OS,
+            "sw_version": env!("CARGO_PKG_VERSION"),
+        });
+
+        ENTITIES.iter().map(|entity| {
+            let state_topic = self.topic(entity.message_type);
+            let mut config = json!({
+                "name": entity.name,
+                "unique_id": format!("{}_{}", node_id, entity.object_id),
+                "state_topic": state_topic,
+                "value_template": entity.value_template,
+                "availability_topic": self.topic("status"),
+                "icon": entity.icon,
+                "device": device,
+            });
+            if let Some(template) = entity.attributes_template {
+                config["json_attributes_topic"] = json!(state_topic);
+                config["json_attributes_template"] = json!(template);
+            }
+            let topic = format!(
+                "{}/{}/{}/{}/config",
+                self.config.discovery_prefix.trim_end_matches('/'), entity.component, node_id, entity.object_id
+            );
+            (topic, config)
+        }).collect()
+    }
+}
+
+/// Poll the event loop until the connection fails. rumqttc would reconnect on the next poll;
+/// that is left to the reporter's backoff instead
+async fn drive(mut eventloop: EventLoop, errors: mpsc::UnboundedSender<String>) {
+    loop {
+        if let Err(e) = eventloop.poll().await {
+            let _ = errors.send(redact(&e.to_string()).into_owned());
+            return;
+        }
+    }
+}
+
+impl Transport for MqttTransport {
+    fn connect(&mut self) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async move {
+            info!("Connecting to MQTT broker: {} (topic: {})", self.broker, self.base_topic);
+            let (client, mut eventloop) = AsyncClient::new(self.options.clone(), REQUEST_CAPACITY);
+
+            // The first polls open the connection and wait for the broker's ConnAck
+            loop {
+                match eventloop.poll().await {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => break,
+                    Ok(_) => {}
+                    Err(e) => return Err(redact(&e.to_string()).into_owned()),
+                }
+            }
+            info!("✅ MQTT connected!");
+
+            let (error_tx, error_rx) = mpsc::unbounded_channel();
+            self.task = Some(tokio::spawn(drive(eventloop, error_tx)));
+            self.errors = Some(error_rx);
+            self.client = Some(client);
+
+            self.publish(self.topic("status"), true, b"online".to_vec()).await?;
+            if self.config.discovery {
+                for (topic, config) in self.discovery_messages() {
+                    self.publish(topic, true, config.to_string().into_bytes()).await?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn send_text(&mut self, json: String) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async move {
+            let msg_type = serde_json::from_str::<MessageType>(&json)
+                .map_err(|e| format!("Message without a type: {}", e))?
+                .msg_type;
+            let retain = !EVENT_TYPES.contains(&msg_type.as_str());
+            self.publish(self.topic(&msg_type), retain, json.into_bytes()).await
+        })
+    }
+
+    fn send_artwork(&mut self, artwork: Artwork) -> BoxFuture<'_, Result<(), String>> {
+        // Raw image bytes; there is no reply, so `artwork_url` stays unset
+        Box::pin(async move {
+            if self.published_artwork.as_ref() == Some(&artwork.content_item_identifier) {
+                return Ok(());
+            }
+            self.publish(self.topic("artwork"), true, artwork.data).await?;
+            self.published_artwork = Some(artwork.content_item_identifier);
+            Ok(())
+        })
+    }
+
+    fn recv(&mut self) -> BoxFuture<'_, Result<Incoming, String>> {
+        // Nothing is subscribed; only the end of the connection is reported
+        Box::pin(async move {
+            let errors = self.errors.as_mut().ok_or_else(|| "MQTT not connected".to_string())?;
+            match errors.recv().await {
+                Some(e) => Err(e),
+                None => Ok(Incoming::Closed),
+            }
+        })
+    }
+
+    fn close(&mut self) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            // A clean disconnect suppresses the last will, so announce `offline` explicitly
+            let _ = self.publish(self.topic("status"), true, b"offline".to_vec()).await;
+            if let Some(client) = self.client.take() {
+                let _ = client.disconnect().await;
+            }
+            if let Some(task) = self.task.take() {
+                let abort = task.abort_handle();
+                if tokio::time::timeout(CLOSE_TIMEOUT, task).await.is_err() {
+                    abort.abort();
+                }
+            }
+        })
+    }
+}
+
+impl Drop for MqttTransport {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! WebSocket transport
+//! One long-lived connection; artwork is sent as an `upload_artwork_meta` message followed by a
+//! binary frame
+
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, handshake::client::Request, http::HeaderValue, Message};
+use tokio_tungstenite::{connect_async_tls_with_config, Connector, MaybeTlsStream, WebSocketStream};
+use tracing::info;
+use url::Url;
+
+use super::{tls_config, Artwork, Incoming, Transport};
+use crate::services::redact::redact;
+use crate::services::reporter::AuthMode;
+
+/// Subprotocol the server selects in `AuthMode::Subprotocol`
+const AUTH_SUBPROTOCOL: &str = "shikenmatrix";
+
+#[derive(Debug, Clone, Serialize)]
+struct UploadArtworkMetaMessage<'a> {
+    #[serde(rename = "type")]
+    msg_type: &'static str,
+    content_item_identifier: &'a str,
+    mime_type: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<u32>,
+}
+
+pub struct WebSocketTransport {
+    url: Url,
+    token: String,
+    auth_mode: AuthMode,
+    stream: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+}
+
+impl WebSocketTransport {
+    pub fn new(url: Url, token: String, auth_mode: AuthMode) -> Self {
+        Self { url, token, auth_mode, stream: None }
+    }
+
+    fn stream(&mut self) -> Result<&mut WebSocketStream<MaybeTlsStream<TcpStream>>, String> {
+        self.stream.as_mut().ok_or_else(|| "WebSocket not connected".to_string())
+    }
+
+    async fn send(&mut self, message: Message) -> Result<(), String> {
+        self.stream()?.send(message).await.map_err(|e| e.to_string())
+    }
+}
+
+/// Build the handshake request carrying the token as configured
+pub(crate) fn build_request(mut url: Url, token: &str, auth_mode: AuthMode) -> Result<Request, String> {
+    if auth_mode == AuthMode::Query {
+        url.query_pairs_mut().append_pair("token", token);
+    }
+    let mut request = url.as_str().into_client_request().map_err(|e| e.to_string())?;
+    if token.is_empty() {
+        return Ok(request);
+    }
+
+    let header = match auth_mode {
+        AuthMode::Bearer => Some(("Authorization", format!("Bearer {}", token))),
+        AuthMode::Subprotocol => Some(("Sec-WebSocket-Protocol", format!("{}, {}", AUTH_SUBPROTOCOL, token))),
+        AuthMode::Query => None,
+    };
+    if let Some((name, value)) = header {
+        let value = HeaderValue::from_str(&value).map_err(|_| "Token contains characters not allowed in a header".to_string())?;
+        request.headers_mut().insert(name, value);
+    }
+    Ok(request)
+}
+
+impl Transport for WebSocketTransport {
+    fn connect(&mut self) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async move {
+            let request = build_request(self.url.clone(), &self.token, self.auth_mode)?;
+            info!("Connecting to WebSocket: {} (auth: {:?})", redact(&request.uri().to_string()), self.auth_mode);
+
+            // Create TLS connector that forces HTTP/1.1 (required for WebSocket over HTTPS)
+            let connector = Connector::Rustls(tls_config());
+
+            let (stream, response) = connect_async_tls_with_config(request, None, false, Some(connector))
+                .await
+                .map_err(|e| e.to_string())?;
+            info!("✅ WebSocket connected! Status: {}", response.status());
+            self.stream = Some(stream);
+            Ok(())
+        })
+    }
+
+    fn send_text(&mut self, json: String) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(self.send(Message::Text(json.into())))
+    }
+
+    fn send_artwork(&mut self, artwork: Artwork) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(async move {
+            let meta = UploadArtworkMetaMessage {
+                msg_type: "upload_artwork_meta",
+                content_item_identifier: &artwork.content_item_identifier,
+                mime_type: &artwork.mime_type,
+                width: artwork.width,
+                height: artwork.height,
+            };
+            let meta_json = serde_json::to_string(&meta).map_err(|e| e.to_string())?;
+            self.send(Message::Text(meta_json.into())).await?;
+            self.send(Message::Binary(artwork.data.into())).await
+        })
+    }
+
+    fn recv(&mut self) -> BoxFuture<'_, Result<Incoming, String>> {
+        Box::pin(async move {
+            let stream = self.stream()?;
+            loop {
+                match stream.next().await {
+                    Some(Ok(Message::Text(text))) => return Ok(Incoming::Text(text.to_string())),
+                    Some(Ok(Message::Pong(payload))) => return Ok(Incoming::Pong(payload.to_vec())),
+                    Some(Ok(Message::Close(_))) | None => return Ok(Incoming::Closed),
+                    Some(Err(e)) => return Err(e.to_string()),
+                    Some(Ok(_)) => {}
+                }
+            }
+        })
+    }
+
+    fn supports_ping(&self) -> bool {
+        true
+    }
+
+    fn ping(&mut self, payload: Vec<u8>) -> BoxFuture<'_, Result<(), String>> {
+        Box::pin(self.send(Message::Ping(payload.into())))
+    }
+
+    fn close(&mut self) -> BoxFuture<'_, ()> {
+        Box::pin(async move {
+            if let Some(stream) = self.stream.as_mut() {
+                let _ = stream.close(None).await;
+            }
+        })
+    }
+}
@@ -0,0 +1,45 @@
+//! Helpers shared by the integration tests
+
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+/// A `dbus-daemon` private to one test binary, killed on drop
+pub struct PrivateBus {
+    child: Child,
+    /// Address to connect to, e.g. `unix:path=/tmp/dbus-XXXX,guid=...`
+    pub address: String,
+}
+
+impl PrivateBus {
+    /// Start a session-type bus; `None` (test skipped) when `dbus-daemon` is not installed
+    pub fn start() -> Option<Self> {
+        let mut child = match Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address=1"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                eprintln!("skipping: cannot start dbus-daemon: {}", e);
+                return None;
+            }
+        };
+
+        let mut address = String::new();
+        let stdout = child.stdout.take().expect("dbus-daemon stdout");
+        BufReader::new(stdout).read_line(&mut address).expect("dbus-daemon address");
+        let address = address.trim().to_string();
+        assert!(!address.is_empty(), "dbus-daemon did not print its address");
+        Some(Self { child, address })
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
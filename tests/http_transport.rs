@@ -0,0 +1,200 @@
+//! HTTP transport against a local server
+
+use shikenmatrix_native::services::transport::{Artwork, HttpTransport, Incoming};
+use shikenmatrix_native::services::{AuthMode, Transport};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use url::Url;
+
+const TOKEN: &str = "s3cret-token";
+
+/// A request as received by the server
+#[derive(Debug)]
+struct Request {
+    /// Path and query
+    target: String,
+    /// Lowercase names
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// Answer each request with the next `(status, body)`; every connection is closed after one response
+async fn serve(responses: Vec<(u16, &'static str)>) -> (Url, mpsc::UnboundedReceiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = Url::parse(&format!("http://{}/report", listener.local_addr().unwrap())).unwrap();
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        for (status, body) in responses {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).await.unwrap();
+            let target = request_line.split_whitespace().nth(1).unwrap_or_default().to_string();
+            let mut headers = HashMap::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).await.unwrap();
+                let Some((name, value)) = line.trim_end().split_once(':') else { break };
+                headers.insert(name.to_ascii_lowercase(), value.trim().to_string());
+            }
+            let length = headers.get("content-length").and_then(|l| l.parse().ok()).unwrap_or(0);
+            let mut request_body = vec![0; length];
+            reader.read_exact(&mut request_body).await.unwrap();
+            let _ = tx.send(Request { target, headers, body: request_body });
+
+            let response = format!(
+                "HTTP/1.1 {} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let mut stream = reader.into_inner();
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.shutdown().await.unwrap();
+        }
+    });
+    (url, rx)
+}
+
+async fn recv_reply(transport: &mut HttpTransport) -> String {
+    match tokio::time::timeout(Duration::from_secs(1), transport.recv()).await {
+        Ok(Ok(Incoming::Text(text))) => text,
+        other => panic!("expected a reply, got {:?}", other.map(|r| r.map(|_| ()))),
+    }
+}
+
+#[tokio::test]
+async fn posts_json_with_bearer_token_and_returns_replies() {
+    let (url, mut requests) = serve(vec![(200, r#"{"type":"welcome","features":{"window_list":false}}"#)]).await;
+    let mut transport = HttpTransport::new(url, TOKEN.to_string(), AuthMode::Bearer).unwrap();
+    transport.connect().await.unwrap();
+
+    transport.send_text(r#"{"type":"hello"}"#.to_string()).await.unwrap();
+    let request = requests.recv().await.unwrap();
+    assert_eq!(request.target, "/report");
+    assert_eq!(request.body, br#"{"type":"hello"}"#);
+    assert_eq!(request.headers["authorization"], format!("Bearer {}", TOKEN));
+    assert_eq!(request.headers["content-type"], "application/json");
+    assert!(request.headers["user-agent"].starts_with("shikenmatrix/"));
+
+    let reply: serde_json::Value = serde_json::from_str(&recv_reply(&mut transport).await).unwrap();
+    assert_eq!(reply["type"], "welcome");
+    assert_eq!(reply["features"]["window_list"], false);
+}
+
+#[tokio::test]
+async fn query_auth_puts_the_token_in_the_url() {
+    let (url, mut requests) = serve(vec![(204, "")]).await;
+    let mut transport = HttpTransport::new(url, TOKEN.to_string(), AuthMode::Query).unwrap();
+
+    transport.send_text(r#"{"type":"presence"}"#.to_string()).await.unwrap();
+    let request = requests.recv().await.unwrap();
+    assert_eq!(request.target, format!("/report?token={}", TOKEN));
+    assert!(!request.headers.contains_key("authorization"));
+}
+
+#[tokio::test]
+async fn artwork_is_posted_raw_and_answered_with_its_url() {
+    let (url, mut requests) = serve(vec![(
+        200,
+        r#"[{"type":"artwork_uploaded","content_item_identifier":"player:track 1","artwork_url":"https://cdn.example/a.png"}]"#,
+    )])
+    .await;
+    let mut transport = HttpTransport::new(url, TOKEN.to_string(), AuthMode::Bearer).unwrap();
+
+    let artwork = Artwork {
+        content_item_identifier: "player:track 1".to_string(),
+        mime_type: "image/png".to_string(),
+        width: Some(3),
+        height: Some(2),
+        data: vec![0x89, b'P', b'N', b'G'],
+    };
+    transport.send_artwork(artwork).await.unwrap();
+    let request = requests.recv().await.unwrap();
+    let query: HashMap<String, String> = Url::parse(&format!("http://localhost{}", request.target))
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect();
+    assert_eq!(query["type"], "upload_artwork");
+    assert_eq!(query["content_item_identifier"], "player:track 1");
+    assert_eq!((query["width"].as_str(), query["height"].as_str()), ("3", "2"));
+    assert_eq!(request.headers["content-type"], "image/png");
+    assert_eq!(request.headers["authorization"], format!("Bearer {}", TOKEN));
+    assert_eq!(request.body, [0x89, b'P', b'N', b'G']);
+
+    let reply: serde_json::Value = serde_json::from_str(&recv_reply(&mut transport).await).unwrap();
+    assert_eq!(reply["type"], "artwork_uploaded");
+    assert_eq!(reply["artwork_url"], "https://cdn.example/a.png");
+}
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let (url, mut requests) = serve(vec![(503, ""), (429, ""), (200, "")]).await;
+    let mut transport = HttpTransport::new(url, String::new(), AuthMode::Bearer).unwrap();
+
+    transport.send_text(r#"{"type":"presence"}"#.to_string()).await.unwrap();
+    for _ in 0..3 {
+        let request = requests.recv().await.unwrap();
+        assert_eq!(request.body, br#"{"type":"presence"}"#);
+        assert!(!request.headers.contains_key("authorization"));
+    }
+}
+
+#[tokio::test]
+async fn persistent_server_errors_fail_the_connection() {
+    let (url, mut requests) = serve(vec![(500, ""), (502, ""), (500, "")]).await;
+    let mut transport = HttpTransport::new(url, TOKEN.to_string(), AuthMode::Bearer).unwrap();
+
+    let error = transport.send_text(r#"{"type":"presence"}"#.to_string()).await.unwrap_err();
+    assert!(error.contains("after 3 attempts") && error.contains("500"), "{}", error);
+    for _ in 0..3 {
+        requests.recv().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn rejected_token_fails_without_retrying() {
+    let (url, mut requests) = serve(vec![(401, "")]).await;
+    let mut transport = HttpTransport::new(url, TOKEN.to_string(), AuthMode::Bearer).unwrap();
+
+    let error = transport.send_text(r#"{"type":"presence"}"#.to_string()).await.unwrap_err();
+    assert!(error.contains("401"), "{}", error);
+    assert!(!error.contains(TOKEN), "token leaked into {}", error);
+    requests.recv().await.unwrap();
+}
+
+#[tokio::test]
+async fn other_client_errors_drop_the_message() {
+    let (url, mut requests) = serve(vec![(200, ""), (400, ""), (200, "")]).await;
+    let mut transport = HttpTransport::new(url, TOKEN.to_string(), AuthMode::Bearer).unwrap();
+    transport.connect().await.unwrap();
+
+    transport.send_text(r#"{"type":"hello"}"#.to_string()).await.unwrap();
+    transport.send_text(r#"{"type":"bogus"}"#.to_string()).await.unwrap();
+    transport.send_text(r#"{"type":"presence"}"#.to_string()).await.unwrap();
+    assert_eq!(requests.recv().await.unwrap().body, br#"{"type":"hello"}"#);
+    assert_eq!(requests.recv().await.unwrap().body, br#"{"type":"bogus"}"#);
+    assert_eq!(requests.recv().await.unwrap().body, br#"{"type":"presence"}"#);
+}
+
+#[tokio::test]
+async fn rejected_hello_fails_the_connection() {
+    let (url, mut requests) = serve(vec![(404, ""), (200, ""), (404, "")]).await;
+    let mut transport = HttpTransport::new(url, TOKEN.to_string(), AuthMode::Bearer).unwrap();
+    transport.connect().await.unwrap();
+
+    let error = transport.send_text(r#"{"type":"hello"}"#.to_string()).await.unwrap_err();
+    assert!(error.contains("404"), "{}", error);
+    assert_eq!(requests.recv().await.unwrap().body, br#"{"type":"hello"}"#);
+
+    // Every `connect` needs a successful request again
+    transport.send_text(r#"{"type":"hello"}"#.to_string()).await.unwrap();
+    transport.connect().await.unwrap();
+    transport.send_text(r#"{"type":"hello"}"#.to_string()).await.unwrap_err();
+}
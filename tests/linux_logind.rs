@@ -0,0 +1,114 @@
+//! logind session watcher against a mock logind on a private bus
+//! Skipped when `dbus-daemon` is not installed
+
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::PrivateBus;
+use shikenmatrix_native::platform::linux::LogindSessionSource;
+use shikenmatrix_native::platform::{SessionEvent, SessionSource};
+use std::collections::HashMap;
+use std::time::Duration;
+use zbus::zvariant::{OwnedObjectPath, Value};
+
+const LOGIN1_NAME: &str = "org.freedesktop.login1";
+const MANAGER_PATH: &str = "/org/freedesktop/login1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.login1.Manager";
+const SESSION_PATH: &str = "/org/freedesktop/login1/session/_31";
+const SESSION_INTERFACE: &str = "org.freedesktop.login1.Session";
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct MockManager;
+
+#[zbus::interface(name = "org.freedesktop.login1.Manager")]
+impl MockManager {
+    fn get_session(&self, _session_id: &str) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(SESSION_PATH).unwrap()
+    }
+
+    #[zbus(name = "GetSessionByPID")]
+    fn get_session_by_pid(&self, _pid: u32) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(SESSION_PATH).unwrap()
+    }
+}
+
+struct MockSession {
+    locked_hint: bool,
+}
+
+#[zbus::interface(name = "org.freedesktop.login1.Session")]
+impl MockSession {
+    #[zbus(property)]
+    fn locked_hint(&self) -> bool {
+        self.locked_hint
+    }
+}
+
+fn start_logind(bus: &PrivateBus, locked_hint: bool) -> zbus::blocking::Connection {
+    zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .name(LOGIN1_NAME)
+        .unwrap()
+        .serve_at(MANAGER_PATH, MockManager)
+        .unwrap()
+        .serve_at(SESSION_PATH, MockSession { locked_hint })
+        .unwrap()
+        .build()
+        .expect("register mock logind")
+}
+
+fn emit_locked_hint(logind: &zbus::blocking::Connection, locked: bool) {
+    let changed: HashMap<&str, Value> = HashMap::from([("LockedHint", Value::from(locked))]);
+    logind
+        .emit_signal(
+            None::<()>,
+            SESSION_PATH,
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+            &(SESSION_INTERFACE, changed, Vec::<&str>::new()),
+        )
+        .unwrap();
+}
+
+fn emit_session_signal(logind: &zbus::blocking::Connection, member: &str) {
+    logind.emit_signal(None::<()>, SESSION_PATH, SESSION_INTERFACE, member, &()).unwrap();
+}
+
+fn emit_prepare_for_sleep(logind: &zbus::blocking::Connection, start: bool) {
+    logind.emit_signal(None::<()>, MANAGER_PATH, MANAGER_INTERFACE, "PrepareForSleep", &(start,)).unwrap();
+}
+
+#[test]
+fn lock_state_follows_locked_hint() {
+    let Some(bus) = PrivateBus::start() else { return };
+    let logind = start_logind(&bus, false);
+    let events = LogindSessionSource::with_address(bus.address.as_str()).subscribe().expect("subscribe");
+    let next = || events.recv_timeout(EVENT_TIMEOUT).expect("session event");
+
+    // `Lock` is only a request to the screen locker; the state changes with `LockedHint`
+    emit_session_signal(&logind, "Lock");
+    emit_locked_hint(&logind, true);
+    assert_eq!(next(), SessionEvent::Locked);
+
+    // A repeated hint does not produce another event, and neither does `Unlock`
+    emit_locked_hint(&logind, true);
+    emit_session_signal(&logind, "Unlock");
+    emit_prepare_for_sleep(&logind, true);
+    assert_eq!(next(), SessionEvent::Suspending);
+
+    emit_prepare_for_sleep(&logind, false);
+    assert_eq!(next(), SessionEvent::Resumed);
+
+    emit_locked_hint(&logind, false);
+    assert_eq!(next(), SessionEvent::Unlocked);
+}
+
+#[test]
+fn already_locked_session_is_reported_first() {
+    let Some(bus) = PrivateBus::start() else { return };
+    let _logind = start_logind(&bus, true);
+    let events = LogindSessionSource::with_address(bus.address.as_str()).subscribe().expect("subscribe");
+
+    assert_eq!(events.recv_timeout(EVENT_TIMEOUT).expect("initial state"), SessionEvent::Locked);
+}
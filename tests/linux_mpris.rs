@@ -0,0 +1,138 @@
+//! MPRIS backend against a mock player on a private session bus
+//! Skipped when `dbus-daemon` is not installed
+
+#![cfg(target_os = "linux")]
+
+mod common;
+
+use common::PrivateBus;
+use shikenmatrix_native::platform::linux::{get_media_sessions, subscribe_media_changes};
+use shikenmatrix_native::platform::MediaSession;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+
+const PLAYER_NAME: &str = "org.mpris.MediaPlayer2.mockplayer.instance42";
+const PLAYER_PATH: &str = "/org/mpris/MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct PlayerState {
+    status: &'static str,
+    /// Microseconds
+    position: i64,
+    rate: f64,
+    art_url: String,
+}
+
+struct MockPlayer(Arc<Mutex<PlayerState>>);
+
+fn owned(value: Value<'_>) -> OwnedValue {
+    value.try_into().expect("owned value")
+}
+
+#[zbus::interface(name = "org.mpris.MediaPlayer2.Player")]
+impl MockPlayer {
+    #[zbus(property)]
+    fn playback_status(&self) -> String {
+        self.0.lock().unwrap().status.to_string()
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        let state = self.0.lock().unwrap();
+        HashMap::from([
+            ("mpris:trackid".to_string(), owned(ObjectPath::from_static_str_unchecked("/org/mpris/MediaPlayer2/Track/7").into())),
+            ("xesam:title".to_string(), owned("Clair de Lune".into())),
+            ("xesam:artist".to_string(), owned(vec!["Claude Debussy", "Isao Tomita"].into())),
+            ("xesam:album".to_string(), owned("Snowflakes Are Dancing".into())),
+            ("mpris:length".to_string(), owned(Value::I64(300_000_000))),
+            ("mpris:artUrl".to_string(), owned(state.art_url.as_str().into())),
+        ])
+    }
+
+    #[zbus(property)]
+    fn position(&self) -> i64 {
+        self.0.lock().unwrap().position
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        self.0.lock().unwrap().rate
+    }
+}
+
+fn next_sessions(events: &std::sync::mpsc::Receiver<Vec<MediaSession>>) -> Vec<MediaSession> {
+    events.recv_timeout(EVENT_TIMEOUT).expect("media event")
+}
+
+#[test]
+fn mock_player_mapping_and_signals() {
+    let Some(bus) = PrivateBus::start() else { return };
+    std::env::set_var("DBUS_SESSION_BUS_ADDRESS", &bus.address);
+
+    let art_path = std::env::temp_dir().join(format!("shikenmatrix-mpris-art-{}.png", std::process::id()));
+    image::RgbaImage::new(3, 2).save(&art_path).expect("write artwork");
+    let art_url = url::Url::from_file_path(&art_path).unwrap().to_string();
+
+    let state = Arc::new(Mutex::new(PlayerState { status: "Playing", position: 12_500_000, rate: 1.5, art_url }));
+    let player = zbus::blocking::connection::Builder::address(bus.address.as_str())
+        .unwrap()
+        .name(PLAYER_NAME)
+        .unwrap()
+        .serve_at(PLAYER_PATH, MockPlayer(state.clone()))
+        .unwrap()
+        .build()
+        .expect("register mock player");
+
+    // Metadata and playback mapping
+    let sessions = get_media_sessions().expect("sessions");
+    assert_eq!(sessions.len(), 1);
+    let session = &sessions[0];
+    assert_eq!(session.id, PLAYER_NAME);
+    let metadata = &session.metadata;
+    assert_eq!(metadata.bundle_identifier.as_deref(), Some("mockplayer"));
+    assert_eq!(metadata.title.as_deref(), Some("Clair de Lune"));
+    assert_eq!(metadata.artist.as_deref(), Some("Claude Debussy, Isao Tomita"));
+    assert_eq!(metadata.album.as_deref(), Some("Snowflakes Are Dancing"));
+    assert_eq!(metadata.duration, 300.0);
+    assert_eq!(metadata.content_item_identifier.as_deref(), Some("mockplayer:/org/mpris/MediaPlayer2/Track/7"));
+    assert_eq!(metadata.artwork_mime_type.as_deref(), Some("image/png"));
+    assert_eq!((metadata.artwork_width, metadata.artwork_height), (Some(3), Some(2)));
+    assert!(session.state.playing);
+    assert_eq!(session.state.playback_rate, 1.5);
+    assert_eq!(session.state.elapsed_time, 12.5);
+
+    let events = subscribe_media_changes().expect("subscribe");
+    assert_eq!(next_sessions(&events).len(), 1);
+
+    // Seeked: the new position is read back
+    state.lock().unwrap().position = 200_000_000;
+    player.emit_signal(None::<()>, PLAYER_PATH, PLAYER_INTERFACE, "Seeked", &(200_000_000i64,)).unwrap();
+    let sessions = next_sessions(&events);
+    assert_eq!(sessions[0].state.elapsed_time, 200.0);
+
+    // PropertiesChanged: paused players report a rate of 0
+    state.lock().unwrap().status = "Paused";
+    let changed: HashMap<&str, Value> = HashMap::from([("PlaybackStatus", Value::from("Paused"))]);
+    player
+        .emit_signal(
+            None::<()>,
+            PLAYER_PATH,
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+            &(PLAYER_INTERFACE, changed, Vec::<&str>::new()),
+        )
+        .unwrap();
+    let sessions = next_sessions(&events);
+    assert!(!sessions[0].state.playing);
+    assert_eq!(sessions[0].state.playback_rate, 0.0);
+
+    // NameOwnerChanged: a player leaving the bus is dropped
+    drop(player);
+    let sessions = next_sessions(&events);
+    assert!(sessions.is_empty(), "player should be gone: {:?}", sessions.iter().map(|s| &s.id).collect::<Vec<_>>());
+
+    let _ = std::fs::remove_file(&art_path);
+}
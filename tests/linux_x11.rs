@@ -0,0 +1,126 @@
+//! X11 backend against a private Xvfb server
+//! Needs `Xvfb`, so ignored by default: `cargo test --test linux_x11 -- --ignored`
+
+#![cfg(target_os = "linux")]
+
+use shikenmatrix_native::platform::linux::{get_frontmost_window, subscribe_window_changes};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, CreateWindowAux, PropMode, Window, WindowClass};
+use x11rb::rust_connection::RustConnection;
+use x11rb::wrapper::ConnectionExt as _;
+
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Xvfb(Child);
+
+impl Drop for Xvfb {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Start Xvfb on a free display and point `DISPLAY` at it
+fn start_xvfb() -> Xvfb {
+    let display = (99..200)
+        .find(|n| !Path::new(&format!("/tmp/.X11-unix/X{}", n)).exists())
+        .expect("no free X display");
+    let child = Command::new("Xvfb")
+        .args([format!(":{}", display).as_str(), "-nolisten", "tcp", "-screen", "0", "640x480x24"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("cannot start Xvfb");
+    let xvfb = Xvfb(child);
+
+    let socket = format!("/tmp/.X11-unix/X{}", display);
+    let deadline = Instant::now() + EVENT_TIMEOUT;
+    while !Path::new(&socket).exists() {
+        assert!(Instant::now() < deadline, "Xvfb did not come up on :{}", display);
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    std::env::set_var("DISPLAY", format!(":{}", display));
+    xvfb
+}
+
+fn atom(conn: &RustConnection, name: &str) -> u32 {
+    conn.intern_atom(false, name.as_bytes()).unwrap().reply().unwrap().atom
+}
+
+/// Create an unmapped client window carrying the EWMH properties a real application sets
+fn create_window(conn: &RustConnection, root: Window, title: &str, class: &[u8]) -> Window {
+    let window = conn.generate_id().unwrap();
+    conn.create_window(0, window, root, 0, 0, 100, 80, 0, WindowClass::INPUT_OUTPUT, 0, &CreateWindowAux::new())
+        .unwrap();
+    set_title(conn, window, title);
+    conn.change_property8(PropMode::REPLACE, window, AtomEnum::WM_CLASS, AtomEnum::STRING, class).unwrap();
+    conn.change_property32(PropMode::REPLACE, window, atom(conn, "_NET_WM_PID"), AtomEnum::CARDINAL, &[std::process::id()])
+        .unwrap();
+    // 2x2 opaque red icon
+    conn.change_property32(
+        PropMode::REPLACE,
+        window,
+        atom(conn, "_NET_WM_ICON"),
+        AtomEnum::CARDINAL,
+        &[2, 2, 0xFFFF_0000, 0xFFFF_0000, 0xFFFF_0000, 0xFFFF_0000],
+    )
+    .unwrap();
+    window
+}
+
+fn set_title(conn: &RustConnection, window: Window, title: &str) {
+    conn.change_property8(PropMode::REPLACE, window, atom(conn, "_NET_WM_NAME"), atom(conn, "UTF8_STRING"), title.as_bytes())
+        .unwrap();
+    conn.flush().unwrap();
+}
+
+fn activate(conn: &RustConnection, root: Window, window: Window) {
+    conn.change_property32(PropMode::REPLACE, root, atom(conn, "_NET_ACTIVE_WINDOW"), AtomEnum::WINDOW, &[window])
+        .unwrap();
+    conn.flush().unwrap();
+}
+
+#[test]
+#[ignore = "needs Xvfb"]
+fn frontmost_window_and_focus_events() {
+    let _xvfb = start_xvfb();
+    let (conn, screen) = x11rb::connect(None).expect("connect to Xvfb");
+    let root = conn.setup().roots[screen].root;
+
+    let editor = create_window(&conn, root, "notes.txt — Editor", b"editor\0Editor\0");
+    activate(&conn, root, editor);
+
+    let own_name = std::fs::read_to_string("/proc/self/comm").unwrap().trim_end().to_string();
+    let info = get_frontmost_window().expect("frontmost window");
+    assert_eq!(info.title, "notes.txt — Editor");
+    assert_eq!(info.process_name, own_name);
+    assert_eq!(info.pid, std::process::id() as i32);
+    assert_eq!(info.app_id.as_deref(), Some("Editor"));
+    assert!(!info.private_browsing);
+    let icon = info.icon_data.expect("icon converted from _NET_WM_ICON");
+    assert!(icon.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+    // The watcher reports the current window first, then every focus and title change
+    let events = subscribe_window_changes().expect("subscribe");
+    let first = events.recv_timeout(EVENT_TIMEOUT).expect("initial window");
+    assert_eq!(first.title, "notes.txt — Editor");
+
+    let browser = create_window(&conn, root, "Example Domain — Browser", b"browser\0Browser\0");
+    activate(&conn, root, browser);
+    let focused = events.recv_timeout(EVENT_TIMEOUT).expect("focus change");
+    assert_eq!(focused.title, "Example Domain — Browser");
+    assert_eq!(focused.app_id.as_deref(), Some("Browser"));
+
+    set_title(&conn, browser, "Another Page — Browser");
+    let renamed = events.recv_timeout(EVENT_TIMEOUT).expect("title change");
+    assert_eq!(renamed.title, "Another Page — Browser");
+
+    // Title changes of windows that are no longer focused are ignored
+    set_title(&conn, editor, "ignored");
+    activate(&conn, root, editor);
+    let refocused = events.recv_timeout(EVENT_TIMEOUT).expect("focus back");
+    assert_eq!(refocused.title, "ignored");
+}
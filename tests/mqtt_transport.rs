@@ -0,0 +1,219 @@
+//! MQTT transport against a private mosquitto broker
+//! Needs `mosquitto`, so ignored by default: `cargo test --test mqtt_transport -- --ignored`
+
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, Publish, QoS};
+use shikenmatrix_native::services::handshake::device_id;
+use shikenmatrix_native::services::transport::{Artwork, MqttTransport};
+use shikenmatrix_native::services::{MqttConfig, Transport};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use url::Url;
+
+const TOPIC_PREFIX: &str = "test-shikenmatrix";
+const DISCOVERY_PREFIX: &str = "test-homeassistant";
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long to wait before concluding that nothing more arrives
+const QUIET: Duration = Duration::from_millis(500);
+
+struct Mosquitto {
+    child: Child,
+    config_path: std::path::PathBuf,
+    port: u16,
+}
+
+impl Drop for Mosquitto {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_file(&self.config_path);
+    }
+}
+
+/// Start a broker on a free loopback port
+fn start_mosquitto(name: &str) -> Mosquitto {
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let config_path = std::env::temp_dir().join(format!("shikenmatrix-{}-{}.conf", name, std::process::id()));
+    std::fs::write(&config_path, format!("listener {} 127.0.0.1\nallow_anonymous true\n", port)).unwrap();
+
+    let child = Command::new("mosquitto")
+        .arg("-c")
+        .arg(&config_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("cannot start mosquitto");
+    let broker = Mosquitto { child, config_path, port };
+
+    let deadline = Instant::now() + EVENT_TIMEOUT;
+    while std::net::TcpStream::connect(("127.0.0.1", port)).is_err() {
+        assert!(Instant::now() < deadline, "mosquitto did not come up on port {}", port);
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    broker
+}
+
+fn base_topic() -> String {
+    format!("{}/{}", TOPIC_PREFIX, device_id())
+}
+
+fn transport(broker: &Mosquitto, discovery: bool) -> MqttTransport {
+    let url = Url::parse(&format!("mqtt://127.0.0.1:{}", broker.port)).unwrap();
+    let config = MqttConfig {
+        topic_prefix: TOPIC_PREFIX.to_string(),
+        discovery,
+        discovery_prefix: DISCOVERY_PREFIX.to_string(),
+        ..Default::default()
+    };
+    MqttTransport::new(url, String::new(), config).unwrap()
+}
+
+/// Subscribe to `topics` and forward every publish; returns once the broker acknowledged
+async fn subscribe(broker: &Mosquitto, client_id: &str, topics: &[String]) -> mpsc::UnboundedReceiver<Publish> {
+    let (client, mut eventloop) = AsyncClient::new(MqttOptions::new(client_id, "127.0.0.1", broker.port), 16);
+    for topic in topics {
+        client.subscribe(topic.as_str(), QoS::AtLeastOnce).await.unwrap();
+    }
+
+    let mut acks = 0;
+    let mut early = Vec::new();
+    while acks < topics.len() {
+        match tokio::time::timeout(EVENT_TIMEOUT, eventloop.poll()).await.expect("subscription").unwrap() {
+            Event::Incoming(Packet::SubAck(_)) => acks += 1,
+            Event::Incoming(Packet::Publish(publish)) => early.push(publish),
+            _ => {}
+        }
+    }
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    for publish in early {
+        let _ = tx.send(publish);
+    }
+    tokio::spawn(async move {
+        // Keeps the client alive for as long as the subscription is polled
+        let _client = client;
+        while let Ok(event) = eventloop.poll().await {
+            if let Event::Incoming(Packet::Publish(publish)) = event {
+                if tx.send(publish).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// Everything received until nothing more arrives for `QUIET`
+async fn collect(rx: &mut mpsc::UnboundedReceiver<Publish>) -> Vec<Publish> {
+    let mut received = Vec::new();
+    while let Ok(Some(publish)) = tokio::time::timeout(QUIET, rx.recv()).await {
+        received.push(publish);
+    }
+    received
+}
+
+/// Wait for the next message on `topic`, skipping others
+async fn next_on(rx: &mut mpsc::UnboundedReceiver<Publish>, topic: &str) -> Publish {
+    let deadline = tokio::time::Instant::now() + EVENT_TIMEOUT;
+    loop {
+        let publish = tokio::time::timeout_at(deadline, rx.recv())
+            .await
+            .unwrap_or_else(|_| panic!("nothing published on {}", topic))
+            .expect("subscriber stopped");
+        if publish.topic == topic {
+            return publish;
+        }
+    }
+}
+
+fn payload(publish: &Publish) -> &str {
+    std::str::from_utf8(&publish.payload).unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs mosquitto"]
+async fn state_is_retained_and_events_are_not() {
+    let broker = start_mosquitto("mqtt-retain");
+    let base = base_topic();
+    let mut live = subscribe(&broker, "live", &[format!("{}/#", TOPIC_PREFIX)]).await;
+
+    let mut transport = transport(&broker, true);
+    transport.connect().await.unwrap();
+    transport.send_text(r#"{"type":"window_info","data":{"title":"notes.txt"}}"#.to_string()).await.unwrap();
+    transport.send_text(r#"{"type":"session_state","state":"locked"}"#.to_string()).await.unwrap();
+    transport.send_text(r#"{"type":"history_batch","events":[],"remaining":0}"#.to_string()).await.unwrap();
+
+    // Events still reach current subscribers
+    assert_eq!(payload(&next_on(&mut live, &format!("{}/session_state", base)).await), r#"{"type":"session_state","state":"locked"}"#);
+    next_on(&mut live, &format!("{}/history_batch", base)).await;
+
+    // A subscriber connecting later only gets the retained state
+    let mut late = subscribe(&broker, "late", &[format!("{}/#", TOPIC_PREFIX), format!("{}/#", DISCOVERY_PREFIX)]).await;
+    let retained = collect(&mut late).await;
+    let topic = |name: &str| retained.iter().find(|p| p.topic == format!("{}/{}", base, name));
+    assert!(retained.iter().all(|p| p.retain), "not retained: {:?}", retained);
+    assert_eq!(topic("status").map(payload), Some("online"));
+    assert_eq!(topic("window_info").map(payload), Some(r#"{"type":"window_info","data":{"title":"notes.txt"}}"#));
+    assert!(topic("session_state").is_none() && topic("history_batch").is_none(), "events were retained");
+
+    // Home Assistant discovery
+    let node_id = format!("shikenmatrix_{}", device_id());
+    let discovery: Vec<&Publish> = retained.iter().filter(|p| p.topic.starts_with(DISCOVERY_PREFIX)).collect();
+    assert_eq!(discovery.len(), 5, "{:?}", discovery.iter().map(|p| &p.topic).collect::<Vec<_>>());
+    let window = discovery.iter()
+        .find(|p| p.topic == format!("{}/sensor/{}/window/config", DISCOVERY_PREFIX, node_id))
+        .expect("window sensor");
+    let config: serde_json::Value = serde_json::from_slice(&window.payload).unwrap();
+    assert_eq!(config["state_topic"], format!("{}/window_info", base));
+    assert_eq!(config["availability_topic"], format!("{}/status", base));
+    assert_eq!(config["unique_id"], format!("{}_window", node_id));
+    assert_eq!(config["device"]["identifiers"][0], node_id);
+    assert!(discovery.iter().any(|p| p.topic == format!("{}/binary_sensor/{}/media_playing/config", DISCOVERY_PREFIX, node_id)));
+
+    // A clean close announces `offline` itself
+    transport.close().await;
+    assert_eq!(payload(&next_on(&mut live, &format!("{}/status", base)).await), "offline");
+}
+
+#[tokio::test]
+#[ignore = "needs mosquitto"]
+async fn artwork_is_published_once_per_track() {
+    let broker = start_mosquitto("mqtt-artwork");
+    let base = base_topic();
+    let mut live = subscribe(&broker, "live", &[format!("{}/artwork", base)]).await;
+
+    let mut transport = transport(&broker, false);
+    transport.connect().await.unwrap();
+    let artwork = |id: &str, data: &[u8]| Artwork {
+        content_item_identifier: id.to_string(),
+        mime_type: "image/png".to_string(),
+        width: None,
+        height: None,
+        data: data.to_vec(),
+    };
+    transport.send_artwork(artwork("track-1", b"one")).await.unwrap();
+    transport.send_artwork(artwork("track-1", b"one")).await.unwrap();
+    transport.send_artwork(artwork("track-2", b"two")).await.unwrap();
+    // Back to the first track: the retained artwork is track 2's, so it is published again
+    transport.send_artwork(artwork("track-1", b"one")).await.unwrap();
+
+    let published: Vec<Vec<u8>> = collect(&mut live).await.iter().map(|p| p.payload.to_vec()).collect();
+    assert_eq!(published, [b"one".to_vec(), b"two".to_vec(), b"one".to_vec()]);
+}
+
+#[tokio::test]
+#[ignore = "needs mosquitto"]
+async fn broker_publishes_last_will_when_the_client_vanishes() {
+    let broker = start_mosquitto("mqtt-will");
+    let status = format!("{}/status", base_topic());
+    let mut live = subscribe(&broker, "live", std::slice::from_ref(&status)).await;
+
+    let mut transport = transport(&broker, false);
+    transport.connect().await.unwrap();
+    assert_eq!(payload(&next_on(&mut live, &status).await), "online");
+
+    // Dropping without `close` tears the connection down without a DISCONNECT
+    drop(transport);
+    let will = next_on(&mut live, &status).await;
+    assert_eq!(payload(&will), "offline");
+}
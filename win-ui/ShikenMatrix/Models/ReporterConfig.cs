@@ -11,6 +11,8 @@ namespace ShikenMatrix.Models
         private string _wsUrl = string.Empty;
         private string _token = string.Empty;
         private bool _enableMediaReporting;
+        private byte _windowPrivacy = 2;
+        private byte _mediaPrivacy = 2;
 
         public bool Enabled
         {
@@ -64,6 +66,38 @@ namespace ShikenMatrix.Models
             }
         }
 
+        /// <summary>
+        /// Window privacy level: 0 = off, 1 = app only, 2 = app + title
+        /// </summary>
+        public byte WindowPrivacy
+        {
+            get => _windowPrivacy;
+            set
+            {
+                if (_windowPrivacy != value)
+                {
+                    _windowPrivacy = value;
+                    OnPropertyChanged(nameof(WindowPrivacy));
+                }
+            }
+        }
+
+        /// <summary>
+        /// Media privacy level: 0 = off, 1 = track only, 2 = track + artwork
+        /// </summary>
+        public byte MediaPrivacy
+        {
+            get => _mediaPrivacy;
+            set
+            {
+                if (_mediaPrivacy != value)
+                {
+                    _mediaPrivacy = value;
+                    OnPropertyChanged(nameof(MediaPrivacy));
+                }
+            }
+        }
+
         public event PropertyChangedEventHandler? PropertyChanged;
 
         protected void OnPropertyChanged(string propertyName)
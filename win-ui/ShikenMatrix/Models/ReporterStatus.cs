@@ -1,3 +1,4 @@
+using System;
 using System.ComponentModel;
 
 namespace ShikenMatrix.Models
@@ -10,6 +11,12 @@ namespace ShikenMatrix.Models
         private bool _isRunning;
         private bool _isConnected;
         private string? _lastError;
+        private int? _latencyMs;
+        private int? _smoothedLatencyMs;
+        private uint _reconnectAttempt;
+        private DateTimeOffset? _nextRetry;
+        private uint _pendingMessages;
+        private ulong _droppedMessages;
 
         public bool IsRunning
         {
@@ -50,6 +57,102 @@ namespace ShikenMatrix.Models
             }
         }
 
+        /// <summary>
+        /// Round-trip time of the last heartbeat in milliseconds (null if unknown)
+        /// </summary>
+        public int? LatencyMs
+        {
+            get => _latencyMs;
+            set
+            {
+                if (_latencyMs != value)
+                {
+                    _latencyMs = value;
+                    OnPropertyChanged(nameof(LatencyMs));
+                }
+            }
+        }
+
+        /// <summary>
+        /// Smoothed heartbeat round-trip time in milliseconds (null if unknown)
+        /// </summary>
+        public int? SmoothedLatencyMs
+        {
+            get => _smoothedLatencyMs;
+            set
+            {
+                if (_smoothedLatencyMs != value)
+                {
+                    _smoothedLatencyMs = value;
+                    OnPropertyChanged(nameof(SmoothedLatencyMs));
+                }
+            }
+        }
+
+        /// <summary>
+        /// Failed connection attempts since the last successful connection
+        /// </summary>
+        public uint ReconnectAttempt
+        {
+            get => _reconnectAttempt;
+            set
+            {
+                if (_reconnectAttempt != value)
+                {
+                    _reconnectAttempt = value;
+                    OnPropertyChanged(nameof(ReconnectAttempt));
+                }
+            }
+        }
+
+        /// <summary>
+        /// When the next connection attempt starts (null while connected or connecting)
+        /// </summary>
+        public DateTimeOffset? NextRetry
+        {
+            get => _nextRetry;
+            set
+            {
+                if (_nextRetry != value)
+                {
+                    _nextRetry = value;
+                    OnPropertyChanged(nameof(NextRetry));
+                }
+            }
+        }
+
+        /// <summary>
+        /// Messages waiting to be sent
+        /// </summary>
+        public uint PendingMessages
+        {
+            get => _pendingMessages;
+            set
+            {
+                if (_pendingMessages != value)
+                {
+                    _pendingMessages = value;
+                    OnPropertyChanged(nameof(PendingMessages));
+                }
+            }
+        }
+
+        /// <summary>
+        /// Messages dropped because the outbound queue limits were exceeded
+        /// </summary>
+        public ulong DroppedMessages
+        {
+            get => _droppedMessages;
+            set
+            {
+                if (_droppedMessages != value)
+                {
+                    _droppedMessages = value;
+                    OnPropertyChanged(nameof(DroppedMessages));
+                }
+            }
+        }
+
         public event PropertyChangedEventHandler? PropertyChanged;
 
         protected void OnPropertyChanged(string propertyName)
@@ -28,8 +28,10 @@ namespace ShikenMatrix.Native
         public IntPtr Token;      // char* (owned by Rust)
         [MarshalAs(UnmanagedType.U1)]
         public bool EnableMediaReporting;
-        // 7 bytes padding at end
-        private byte _pad8, _pad9, _pad10, _pad11, _pad12, _pad13, _pad14;
+        public byte WindowPrivacy;  // 0 = off, 1 = app only, 2 = app + title
+        public byte MediaPrivacy;   // 0 = off, 1 = track only, 2 = track + artwork
+        // 5 bytes padding at end
+        private byte _pad8, _pad9, _pad10, _pad11, _pad12;
     }
 
     /// <summary>
@@ -54,6 +56,12 @@ namespace ShikenMatrix.Native
         // 6 bytes padding for alignment before pointer
         private byte _pad1, _pad2, _pad3, _pad4, _pad5, _pad6;
         public IntPtr LastError; // char* (owned by Rust, null if no error)
+        public int LatencyMs;         // last heartbeat round-trip time, -1 if unknown
+        public int SmoothedLatencyMs; // smoothed round-trip time, -1 if unknown
+        public uint ReconnectAttempt; // failed attempts since the last successful connection
+        public long NextRetryMs;      // Unix time (ms) of the next attempt, -1 if none scheduled
+        public uint PendingMessages;  // messages waiting to be sent
+        public ulong DroppedMessages; // messages dropped by the outbound queue limits
     }
 
     /// <summary>
@@ -186,6 +194,33 @@ namespace ShikenMatrix.Native
         [return: MarshalAs(UnmanagedType.U1)]
         internal static extern bool SmReporterIsRunning();
 
+        /// <summary>
+        /// Pause reporting for the given number of seconds (0 = until resumed)
+        /// </summary>
+        [DllImport(DllName, EntryPoint = "sm_reporter_pause", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        internal static extern bool SmReporterPause(ulong durationSecs);
+
+        /// <summary>
+        /// Resume reporting after SmReporterPause
+        /// </summary>
+        [DllImport(DllName, EntryPoint = "sm_reporter_resume", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        internal static extern bool SmReporterResume();
+
+        /// <summary>
+        /// Get the active pause: -1 = not paused, 0 = until resumed, otherwise Unix time (ms) it ends
+        /// </summary>
+        [DllImport(DllName, EntryPoint = "sm_reporter_get_paused_until", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern long SmReporterGetPausedUntil();
+
+        /// <summary>
+        /// Hint that the network changed; skips the remaining reconnect backoff
+        /// </summary>
+        [DllImport(DllName, EntryPoint = "sm_reporter_network_changed", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        [return: MarshalAs(UnmanagedType.U1)]
+        internal static extern bool SmReporterNetworkChanged();
+
         #endregion
 
         #region Version Methods
@@ -85,7 +85,9 @@ namespace ShikenMatrix.Services
                     Enabled = config.Enabled,
                     WsUrl = MarshalHelper.PtrToStringUTF8(config.WsUrl) ?? string.Empty,
                     Token = MarshalHelper.PtrToStringUTF8(config.Token) ?? string.Empty,
-                    EnableMediaReporting = config.EnableMediaReporting
+                    EnableMediaReporting = config.EnableMediaReporting,
+                    WindowPrivacy = config.WindowPrivacy,
+                    MediaPrivacy = config.MediaPrivacy
                 };
 
                 NativeMethods.SmConfigFree(configPtr);
@@ -114,7 +116,9 @@ namespace ShikenMatrix.Services
                     Enabled = config.Enabled,
                     WsUrl = wsUrlPtr,
                     Token = tokenPtr,
-                    EnableMediaReporting = config.EnableMediaReporting
+                    EnableMediaReporting = config.EnableMediaReporting,
+                    WindowPrivacy = config.WindowPrivacy,
+                    MediaPrivacy = config.MediaPrivacy
                 };
 
                 IntPtr configPtr = Marshal.AllocHGlobal(Marshal.SizeOf(nativeConfig));
@@ -165,7 +169,9 @@ namespace ShikenMatrix.Services
                     Enabled = config.Enabled,
                     WsUrl = wsUrlPtr,
                     Token = tokenPtr,
-                    EnableMediaReporting = config.EnableMediaReporting
+                    EnableMediaReporting = config.EnableMediaReporting,
+                    WindowPrivacy = config.WindowPrivacy,
+                    MediaPrivacy = config.MediaPrivacy
                 };
 
                 IntPtr configPtr = Marshal.AllocHGlobal(Marshal.SizeOf(nativeConfig));
@@ -211,6 +217,41 @@ namespace ShikenMatrix.Services
             return NativeMethods.SmReporterIsRunning();
         }
 
+        /// <summary>
+        /// Pause reporting without disconnecting; null pauses until ResumeReporter is called
+        /// </summary>
+        public bool PauseReporter(TimeSpan? duration)
+        {
+            ulong seconds = duration.HasValue ? (ulong)Math.Max(1, duration.Value.TotalSeconds) : 0;
+            return NativeMethods.SmReporterPause(seconds);
+        }
+
+        /// <summary>
+        /// Resume reporting after PauseReporter
+        /// </summary>
+        public bool ResumeReporter()
+        {
+            return NativeMethods.SmReporterResume();
+        }
+
+        /// <summary>
+        /// Whether reporting is paused, and until when (null end = until resumed)
+        /// </summary>
+        public bool IsPaused(out DateTimeOffset? until)
+        {
+            long pausedUntil = NativeMethods.SmReporterGetPausedUntil();
+            until = pausedUntil > 0 ? DateTimeOffset.FromUnixTimeMilliseconds(pausedUntil) : null;
+            return pausedUntil >= 0;
+        }
+
+        /// <summary>
+        /// Hint that the network changed so a pending reconnect is attempted right away
+        /// </summary>
+        public bool NotifyNetworkChanged()
+        {
+            return NativeMethods.SmReporterNetworkChanged();
+        }
+
         /// <summary>
         /// Get the current status of the reporter
         /// </summary>
@@ -225,7 +266,13 @@ namespace ShikenMatrix.Services
             {
                 IsRunning = status.IsRunning,
                 IsConnected = status.IsConnected,
-                LastError = MarshalHelper.PtrToStringUTF8(status.LastError)
+                LastError = MarshalHelper.PtrToStringUTF8(status.LastError),
+                LatencyMs = status.LatencyMs >= 0 ? status.LatencyMs : null,
+                SmoothedLatencyMs = status.SmoothedLatencyMs >= 0 ? status.SmoothedLatencyMs : null,
+                ReconnectAttempt = status.ReconnectAttempt,
+                NextRetry = status.NextRetryMs >= 0 ? DateTimeOffset.FromUnixTimeMilliseconds(status.NextRetryMs) : null,
+                PendingMessages = status.PendingMessages,
+                DroppedMessages = status.DroppedMessages
             };
         }
 
@@ -1,6 +1,7 @@
 using System;
 using System.Collections.ObjectModel;
 using System.ComponentModel;
+using System.Net.NetworkInformation;
 using System.Threading.Tasks;
 using Microsoft.UI.Dispatching;
 using ShikenMatrix.Models;
@@ -73,6 +74,9 @@ namespace ShikenMatrix.ViewModels
             _statusTimer.Interval = TimeSpan.FromSeconds(1);
             _statusTimer.Tick += OnStatusTimerTick;
 
+            // Reconnect right away when the network comes back instead of waiting out the backoff
+            NetworkChange.NetworkAddressChanged += OnNetworkAddressChanged;
+
             // Load initial config
             LoadConfig();
             
@@ -504,6 +508,14 @@ namespace ShikenMatrix.ViewModels
             }
         }
 
+        private void OnNetworkAddressChanged(object? sender, EventArgs e)
+        {
+            if (IsRunning)
+            {
+                _bridge.NotifyNetworkChanged();
+            }
+        }
+
         #endregion
 
         #region Lifecycle
@@ -535,6 +547,7 @@ namespace ShikenMatrix.ViewModels
                 _statusTimer.Stop();
                 _statusTimer.Tick -= OnStatusTimerTick;
             }
+            NetworkChange.NetworkAddressChanged -= OnNetworkAddressChanged;
 
             // Clear callbacks to prevent memory leaks
             _bridge.ClearCallbacks();